@@ -0,0 +1,11 @@
+use std::env;
+
+fn main() {
+    let target = env::var("TARGET").unwrap();
+
+    // ARMv6-M doesn't have CAS instructions so `Stats` is not available on it
+    if target.starts_with("thumbv6m-") {
+        println!("cargo:rustc-cfg=armv6m");
+    }
+    println!("cargo:rustc-check-cfg=cfg(armv6m)");
+}
@@ -0,0 +1,82 @@
+//! Arena usage of a `Vec` that grows one `push` at a time
+//!
+//! Compares `BumpAlloc` against the same allocator with the default `GlobalAlloc::realloc`, which
+//! always allocates a new block and copies the old one.
+
+use core::alloc::{GlobalAlloc, Layout};
+use std::time::Instant;
+
+use alloc_many::{allocator, oom, Alloc};
+use alloc_many_bump::{consts, BumpAlloc};
+use alloc_many_collections::vec::Vec;
+
+#[oom]
+fn oom(_: Layout) -> ! {
+    panic!("OOM")
+}
+
+/// `BumpAlloc` without in-place `realloc`
+struct Copying<A>(A);
+
+unsafe impl<A> GlobalAlloc for Copying<A>
+where
+    A: GlobalAlloc,
+{
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        self.0.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        self.0.dealloc(ptr, layout)
+    }
+}
+
+#[allocator]
+static A: BumpAlloc<consts::U65536> = BumpAlloc::new();
+
+#[allocator]
+static B: Copying<BumpAlloc<consts::U65536>> = Copying(BumpAlloc::new());
+
+// pushes `n` elements and reports the arena usage and the best time out of several runs
+fn push<A>(n: usize, arena: &BumpAlloc<consts::U65536>) -> (usize, u128)
+where
+    A: Alloc,
+{
+    let mut best = u128::max_value();
+    let mut usage = 0;
+    for _ in 0..10 {
+        let start = Instant::now();
+        let mut v: Vec<A, u32> = Vec::new();
+        for i in 0..n {
+            v.push(i as u32);
+        }
+        best = best.min(start.elapsed().as_nanos());
+
+        usage = arena.used();
+        drop(v);
+        unsafe { arena.reset() }
+    }
+
+    (usage, best)
+}
+
+fn main() {
+    println!(
+        "{:>6} {:>8} {:>14} {:>14} {:>14} {:>14}",
+        "len", "bytes", "in-place (B)", "copying (B)", "in-place (ns)", "copying (ns)"
+    );
+
+    for &n in &[16, 64, 256, 1024, 4096] {
+        let (a, ta) = push::<A>(n, &A);
+        let (b, tb) = push::<B>(n, &B.0);
+        println!(
+            "{:>6} {:>8} {:>14} {:>14} {:>14} {:>14}",
+            n,
+            n * 4,
+            a,
+            b,
+            ta,
+            tb
+        );
+    }
+}
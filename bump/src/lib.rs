@@ -1,84 +1,621 @@
-//! A lock-free bump pointer allocator that never frees memory
+//! A lock-free bump pointer allocator
+//!
+//! `dealloc` only frees memory if the block is the last one that was allocated, and `realloc`
+//! grows or shrinks that block in place. The allocator can also be rewound to reclaim all the
+//! memory allocated after some point: see `BumpAlloc_::mark`, `BumpAlloc_::reset_to` and
+//! `Scoped::with_scope`.
 //!
 //! # Minimum Supported Rust Version (MSRV)
 //!
-//! This crate is guaranteed to compile on stable Rust 1.36 and up. It might compile on older
+//! This crate is guaranteed to compile on stable Rust 1.61 and up. It might compile on older
 //! versions but that may change in any new patch release.
+//!
+//! 1.61 is the first release that accepts trait bounds on `const fn`s; `BumpAlloc_::new` needs
+//! one to pick the index type of the allocator from its capacity.
 
 #![deny(missing_docs)]
 #![deny(rust_2018_compatibility)]
 #![deny(rust_2018_idioms)]
 #![deny(warnings)]
-#![feature(maybe_uninit)]
 #![no_std]
 
 use core::{
     alloc::{GlobalAlloc, Layout},
-    convert::TryFrom,
+    marker::PhantomData,
     mem::MaybeUninit,
+    ops::Deref,
     ptr,
-    sync::atomic::{AtomicU16, Ordering},
+    sync::atomic::{AtomicU16, AtomicU32, AtomicUsize, Ordering},
 };
 
+use alloc_many::{Alloc, AllocInfo, Owns, Usage};
 pub use generic_array::typenum::consts;
-use generic_array::{ArrayLength, GenericArray};
+use generic_array::{
+    typenum::{Cmp, Compare, Equal, Greater, Less, U4294967296, U65536},
+    ArrayLength, GenericArray,
+};
 
 /// Lock-free bump pointer allocator
-pub type BumpAlloc<N> = BumpAlloc_<GenericArray<u8, N>>;
+pub type BumpAlloc<N> = BumpAlloc_<GenericArray<u8, N>, <N as Capacity>::Index>;
 
 /// Lock-free bump pointer allocator (stable `const-fn` workaround)
-pub struct BumpAlloc_<A> {
-    // `u16` ought to be big enough for everyone
-    index: AtomicU16,
+pub struct BumpAlloc_<A, I> {
+    index: I,
+    // highest value `index` has ever reached; not affected by rewinds
+    peak: I,
+    // number of scopes that are open; see `Scoped::with_scope`
+    scopes: AtomicUsize,
+    // number of allocations, and reallocations, made on the allocator itself rather than on a
+    // `Scope`
+    unscoped: AtomicUsize,
+    // number of those that are in progress
+    pending: AtomicUsize,
     memory: MaybeUninit<A>,
 }
 
-impl<A> BumpAlloc_<A> {
+/// A position in the memory of a bump pointer allocator; see `BumpAlloc_::mark`
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Checkpoint {
+    index: usize,
+}
+
+impl<A, I> BumpAlloc_<A, I>
+where
+    I: Index,
+{
     /// Creates a bump pointer allocator of capacity `N`
+    // NOTE one `new` per index type would avoid the `I: Index` bound but then `BumpAlloc::new()`
+    // would be ambiguous because `N`, and thus the index type, is not known when the method is
+    // resolved
     pub const fn new() -> Self {
         Self {
-            index: AtomicU16::new(0),
+            index: I::ZERO,
+            peak: I::ZERO,
+            scopes: AtomicUsize::new(0),
+            unscoped: AtomicUsize::new(0),
+            pending: AtomicUsize::new(0),
             memory: MaybeUninit::uninit(),
         }
     }
+
+    /// Returns the current position of the allocator
+    pub fn mark(&self) -> Checkpoint {
+        Checkpoint {
+            index: self.index.load(Ordering::SeqCst),
+        }
+    }
+
+    /// Rewinds the allocator to `checkpoint`, reclaiming all the memory allocated after it
+    ///
+    /// # Safety
+    ///
+    /// `checkpoint` must have been created by this allocator and every block allocated after
+    /// `checkpoint` was created, by any thread, must have been deallocated. Otherwise the
+    /// memory of those blocks will be handed out again by future allocations.
+    pub unsafe fn reset_to(&self, checkpoint: Checkpoint) {
+        self.index.store(checkpoint.index, Ordering::SeqCst)
+    }
+
+    /// Rewinds the allocator to its initial state, reclaiming all its memory
+    ///
+    /// # Safety
+    ///
+    /// Every block allocated on this allocator must have been deallocated. See `reset_to`.
+    pub unsafe fn reset(&self) {
+        self.reset_to(Checkpoint { index: 0 })
+    }
+
+    // runs an allocation, or reallocation, made on the allocator itself rather than on a `Scope`
+    fn direct<T>(&self, f: impl FnOnce() -> T) -> T {
+        // NOTE(Ordering) both counters are incremented before the block is claimed, and all these
+        // operations are `SeqCst`, so `Frame` can't observe the new `index` without also observing
+        // the new counts
+        self.pending.fetch_add(1, Ordering::SeqCst);
+        self.unscoped.fetch_add(1, Ordering::SeqCst);
+        let t = f();
+        self.pending.fetch_sub(1, Ordering::SeqCst);
+        t
+    }
 }
 
-unsafe impl<N> GlobalAlloc for BumpAlloc_<GenericArray<u8, N>>
+// an open scope; rewinds the allocator when dropped; see `Scoped::with_scope`
+struct Frame<'a, A, I>
 where
-    N: ArrayLength<u8>,
+    I: Index,
 {
-    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
-        let align = if let Ok(align) = u16::try_from(layout.align()) {
-            align
-        } else {
-            return ptr::null_mut();
-        };
-        let size = if let Ok(size) = u16::try_from(layout.size()) {
-            size
-        } else {
-            return ptr::null_mut();
-        };
-        let len = N::U16;
+    alloc: &'a BumpAlloc_<A, I>,
+    checkpoint: Checkpoint,
+    // value of `alloc.unscoped` when the scope was opened
+    unscoped: usize,
+    // whether no allocation made on the allocator itself was in progress when the scope was opened
+    clean: bool,
+}
+
+impl<'a, A, I> Frame<'a, A, I>
+where
+    I: Index,
+{
+    fn open(alloc: &'a BumpAlloc_<A, I>) -> Self {
+        alloc.scopes.fetch_add(1, Ordering::SeqCst);
+
+        // NOTE(Ordering) `pending` must be read after `unscoped` and before the mark is taken;
+        // otherwise an allocation that was counted before the scope was opened could claim a block
+        // past the checkpoint without `drop` noticing
+        let unscoped = alloc.unscoped.load(Ordering::SeqCst);
+        let clean = alloc.pending.load(Ordering::SeqCst) == 0;
+
+        Frame {
+            alloc,
+            checkpoint: alloc.mark(),
+            unscoped,
+            clean,
+        }
+    }
+}
+
+impl<'a, A, I> Drop for Frame<'a, A, I>
+where
+    I: Index,
+{
+    fn drop(&mut self) {
+        let alloc = self.alloc;
+
+        // the blocks allocated on this scope can't outlive it, and neither can the ones allocated
+        // on scopes that have been closed, so the allocator can be rewound to the checkpoint if no
+        // other scope is open and no block was allocated on the allocator itself in the meantime
+        if self.clean {
+            loop {
+                // NOTE the index must be read before the counters; see `direct`
+                let index = alloc.index.load(Ordering::SeqCst);
+
+                if index <= self.checkpoint.index
+                    || alloc.scopes.load(Ordering::SeqCst) != 1
+                    || alloc.unscoped.load(Ordering::SeqCst) != self.unscoped
+                    || alloc
+                        .index
+                        .compare_exchange_weak(
+                            index,
+                            self.checkpoint.index,
+                            Ordering::SeqCst,
+                            Ordering::SeqCst,
+                        )
+                        .is_ok()
+                {
+                    break;
+                }
+            }
+        }
+
+        alloc.scopes.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl<N> BumpAlloc_<GenericArray<u8, N>, N::Index>
+where
+    N: Capacity,
+{
+    /// Returns the capacity of the allocator, in bytes
+    pub fn capacity(&self) -> usize {
+        N::USIZE
+    }
+
+    /// Returns the number of bytes that have been handed out, including alignment padding
+    pub fn used(&self) -> usize {
+        self.index.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of bytes that are still available
+    ///
+    /// Alignment padding may prevent an allocation of exactly this size from succeeding.
+    pub fn remaining(&self) -> usize {
+        N::USIZE - self.used()
+    }
+
+    /// Returns the highest number of bytes that have been in use at any point
+    ///
+    /// Unlike `used`, this value is not lowered by `dealloc` or by rewinding the allocator so it
+    /// can be used to size the capacity of the allocator.
+    pub fn high_water_mark(&self) -> usize {
+        self.peak.load(Ordering::Relaxed)
+    }
+
+    /// Returns `true` if `ptr` points into the memory of this allocator
+    pub fn contains(&self, ptr: *const u8) -> bool {
+        let start = self.memory.as_ptr() as usize;
+        let ptr = ptr as usize;
+
+        start <= ptr && ptr - start < N::USIZE
+    }
+
+    // `GlobalAlloc::alloc` minus the bookkeeping of scopes
+    unsafe fn claim(&self, layout: Layout) -> *mut u8 {
+        let align = layout.align();
+        let size = layout.size();
+        let len = N::USIZE;
 
-        // XXX(Ordering) TSAN seems to be happy with `Relaxed` ordering though I'm a bit skeptical
         loop {
-            let index = self.index.load(Ordering::Relaxed);
+            let index = self.index.load(Ordering::SeqCst);
+
+            // NOTE this addition can't overflow because `index <= len` and the memory of the
+            // allocator fits in the address space
+            let res = (self.memory.as_ptr() as usize + index) % align;
+            let start = if res == 0 {
+                Some(index)
+            } else {
+                index.checked_add(align - res)
+            };
+            let end = start.and_then(|start| start.checked_add(size));
 
-            let res =
-                ((self.memory.as_ptr() as usize + usize::from(index)) % usize::from(align)) as u16;
-            let start = if res == 0 { index } else { index + align - res };
+            let (start, end) = match (start, end) {
+                (Some(start), Some(end)) if end <= len => (start, end),
+                _ => break ptr::null_mut(),
+            };
 
-            if start + size > len {
-                break ptr::null_mut();
-            } else if self
+            if self
                 .index
-                .compare_exchange_weak(index, start + size, Ordering::Relaxed, Ordering::Relaxed)
+                .compare_exchange_weak(index, end, Ordering::SeqCst, Ordering::SeqCst)
                 .is_ok()
             {
-                break (self.memory.as_ptr() as *mut u8).add(usize::from(start));
+                self.peak.fetch_max(end, Ordering::Relaxed);
+
+                break (self.memory.as_ptr() as *mut u8).add(start);
+            }
+        }
+    }
+
+    // `GlobalAlloc::realloc` minus the bookkeeping of scopes
+    unsafe fn resize(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let start = ptr as usize - self.memory.as_ptr() as usize;
+        let end = start + layout.size();
+
+        // grow or shrink in place if this is the last block that was allocated
+        if let Some(new_end) = start.checked_add(new_size) {
+            if new_end <= N::USIZE
+                && self
+                    .index
+                    .compare_exchange(end, new_end, Ordering::SeqCst, Ordering::SeqCst)
+                    .is_ok()
+            {
+                self.peak.fetch_max(new_end, Ordering::Relaxed);
+
+                return ptr;
+            }
+        }
+
+        if new_size <= layout.size() {
+            // shrinking in place always succeeds; the tail of the block is not reclaimed
+            ptr
+        } else {
+            let new_ptr = self.claim(Layout::from_size_align_unchecked(new_size, layout.align()));
+
+            if !new_ptr.is_null() {
+                ptr::copy_nonoverlapping(ptr, new_ptr, layout.size());
+                self.dealloc(ptr, layout);
             }
+
+            new_ptr
         }
     }
+}
+
+unsafe impl<N> GlobalAlloc for BumpAlloc_<GenericArray<u8, N>, N::Index>
+where
+    N: Capacity,
+{
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        self.direct(|| self.claim(layout))
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        // reclaim the memory if this is the last block that was allocated
+        let start = ptr as usize - self.memory.as_ptr() as usize;
+        let end = start + layout.size();
+        let _ = self
+            .index
+            .compare_exchange(end, start, Ordering::SeqCst, Ordering::SeqCst);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        self.direct(|| self.resize(ptr, layout, new_size))
+    }
+}
+
+unsafe impl<N> Owns for BumpAlloc_<GenericArray<u8, N>, N::Index>
+where
+    N: Capacity,
+{
+    fn owns(&self, ptr: *mut u8, layout: Layout) -> bool {
+        let start = self.memory.as_ptr() as usize;
+        let end = start + N::USIZE;
+        let ptr = ptr as usize;
+
+        start <= ptr && ptr <= end && layout.size() <= end - ptr
+    }
+}
+
+impl<N> Usage for BumpAlloc_<GenericArray<u8, N>, N::Index>
+where
+    N: Capacity,
+{
+    fn capacity(&self) -> usize {
+        BumpAlloc_::capacity(self)
+    }
+
+    fn used(&self) -> usize {
+        BumpAlloc_::used(self)
+    }
+}
+
+/// Capacities, in bytes, supported by `BumpAlloc`
+///
+/// The allocator uses the narrowest atomic integer that can index its memory: `AtomicU16` for
+/// capacities below 64 KiB, `AtomicU32` for capacities below 4 GiB and `AtomicUsize` for larger
+/// capacities.
+pub trait Capacity: ArrayLength<u8> {
+    /// The atomic integer used to index the memory of the allocator
+    type Index: Index;
+}
+
+impl<N> Capacity for N
+where
+    N: ArrayLength<u8> + Cmp<U65536>,
+    Compare<N, U65536>: Narrow<N>,
+{
+    type Index = <Compare<N, U65536> as Narrow<N>>::Index;
+}
+
+/// Atomic integers used to index the memory of a `BumpAlloc`
+///
+/// This trait is sealed and can't be implemented outside this crate.
+pub trait Index: sealed::Sealed {
+    #[doc(hidden)]
+    const ZERO: Self;
+
+    #[doc(hidden)]
+    fn load(&self, order: Ordering) -> usize;
+
+    #[doc(hidden)]
+    fn store(&self, value: usize, order: Ordering);
+
+    #[doc(hidden)]
+    fn fetch_max(&self, value: usize, order: Ordering) -> usize;
+
+    #[doc(hidden)]
+    fn compare_exchange(
+        &self,
+        current: usize,
+        new: usize,
+        success: Ordering,
+        failure: Ordering,
+    ) -> Result<usize, usize>;
+
+    #[doc(hidden)]
+    fn compare_exchange_weak(
+        &self,
+        current: usize,
+        new: usize,
+        success: Ordering,
+        failure: Ordering,
+    ) -> Result<usize, usize>;
+}
+
+macro_rules! index {
+    ($($atomic:ident: $uxx:ident,)+) => {
+        $(
+            impl sealed::Sealed for $atomic {}
+
+            impl Index for $atomic {
+                const ZERO: Self = $atomic::new(0);
+
+                fn load(&self, order: Ordering) -> usize {
+                    $atomic::load(self, order) as usize
+                }
+
+                // NOTE `value` never exceeds the capacity of the allocator, which fits in `$uxx`
+                fn store(&self, value: usize, order: Ordering) {
+                    $atomic::store(self, value as $uxx, order)
+                }
+
+                // NOTE `value` never exceeds the capacity of the allocator, which fits in `$uxx`
+                fn fetch_max(&self, value: usize, order: Ordering) -> usize {
+                    $atomic::fetch_max(self, value as $uxx, order) as usize
+                }
+
+                // NOTE `current` and `new` never exceed the capacity of the allocator, which fits
+                // in `$uxx`
+                fn compare_exchange(
+                    &self,
+                    current: usize,
+                    new: usize,
+                    success: Ordering,
+                    failure: Ordering,
+                ) -> Result<usize, usize> {
+                    $atomic::compare_exchange(self, current as $uxx, new as $uxx, success, failure)
+                        .map(|x| x as usize)
+                        .map_err(|x| x as usize)
+                }
+
+                fn compare_exchange_weak(
+                    &self,
+                    current: usize,
+                    new: usize,
+                    success: Ordering,
+                    failure: Ordering,
+                ) -> Result<usize, usize> {
+                    $atomic::compare_exchange_weak(
+                        self,
+                        current as $uxx,
+                        new as $uxx,
+                        success,
+                        failure,
+                    )
+                    .map(|x| x as usize)
+                    .map_err(|x| x as usize)
+                }
+            }
+        )+
+    };
+}
+
+index! {
+    AtomicU16: u16,
+    AtomicU32: u32,
+    AtomicUsize: usize,
+}
+
+// picks the index type of capacity `N` from the result of comparing `N` to 64 Ki
+#[doc(hidden)]
+pub trait Narrow<N> {
+    type Index: Index;
+}
+
+impl<N> Narrow<N> for Less {
+    type Index = AtomicU16;
+}
+
+impl<N> Narrow<N> for Equal
+where
+    N: Cmp<U4294967296>,
+    Compare<N, U4294967296>: Wide,
+{
+    type Index = <Compare<N, U4294967296> as Wide>::Index;
+}
+
+impl<N> Narrow<N> for Greater
+where
+    N: Cmp<U4294967296>,
+    Compare<N, U4294967296>: Wide,
+{
+    type Index = <Compare<N, U4294967296> as Wide>::Index;
+}
+
+// picks the index type of capacity `N` (>= 64 Ki) from the result of comparing `N` to 4 Gi
+#[doc(hidden)]
+pub trait Wide {
+    type Index: Index;
+}
+
+impl Wide for Less {
+    type Index = AtomicU32;
+}
+
+impl Wide for Equal {
+    type Index = AtomicUsize;
+}
+
+impl Wide for Greater {
+    type Index = AtomicUsize;
+}
+
+/// Scoped allocations on bump pointer allocator singletons
+///
+/// This trait is implemented for all the singletons created with `#[allocator]` from a
+/// `BumpAlloc`.
+pub trait Scoped: Alloc + Deref + Sized {
+    /// Runs `f` and then rewinds the allocator to where it was before `f` ran, if possible
+    ///
+    /// `f` receives the singleton `Scope<'s, Self>`, which allocates on `Self`. Collections
+    /// instantiated on it can't escape `f` because `Scope` is tied to the lifetime of the scope:
+    ///
+    /// ``` ignore
+    /// #[allocator]
+    /// static A: BumpAlloc<consts::U1024> = BumpAlloc::new();
+    ///
+    /// fn process<S>(_: S) where S: Alloc {
+    ///     let frame: Vec<S, u8> = Vec::with_capacity(512);
+    ///     // ..
+    /// }
+    ///
+    /// loop {
+    ///     A.with_scope(|scope| process(scope));
+    /// }
+    /// ```
+    ///
+    /// Blocks allocated before the scope was opened are left untouched. The memory allocated
+    /// while `f` ran is not reclaimed if, in the meantime, a block was allocated on `Self` rather
+    /// than on the scope, or if another scope on the same allocator, e.g. an enclosing one or one
+    /// opened by another thread, is still open.
+    fn with_scope<F, R>(&self, f: F) -> R
+    where
+        F: for<'s> FnOnce(Scope<'s, Self>) -> R;
+}
+
+impl<S, N> Scoped for S
+where
+    S: Alloc + Default + Deref<Target = BumpAlloc<N>>,
+    N: Capacity,
+{
+    fn with_scope<F, R>(&self, f: F) -> R
+    where
+        F: for<'s> FnOnce(Scope<'s, Self>) -> R,
+    {
+        // NOTE the frame is dropped, rewinding the allocator, even if `f` panics
+        let _frame = Frame::open(&**self);
+
+        f(Scope {
+            _scope: PhantomData,
+            _singleton: PhantomData,
+        })
+    }
+}
+
+/// An allocator singleton whose allocations are tied to the lifetime `'s`; see `Scoped`
+pub struct Scope<'s, S> {
+    // NOTE invariant in `'s`
+    _scope: PhantomData<fn(&'s ()) -> &'s ()>,
+    _singleton: PhantomData<S>,
+}
+
+impl<'s, S> Clone for Scope<'s, S> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'s, S> Copy for Scope<'s, S> {}
+
+// NOTE allocations made on a scope skip the bookkeeping of `GlobalAlloc`; that's how `Frame` tells
+// them apart from the allocations made on the singleton
+unsafe impl<'s, S, N> Alloc for Scope<'s, S>
+where
+    S: Alloc + Default + Deref<Target = BumpAlloc<N>>,
+    N: Capacity,
+{
+    #[inline(always)]
+    unsafe fn alloc(layout: Layout) -> *mut u8 {
+        S::default().claim(layout)
+    }
+
+    #[inline(always)]
+    unsafe fn dealloc(ptr: *mut u8, layout: Layout) {
+        S::dealloc(ptr, layout)
+    }
+
+    #[inline(always)]
+    unsafe fn alloc_zeroed(layout: Layout) -> *mut u8 {
+        let ptr = Self::alloc(layout);
+
+        if !ptr.is_null() {
+            ptr::write_bytes(ptr, 0, layout.size());
+        }
+
+        ptr
+    }
+
+    #[inline(always)]
+    unsafe fn realloc(ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        S::default().resize(ptr, layout, new_size)
+    }
+
+    fn oom(layout: Layout) -> ! {
+        S::oom(layout)
+    }
+
+    fn info() -> AllocInfo {
+        S::info()
+    }
+}
 
-    unsafe fn dealloc(&self, _: *mut u8, _: Layout) {}
+mod sealed {
+    pub trait Sealed {}
 }
@@ -0,0 +1,125 @@
+use core::alloc::{GlobalAlloc, Layout};
+
+use alloc_many::Owns;
+use alloc_many_bump::{consts, BumpAlloc, Capacity};
+use generic_array::typenum::Sub1;
+
+const SIZES: &[usize] = &[
+    0,
+    1,
+    2,
+    3,
+    7,
+    8,
+    9,
+    31,
+    32,
+    33,
+    63,
+    64,
+    65,
+    1023,
+    65534,
+    65535,
+    65536,
+    1 << 31,
+    usize::max_value() >> 1,
+];
+
+const ALIGNS: &[usize] = &[1, 2, 4, 8, 16, 32, 64, 128, 4096, 1 << 15, 1 << 16, 1 << 20];
+
+// all the valid layouts that can be built from `SIZES` and `ALIGNS`
+fn layouts() -> impl Iterator<Item = Layout> {
+    SIZES.iter().flat_map(|&size| {
+        ALIGNS
+            .iter()
+            .filter_map(move |&align| Layout::from_size_align(size, align).ok())
+    })
+}
+
+// a deterministic stream of layouts (xorshift)
+fn random_layouts(mut seed: u32, max_size: usize) -> impl Iterator<Item = Layout> {
+    (0..1_000).map(move |_| {
+        seed ^= seed << 13;
+        seed ^= seed >> 17;
+        seed ^= seed << 5;
+
+        let size = seed as usize % (max_size + 1);
+        let align = 1 << ((seed >> 24) % 8);
+        Layout::from_size_align(size, align).unwrap()
+    })
+}
+
+// drives `alloc` with `layouts` and checks that the returned blocks are aligned, don't leave the
+// arena and don't overlap each other
+fn check<N, I>(alloc: &BumpAlloc<N>, layouts: I)
+where
+    N: Capacity,
+    I: IntoIterator<Item = Layout>,
+{
+    let mut blocks: Vec<(usize, usize)> = vec![];
+
+    for layout in layouts {
+        let used = alloc.used();
+        let ptr = unsafe { alloc.alloc(layout) };
+
+        if ptr.is_null() {
+            assert_eq!(alloc.used(), used, "{:?}", layout);
+            continue;
+        }
+
+        let start = ptr as usize;
+        let end = start + layout.size();
+        assert_eq!(start % layout.align(), 0, "{:?}", layout);
+        assert!(alloc.owns(ptr, layout), "{:?}", layout);
+        for &(s, e) in &blocks {
+            assert!(end <= s || e <= start, "{:?}", layout);
+        }
+
+        blocks.push((start, end));
+        assert!(alloc.used() <= N::USIZE);
+    }
+}
+
+#[test]
+fn single() {
+    for layout in layouts() {
+        let alloc = BumpAlloc::<consts::U64>::new();
+        check(&alloc, Some(layout));
+    }
+}
+
+#[test]
+fn pairs() {
+    for first in layouts() {
+        for second in layouts() {
+            let alloc = BumpAlloc::<consts::U128>::new();
+            check(&alloc, vec![first, second, first]);
+        }
+    }
+}
+
+#[test]
+fn near_the_end() {
+    // the largest arena that uses a 16-bit index
+    let alloc: Box<BumpAlloc<Sub1<consts::U65536>>> = Box::new(BumpAlloc::new());
+
+    unsafe {
+        assert!(!alloc
+            .alloc(Layout::from_size_align(65533, 1).unwrap())
+            .is_null());
+    }
+
+    check(&*alloc, layouts());
+}
+
+#[test]
+fn random() {
+    for seed in 1..=32 {
+        let alloc = BumpAlloc::<consts::U1024>::new();
+        check(&alloc, random_layouts(seed, 64));
+
+        let alloc: Box<BumpAlloc<Sub1<consts::U65536>>> = Box::new(BumpAlloc::new());
+        check(&*alloc, random_layouts(seed, 256));
+    }
+}
@@ -0,0 +1,68 @@
+use core::{alloc::Layout, mem};
+
+use alloc_many::{allocator, oom, Alloc};
+use alloc_many_bump::{consts, BumpAlloc, Capacity};
+use alloc_many_collections::{boxed::Box, vec::Vec};
+use generic_array::typenum::Sub1;
+
+#[oom]
+fn oom(_: Layout) -> ! {
+    panic!("OOM")
+}
+
+#[test]
+fn index_width() {
+    fn width<N>() -> usize
+    where
+        N: Capacity,
+    {
+        mem::size_of::<N::Index>()
+    }
+
+    assert_eq!(width::<consts::U1>(), 2);
+    assert_eq!(width::<Sub1<consts::U65536>>(), 2);
+    assert_eq!(width::<consts::U65536>(), 4);
+    assert_eq!(width::<Sub1<consts::U4294967296>>(), 4);
+    assert_eq!(width::<consts::U4294967296>(), mem::size_of::<usize>());
+}
+
+#[test]
+fn large() {
+    const N: usize = 128 * 1024;
+
+    #[allocator]
+    static A: BumpAlloc<consts::U131072> = BumpAlloc::new();
+
+    // a single allocation larger than 64 KiB
+    let mut v: Vec<A, u8> = Vec::with_capacity(80 * 1024);
+    v.extend((0..80 * 1024).map(|i| i as u8));
+    assert_eq!(A.used(), 80 * 1024);
+
+    // allocations that start past the first 64 KiB
+    let x: Box<A, [u8; 8 * 1024]> = Box::new([1; 8 * 1024]);
+    assert_eq!(A.used(), 88 * 1024);
+    assert!(v.iter().enumerate().all(|(i, x)| *x == i as u8));
+    assert!(x.iter().all(|x| *x == 1));
+
+    unsafe {
+        let layout = Layout::from_size_align(N - A.used(), 1).unwrap();
+        assert!(!A::alloc(layout).is_null());
+        assert_eq!(A.used(), N);
+
+        assert!(A::alloc(Layout::new::<u8>()).is_null());
+    }
+}
+
+#[test]
+fn too_large() {
+    #[allocator]
+    static A: BumpAlloc<consts::U1024> = BumpAlloc::new();
+
+    unsafe {
+        assert!(A::alloc(Layout::from_size_align(64 * 1024, 1).unwrap()).is_null());
+        // NOTE not `64 * 1024`: a multiple of that alignment may lie in the memory of `A`
+        let align = 1 << (mem::size_of::<usize>() * 8 - 2);
+        assert!(A::alloc(Layout::from_size_align(1, align).unwrap()).is_null());
+        assert_eq!(A.used(), 0);
+    }
+}
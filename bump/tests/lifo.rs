@@ -0,0 +1,92 @@
+use core::alloc::{GlobalAlloc, Layout};
+
+use alloc_many::{allocator, oom};
+use alloc_many_bump::{consts, BumpAlloc};
+use alloc_many_collections::{boxed::Box, vec::Vec};
+
+#[oom]
+fn oom(_: Layout) -> ! {
+    panic!("OOM")
+}
+
+#[test]
+fn dealloc() {
+    let alloc = BumpAlloc::<consts::U64>::new();
+    let layout = Layout::new::<[u8; 16]>();
+
+    unsafe {
+        let x = alloc.alloc(layout);
+        let y = alloc.alloc(layout);
+        assert_eq!(alloc.used(), 32);
+
+        // `x` is not the last block so its memory is not reclaimed
+        alloc.dealloc(x, layout);
+        assert_eq!(alloc.used(), 32);
+
+        alloc.dealloc(y, layout);
+        assert_eq!(alloc.used(), 16);
+
+        // the memory of `y` is reused
+        assert_eq!(alloc.alloc(layout), y);
+    }
+}
+
+#[test]
+fn realloc() {
+    let alloc = BumpAlloc::<consts::U64>::new();
+    let layout = Layout::new::<[u8; 16]>();
+
+    unsafe {
+        let x = alloc.alloc(layout);
+        x.write_bytes(1, 16);
+
+        // grow in place
+        assert_eq!(alloc.realloc(x, layout, 32), x);
+        assert_eq!(alloc.used(), 32);
+
+        // shrink in place
+        let layout = Layout::new::<[u8; 32]>();
+        assert_eq!(alloc.realloc(x, layout, 8), x);
+        assert_eq!(alloc.used(), 8);
+
+        // `x` is no longer the last block so it's moved
+        let layout = Layout::new::<[u8; 8]>();
+        let y = alloc.alloc(layout);
+        let z = alloc.realloc(x, layout, 16);
+        assert_ne!(z, x);
+        assert_eq!(alloc.used(), 32);
+        assert_eq!(*(z as *const [u8; 8]), [1; 8]);
+
+        // a block at the top that can't grow in place can't move either
+        let layout = Layout::new::<[u8; 16]>();
+        assert!(alloc.realloc(z, layout, 64).is_null());
+        assert_eq!(alloc.used(), 32);
+
+        // shrinking a block that's not at the top doesn't reclaim its tail
+        assert_eq!(alloc.realloc(y, Layout::new::<[u8; 8]>(), 1), y);
+        assert_eq!(alloc.used(), 32);
+    }
+}
+
+#[test]
+fn vec() {
+    #[allocator]
+    static A: BumpAlloc<consts::U128> = BumpAlloc::new();
+
+    // a growing vector only uses as much memory as its capacity
+    let mut v: Vec<A, u8> = Vec::new();
+    for i in 0..48 {
+        v.push(i);
+        assert_eq!(A.used(), v.capacity());
+    }
+    assert_eq!(v.capacity(), 64);
+
+    // `v` is no longer the last block so its memory is not reclaimed
+    let x: Box<A, u8> = Box::new(0);
+    v.shrink_to_fit();
+    assert_eq!(A.used(), 65);
+
+    drop(x);
+    drop(v);
+    assert_eq!(A.used(), 64);
+}
@@ -0,0 +1,129 @@
+use core::alloc::Layout;
+
+use alloc_many::{allocator, oom, Alloc};
+use alloc_many_bump::{consts, BumpAlloc, Scoped};
+use alloc_many_collections::{boxed::Box, vec::Vec};
+
+#[oom]
+fn oom(_: Layout) -> ! {
+    panic!("OOM")
+}
+
+fn boxed<S>(_: S, x: u32) -> Box<S, u32>
+where
+    S: Alloc,
+{
+    Box::new(x)
+}
+
+#[test]
+fn reset_to() {
+    #[allocator]
+    static A: BumpAlloc<consts::U64> = BumpAlloc::new();
+
+    let x: Box<A, [u8; 16]> = Box::new([0; 16]);
+    let mark = A.mark();
+
+    let v: Vec<A, u8> = Vec::with_capacity(32);
+    assert_eq!(A.used(), 48);
+    drop(v);
+
+    unsafe { A.reset_to(mark) }
+    assert_eq!(A.used(), 16);
+
+    // the memory is reused
+    let y: Box<A, [u8; 48]> = Box::new([1; 48]);
+    assert_eq!(A.used(), 64);
+    assert_eq!(*x, [0; 16]);
+    assert_eq!(*y, [1; 48]);
+
+    drop((x, y));
+    unsafe { A.reset() }
+    assert_eq!(A.used(), 0);
+    assert_ne!(A.mark(), mark);
+}
+
+#[test]
+fn with_scope() {
+    #[allocator]
+    static A: BumpAlloc<consts::U64> = BumpAlloc::new();
+
+    fn frame<S>(_: S) -> u32
+    where
+        S: Alloc,
+    {
+        let mut v: Vec<S, u32> = Vec::with_capacity(8);
+        v.extend(0..8);
+        let sum = Box::<S, u32>::new(v.iter().sum());
+
+        // NOTE `v` is not the last block that was allocated so `dealloc` doesn't reclaim its memory
+        drop(v);
+        *sum
+    }
+
+    // the allocator is rewound after each frame
+    for _ in 0..10 {
+        assert_eq!(A.with_scope(|scope| frame(scope)), 28);
+        assert_eq!(A.used(), 0);
+    }
+
+    // a block allocated before the scope doesn't prevent rewinding the allocator to the mark
+    let x: Box<A, u32> = Box::new(42);
+    let mark = A.mark();
+    for _ in 0..10 {
+        assert_eq!(A.with_scope(|scope| frame(scope)), 28);
+        assert_eq!(A.mark(), mark);
+        assert_eq!(A.used(), 4);
+    }
+    assert_eq!(*x, 42);
+}
+
+#[test]
+fn nested() {
+    #[allocator]
+    static A: BumpAlloc<consts::U64> = BumpAlloc::new();
+
+    let _x: Box<A, u32> = Box::new(0);
+    A.with_scope(|outer| {
+        let y = boxed(outer, 1);
+        A.with_scope(|inner| {
+            let z = boxed(inner, 2);
+            let _w = boxed(inner, 3);
+            drop(z);
+        });
+
+        // the outer scope is still open so the inner one is not rewound
+        assert_eq!(A.used(), 12);
+        assert_eq!(*y, 1);
+    });
+    assert_eq!(A.used(), 4);
+}
+
+#[test]
+fn unscoped() {
+    #[allocator]
+    static A: BumpAlloc<consts::U64> = BumpAlloc::new();
+
+    // a block allocated on the singleton inside the scope prevents rewinding the allocator
+    let mut x = None;
+    A.with_scope(|scope| {
+        let _y = boxed(scope, 0);
+        x = Some(Box::<A, u32>::new(1));
+    });
+    assert_eq!(A.used(), 8);
+    assert_eq!(x.as_deref(), Some(&1));
+}
+
+#[test]
+fn escape() {
+    #[allocator]
+    static A: BumpAlloc<consts::U64> = BumpAlloc::new();
+
+    // NOTE `Box<Scope<'_, A>, _>` can't be returned from the closure but a block allocated on the
+    // singleton itself can; that block prevents rewinding the allocator
+    let x = A.with_scope(|_| Box::<A, _>::new(42u32));
+    assert_eq!(A.used(), 4);
+
+    let y: Box<A, u32> = Box::new(0);
+    assert_eq!((*x, *y), (42, 0));
+}
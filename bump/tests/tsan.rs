@@ -1,13 +1,17 @@
-use core::{alloc::Layout, time::Duration};
+use core::{
+    alloc::Layout,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
 use std::{
     collections::BTreeSet,
     sync::{Arc, Barrier},
     thread,
 };
 
-use alloc_many::{allocator, oom};
-use alloc_many_bump::{consts, BumpAlloc};
-use alloc_many_collections::boxed::Box;
+use alloc_many::{allocator, oom, Alloc};
+use alloc_many_bump::{consts, BumpAlloc, Scoped};
+use alloc_many_collections::{boxed::Box, sync};
 use threadpool::ThreadPool;
 
 #[oom]
@@ -32,13 +36,14 @@ fn race() {
             // all threads should start allocating at around the same time
             barrier.wait();
 
+            // NOTE the boxes are kept alive because `dealloc` may reclaim their memory
             let boxes = (0..100)
                 .map(|_| {
                     let mut x = Box::<A, _>::new(0);
                     *x += 1;
-                    &*x as *const i32 as usize
+                    x
                 })
-                .collect::<BTreeSet<_>>();
+                .collect::<Vec<_>>();
 
             s.send(boxes).unwrap();
         })
@@ -49,10 +54,131 @@ fn race() {
 
     // check that there's no aliasing among the boxes
     let mut seen = BTreeSet::new();
+    let mut alive = vec![];
     for _ in 0..N {
         let boxes = r.recv().unwrap();
+        let addresses = boxes
+            .iter()
+            .map(|x| &**x as *const i32 as usize)
+            .collect::<BTreeSet<_>>();
+
+        assert_eq!(addresses.len(), boxes.len());
+        assert_eq!(seen.intersection(&addresses).count(), 0);
+        seen.extend(addresses);
+        alive.push(boxes);
+    }
+}
+
+#[test]
+fn arc() {
+    #[allocator]
+    static A: BumpAlloc<consts::U128> = BumpAlloc::new();
+
+    static DROPPED: AtomicUsize = AtomicUsize::new(0);
+
+    struct X {
+        count: AtomicUsize,
+    }
+
+    impl Drop for X {
+        fn drop(&mut self) {
+            DROPPED.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    const N: usize = 10;
+    let x = sync::Arc::<A, _>::new(X {
+        count: AtomicUsize::new(0),
+    });
+    let weak = sync::Arc::downgrade(&x);
+
+    let (s, r) = crossbeam_channel::bounded(N);
+    let pool = ThreadPool::new(N);
+    let barrier = Arc::new(Barrier::new(N + 1));
+    for _ in 0..N {
+        let barrier = barrier.clone();
+        let s = s.clone();
+        let x = x.clone();
+        let weak = weak.clone();
+
+        pool.execute(move || {
+            // all threads should start cloning at around the same time
+            barrier.wait();
+
+            for _ in 0..100 {
+                let y = x.clone();
+                y.count.fetch_add(1, Ordering::Relaxed);
+
+                let z = weak.upgrade().unwrap();
+                drop(y);
+                drop(z);
+            }
+
+            // one of the threads will drop the last strong reference
+            drop(x);
+            s.send(()).unwrap();
+        })
+    }
+
+    thread::sleep(Duration::from_millis(100));
+    drop(x);
+    barrier.wait();
 
-        assert_eq!(seen.intersection(&boxes).count(), 0);
-        seen.extend(boxes);
+    for _ in 0..N {
+        r.recv().unwrap();
+    }
+    pool.join();
+
+    assert!(weak.upgrade().is_none());
+    assert_eq!(DROPPED.load(Ordering::Relaxed), 1);
+}
+
+#[test]
+fn scopes() {
+    #[allocator]
+    static A: BumpAlloc<consts::U4096> = BumpAlloc::new();
+
+    fn frame<S>(_: S, id: usize)
+    where
+        S: Alloc,
+    {
+        // NOTE the allocator may run out of memory because a scope is only rewound when no other
+        // thread has a scope open
+        let boxes = (0..10)
+            .filter_map(|_| Box::<S, _>::try_new(id).ok())
+            .collect::<std::vec::Vec<_>>();
+
+        thread::yield_now();
+
+        // no other thread has overwritten our boxes
+        assert!(boxes.iter().all(|x| **x == id));
+    }
+
+    const N: usize = 10;
+    let (s, r) = crossbeam_channel::bounded(N);
+    let pool = ThreadPool::new(N);
+    let barrier = Arc::new(Barrier::new(N + 1));
+    for id in 0..N {
+        let barrier = barrier.clone();
+        let s = s.clone();
+
+        pool.execute(move || {
+            // all threads should start allocating at around the same time
+            barrier.wait();
+
+            // every thread tries to rewind the allocator while the other threads are allocating
+            for _ in 0..100 {
+                A.with_scope(|scope| frame(scope, id));
+            }
+
+            s.send(()).unwrap();
+        })
+    }
+
+    thread::sleep(Duration::from_millis(100));
+    barrier.wait();
+
+    for _ in 0..N {
+        r.recv().unwrap();
     }
 }
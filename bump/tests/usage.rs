@@ -0,0 +1,82 @@
+use core::alloc::{GlobalAlloc, Layout};
+
+use alloc_many::{allocator, oom};
+use alloc_many_bump::{consts, BumpAlloc, Scoped};
+use alloc_many_collections::vec::Vec;
+
+#[oom]
+fn oom(_: Layout) -> ! {
+    panic!("OOM")
+}
+
+#[test]
+fn accessors() {
+    let alloc = BumpAlloc::<consts::U64>::new();
+    assert_eq!(alloc.capacity(), 64);
+    assert_eq!(alloc.used(), 0);
+    assert_eq!(alloc.remaining(), 64);
+    assert_eq!(alloc.high_water_mark(), 0);
+
+    unsafe {
+        let layout = Layout::new::<[u8; 24]>();
+        let x = alloc.alloc(layout);
+        let y = alloc.alloc(layout);
+        assert_eq!(alloc.used(), 48);
+        assert_eq!(alloc.remaining(), 16);
+        assert_eq!(alloc.high_water_mark(), 48);
+
+        // the high-water mark is not lowered by `dealloc`
+        alloc.dealloc(y, layout);
+        assert_eq!(alloc.used(), 24);
+        assert_eq!(alloc.high_water_mark(), 48);
+
+        // growing a block in place raises it
+        let x = alloc.realloc(x, layout, 56);
+        assert_eq!(alloc.used(), 56);
+        assert_eq!(alloc.high_water_mark(), 56);
+
+        // nor is it lowered by rewinding the allocator
+        alloc.dealloc(x, Layout::new::<[u8; 56]>());
+        alloc.reset();
+        assert_eq!(alloc.used(), 0);
+        assert_eq!(alloc.remaining(), 64);
+        assert_eq!(alloc.high_water_mark(), 56);
+    }
+}
+
+#[test]
+fn contains() {
+    let alloc = BumpAlloc::<consts::U64>::new();
+    let other = BumpAlloc::<consts::U64>::new();
+
+    unsafe {
+        let layout = Layout::new::<[u8; 64]>();
+        let x = alloc.alloc(layout);
+
+        assert!(alloc.contains(x));
+        assert!(alloc.contains(x.add(63)));
+        assert!(!alloc.contains(x.add(64)));
+        assert!(!other.contains(x));
+        assert!(!alloc.contains(&0u8));
+    }
+}
+
+#[test]
+fn scopes() {
+    #[allocator]
+    static A: BumpAlloc<consts::U1024> = BumpAlloc::new();
+
+    fn frame(n: usize) {
+        A.with_scope(|_| {
+            let v: Vec<A, u8> = Vec::with_capacity(n);
+            drop(v);
+        })
+    }
+
+    // the high-water mark records the largest frame
+    for &n in &[128, 512, 256] {
+        frame(n);
+        assert_eq!(A.used(), 0);
+    }
+    assert_eq!(A.high_water_mark(), 512);
+}
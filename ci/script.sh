@@ -7,6 +7,7 @@ main() {
     if [ $T = x86_64-unknown-linux-gnu ] && [ $TRAVIS_RUST_VERSION = nightly ]; then
         cargo test -p alloc-many-collections
         cargo test -p alloc-many-collections --release
+        cargo test -p alloc-many-collections --features alloc-many-collections/nightly
 
         cd bump
 
@@ -0,0 +1,519 @@
+//! A priority queue implemented with a binary heap.
+
+use core::{
+    fmt,
+    iter::FromIterator,
+    mem::{self, ManuallyDrop},
+    ops, ptr, slice,
+};
+
+use alloc_many::Alloc;
+
+use crate::{
+    vec::{self, Vec},
+    TryReserveError,
+};
+
+/// A priority queue implemented with a binary heap.
+///
+/// This will be a max-heap. The elements are stored in a `Vec` allocated on the allocator `A`.
+pub struct BinaryHeap<A, T>
+where
+    A: Alloc,
+{
+    data: Vec<A, T>,
+}
+
+impl<A, T> BinaryHeap<A, T>
+where
+    A: Alloc,
+    T: Ord,
+{
+    /// Creates an empty `BinaryHeap` as a max-heap.
+    pub fn new() -> Self {
+        BinaryHeap { data: Vec::new() }
+    }
+
+    /// Creates an empty `BinaryHeap` with a specific capacity.
+    pub fn with_capacity(capacity: usize) -> Self {
+        BinaryHeap {
+            data: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Returns a mutable reference to the greatest item in the binary heap, or `None` if it is
+    /// empty.
+    ///
+    /// The heap is restored when the returned `PeekMut` is dropped.
+    pub fn peek_mut(&mut self) -> Option<PeekMut<'_, A, T>> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(PeekMut {
+                heap: self,
+                sift: false,
+            })
+        }
+    }
+
+    /// Removes the greatest item from the binary heap and returns it, or `None` if it is empty.
+    pub fn pop(&mut self) -> Option<T> {
+        self.data.pop().map(|mut item| {
+            if !self.is_empty() {
+                mem::swap(&mut item, &mut self.data[0]);
+                self.sift_down_to_bottom(0);
+            }
+            item
+        })
+    }
+
+    /// Pushes an item onto the binary heap.
+    pub fn push(&mut self, item: T) {
+        self.try_push(item)
+            .unwrap_or_else(|e| crate::handle_reserve_error::<A>(e))
+    }
+
+    /// Pushes an item onto the binary heap, returning an error if the heap needs to grow and
+    /// the allocation fails.
+    ///
+    /// On error the heap is left unchanged and `item` is dropped.
+    pub fn try_push(&mut self, item: T) -> Result<(), TryReserveError> {
+        self.data.try_push(item)?;
+        let old_len = self.len() - 1;
+        self.sift_up(0, old_len);
+        Ok(())
+    }
+
+    /// Consumes the `BinaryHeap` and returns a vector in sorted (ascending) order.
+    pub fn into_sorted_vec(mut self) -> Vec<A, T> {
+        let mut end = self.len();
+        while end > 1 {
+            end -= 1;
+            self.data.swap(0, end);
+            self.sift_down_range(0, end);
+        }
+        self.into_vec()
+    }
+
+    /// Moves all the elements of `other` into `self`, leaving `other` empty.
+    pub fn append(&mut self, other: &mut Self) {
+        if self.len() < other.len() {
+            mem::swap(self, other);
+        }
+
+        let start = self.data.len();
+        self.data.append(&mut other.data);
+
+        for i in start..self.data.len() {
+            self.sift_up(0, i);
+        }
+    }
+
+    /// Retains only the elements specified by the predicate.
+    pub fn retain<F>(&mut self, f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let len = self.len();
+        self.data.retain(f);
+
+        if self.len() != len {
+            self.rebuild();
+        }
+    }
+
+    // Moves the element at `pos` up the tree; returns its new position
+    //
+    // NOTE uses a `Hole` so the heap is left in a consistent state if `cmp` panics
+    fn sift_up(&mut self, start: usize, pos: usize) -> usize {
+        unsafe {
+            let mut hole = Hole::new(&mut self.data, pos);
+
+            while hole.pos() > start {
+                let parent = (hole.pos() - 1) / 2;
+                if hole.element() <= hole.get(parent) {
+                    break;
+                }
+                hole.move_to(parent);
+            }
+
+            hole.pos()
+        }
+    }
+
+    // Moves the element at `pos` down the tree, only looking at the elements in `..end`
+    fn sift_down_range(&mut self, pos: usize, end: usize) {
+        unsafe {
+            let mut hole = Hole::new(&mut self.data, pos);
+            let mut child = 2 * hole.pos() + 1;
+
+            while child < end {
+                let right = child + 1;
+                // pick the greater of the two children
+                if right < end && hole.get(child) <= hole.get(right) {
+                    child = right;
+                }
+
+                if hole.element() >= hole.get(child) {
+                    break;
+                }
+
+                hole.move_to(child);
+                child = 2 * hole.pos() + 1;
+            }
+        }
+    }
+
+    // Moves the element at `pos` all the way down to a leaf and then sifts it up
+    //
+    // Faster than `sift_down_range` when the element is known to be small, like in `pop`
+    fn sift_down_to_bottom(&mut self, mut pos: usize) {
+        let end = self.len();
+        let start = pos;
+
+        unsafe {
+            let mut hole = Hole::new(&mut self.data, pos);
+            let mut child = 2 * hole.pos() + 1;
+
+            while child < end {
+                let right = child + 1;
+                if right < end && hole.get(child) <= hole.get(right) {
+                    child = right;
+                }
+
+                hole.move_to(child);
+                child = 2 * hole.pos() + 1;
+            }
+
+            pos = hole.pos();
+        }
+
+        self.sift_up(start, pos);
+    }
+
+    // O(n) heapify
+    fn rebuild(&mut self) {
+        let len = self.len();
+        let mut n = len / 2;
+        while n > 0 {
+            n -= 1;
+            self.sift_down_range(n, len);
+        }
+    }
+}
+
+impl<A, T> BinaryHeap<A, T>
+where
+    A: Alloc,
+{
+    /// Returns the greatest item in the binary heap, or `None` if it is empty.
+    pub fn peek(&self) -> Option<&T> {
+        self.data.first()
+    }
+
+    /// Returns the number of elements the binary heap can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.data.capacity()
+    }
+
+    /// Reserves capacity for at least `additional` more elements to be inserted in the
+    /// `BinaryHeap`.
+    pub fn reserve(&mut self, additional: usize) {
+        self.data.reserve(additional)
+    }
+
+    /// Tries to reserve capacity for at least `additional` more elements to be inserted in the
+    /// `BinaryHeap`.
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.data.try_reserve(additional)
+    }
+
+    /// Discards as much additional capacity as possible.
+    pub fn shrink_to_fit(&mut self) {
+        self.data.shrink_to_fit()
+    }
+
+    /// Consumes the `BinaryHeap` and returns the underlying vector in arbitrary order.
+    pub fn into_vec(self) -> Vec<A, T> {
+        self.data
+    }
+
+    /// Returns the length of the binary heap.
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Checks if the binary heap is empty.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Returns an iterator visiting all values in the underlying vector, in arbitrary order.
+    pub fn iter(&self) -> slice::Iter<'_, T> {
+        self.data.iter()
+    }
+
+    /// Clears the binary heap, returning an iterator over the removed elements.
+    ///
+    /// The elements are removed in arbitrary order.
+    pub fn drain(&mut self) -> vec::Drain<'_, A, T> {
+        self.data.drain(..)
+    }
+
+    /// Drops all items from the binary heap.
+    pub fn clear(&mut self) {
+        self.data.clear()
+    }
+}
+
+// A hole in a slice: an index whose value has been moved out and is held by the `Hole`
+//
+// On drop the value is written back at the current position of the hole
+struct Hole<'a, T> {
+    data: &'a mut [T],
+    elt: ManuallyDrop<T>,
+    pos: usize,
+}
+
+impl<'a, T> Hole<'a, T> {
+    // NOTE `pos` must be in bounds
+    unsafe fn new(data: &'a mut [T], pos: usize) -> Self {
+        debug_assert!(pos < data.len());
+        let elt = ptr::read(data.get_unchecked(pos));
+
+        Hole {
+            data,
+            elt: ManuallyDrop::new(elt),
+            pos,
+        }
+    }
+
+    fn pos(&self) -> usize {
+        self.pos
+    }
+
+    fn element(&self) -> &T {
+        &self.elt
+    }
+
+    // NOTE `index` must be in bounds and different from `pos`
+    unsafe fn get(&self, index: usize) -> &T {
+        debug_assert!(index != self.pos);
+        self.data.get_unchecked(index)
+    }
+
+    // moves the hole to `index`, moving the element at `index` into the current hole
+    //
+    // NOTE `index` must be in bounds and different from `pos`
+    unsafe fn move_to(&mut self, index: usize) {
+        debug_assert!(index != self.pos);
+        let ptr = self.data.as_mut_ptr();
+        ptr::copy_nonoverlapping(ptr.add(index), ptr.add(self.pos), 1);
+        self.pos = index;
+    }
+}
+
+impl<'a, T> Drop for Hole<'a, T> {
+    fn drop(&mut self) {
+        unsafe {
+            let pos = self.pos;
+            ptr::copy_nonoverlapping(&*self.elt, self.data.get_unchecked_mut(pos), 1);
+        }
+    }
+}
+
+/// Structure wrapping a mutable reference to the greatest item on a `BinaryHeap`.
+///
+/// This `struct` is created by the `peek_mut` method on `BinaryHeap`.
+pub struct PeekMut<'a, A, T>
+where
+    A: Alloc,
+    T: Ord,
+{
+    heap: &'a mut BinaryHeap<A, T>,
+    // NOTE only sift down on drop if the element may have been modified
+    sift: bool,
+}
+
+impl<'a, A, T> PeekMut<'a, A, T>
+where
+    A: Alloc,
+    T: Ord,
+{
+    /// Removes the peeked value from the heap and returns it.
+    pub fn pop(mut this: PeekMut<'a, A, T>) -> T {
+        this.sift = false;
+        // NOTE `peek_mut` only hands out a `PeekMut` when the heap is not empty
+        this.heap.pop().unwrap()
+    }
+}
+
+impl<'a, A, T> Drop for PeekMut<'a, A, T>
+where
+    A: Alloc,
+    T: Ord,
+{
+    fn drop(&mut self) {
+        if self.sift {
+            let len = self.heap.len();
+            self.heap.sift_down_range(0, len);
+        }
+    }
+}
+
+impl<'a, A, T> ops::Deref for PeekMut<'a, A, T>
+where
+    A: Alloc,
+    T: Ord,
+{
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.heap.data[0]
+    }
+}
+
+impl<'a, A, T> ops::DerefMut for PeekMut<'a, A, T>
+where
+    A: Alloc,
+    T: Ord,
+{
+    fn deref_mut(&mut self) -> &mut T {
+        self.sift = true;
+        &mut self.heap.data[0]
+    }
+}
+
+impl<'a, A, T> fmt::Debug for PeekMut<'a, A, T>
+where
+    A: Alloc,
+    T: Ord + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("PeekMut").field(&self.heap.data[0]).finish()
+    }
+}
+
+impl<A, T> From<Vec<A, T>> for BinaryHeap<A, T>
+where
+    A: Alloc,
+    T: Ord,
+{
+    /// Converts a `Vec<T>` into a `BinaryHeap<T>` in O(n) time.
+    fn from(vec: Vec<A, T>) -> Self {
+        let mut heap = BinaryHeap { data: vec };
+        heap.rebuild();
+        heap
+    }
+}
+
+impl<A, T> From<BinaryHeap<A, T>> for Vec<A, T>
+where
+    A: Alloc,
+{
+    fn from(heap: BinaryHeap<A, T>) -> Self {
+        heap.data
+    }
+}
+
+impl<A, T> IntoIterator for BinaryHeap<A, T>
+where
+    A: Alloc,
+{
+    type Item = T;
+    type IntoIter = vec::IntoIter<A, T>;
+
+    /// Creates a consuming iterator that moves each value out of the binary heap in arbitrary
+    /// order.
+    fn into_iter(self) -> vec::IntoIter<A, T> {
+        self.data.into_iter()
+    }
+}
+
+impl<'a, A, T> IntoIterator for &'a BinaryHeap<A, T>
+where
+    A: Alloc,
+{
+    type Item = &'a T;
+    type IntoIter = slice::Iter<'a, T>;
+
+    fn into_iter(self) -> slice::Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<A, T> Extend<T> for BinaryHeap<A, T>
+where
+    A: Alloc,
+    T: Ord,
+{
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+
+        for item in iter {
+            self.push(item);
+        }
+    }
+}
+
+impl<'a, A, T> Extend<&'a T> for BinaryHeap<A, T>
+where
+    A: Alloc,
+    T: 'a + Ord + Copy,
+{
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = &'a T>,
+    {
+        self.extend(iter.into_iter().cloned())
+    }
+}
+
+impl<A, T> FromIterator<T> for BinaryHeap<A, T>
+where
+    A: Alloc,
+    T: Ord,
+{
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+    {
+        BinaryHeap::from(iter.into_iter().collect::<Vec<A, T>>())
+    }
+}
+
+impl<A, T> Clone for BinaryHeap<A, T>
+where
+    A: Alloc,
+    T: Clone,
+{
+    fn clone(&self) -> Self {
+        BinaryHeap {
+            data: self.data.clone(),
+        }
+    }
+}
+
+impl<A, T> Default for BinaryHeap<A, T>
+where
+    A: Alloc,
+    T: Ord,
+{
+    fn default() -> Self {
+        BinaryHeap::new()
+    }
+}
+
+impl<A, T> fmt::Debug for BinaryHeap<A, T>
+where
+    A: Alloc,
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
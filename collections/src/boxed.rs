@@ -1,9 +1,28 @@
 //! A pointer type for heap allocations
-use core::{alloc::Layout, cmp, fmt, marker::PhantomData, ops, ptr};
+#[cfg(feature = "nightly")]
+use core::marker::{Tuple, Unsize};
+use core::{
+    alloc::Layout,
+    borrow::{Borrow, BorrowMut},
+    cmp, fmt,
+    future::Future,
+    hash::{Hash, Hasher},
+    iter::FusedIterator,
+    marker::{PhantomData, Unpin},
+    mem::{self, MaybeUninit},
+    ops,
+    pin::Pin,
+    ptr, slice,
+    task::{Context, Poll},
+};
 
 use alloc_many::Alloc;
 
-use crate::unique::Unique;
+use crate::{
+    unique::Unique,
+    vec::{self, Vec},
+    TryReserveError,
+};
 
 /// A pointer type for heap allocations
 pub struct Box<A, T>
@@ -21,21 +40,331 @@ where
 {
     /// Allocates memory on the allocator `A` and then places `x` into it.
     pub fn new(value: T) -> Self {
+        Self::try_new(value).unwrap_or_else(|e| crate::handle_reserve_error::<A>(e))
+    }
+
+    /// Allocates memory on the allocator `A` and then places `x` into it, returning an error if
+    /// the allocation fails
+    pub fn try_new(value: T) -> Result<Self, TryReserveError> {
+        let mut b = Self::try_new_uninit()?;
+
+        unsafe {
+            b.as_mut_ptr().write(value);
+            Ok(b.assume_init())
+        }
+    }
+
+    /// Constructs a new `Pin<Box<A, T>>`. If `T` does not implement `Unpin`, then `x` will be
+    /// pinned in memory and unable to be moved.
+    pub fn pin(x: T) -> Pin<Self> {
+        Box::new(x).into()
+    }
+
+    /// Constructs a new box with uninitialized contents.
+    pub fn new_uninit() -> Box<A, MaybeUninit<T>> {
+        Self::try_new_uninit().unwrap_or_else(|e| crate::handle_reserve_error::<A>(e))
+    }
+
+    /// Constructs a new box with uninitialized contents, returning an error if the allocation
+    /// fails
+    pub fn try_new_uninit() -> Result<Box<A, MaybeUninit<T>>, TryReserveError> {
+        Self::try_alloc(false)
+    }
+
+    /// Constructs a new box with uninitialized contents, with the memory being filled with `0`
+    /// bytes.
+    pub fn new_zeroed() -> Box<A, MaybeUninit<T>> {
+        Self::try_new_zeroed().unwrap_or_else(|e| crate::handle_reserve_error::<A>(e))
+    }
+
+    /// Constructs a new box with uninitialized contents, with the memory being filled with `0`
+    /// bytes, returning an error if the allocation fails
+    pub fn try_new_zeroed() -> Result<Box<A, MaybeUninit<T>>, TryReserveError> {
+        Self::try_alloc(true)
+    }
+
+    fn try_alloc(zeroed: bool) -> Result<Box<A, MaybeUninit<T>>, TryReserveError> {
         let layout = Layout::new::<T>();
 
         unsafe {
-            Unique::new(A::alloc(layout) as *mut T)
-                .map(|ptr| {
-                    ptr.as_ptr().write(value);
-
-                    Box {
-                        _allocator: PhantomData,
-                        ptr,
-                    }
-                })
-                .unwrap_or_else(|| crate::alloc_many_oom(layout))
+            // NOTE zero-sized values are not allocated; see `Drop`
+            let ptr = if layout.size() == 0 {
+                Unique::empty()
+            } else {
+                let ptr = if zeroed {
+                    A::alloc_zeroed(layout)
+                } else {
+                    A::alloc(layout)
+                };
+
+                Unique::new(ptr as *mut MaybeUninit<T>)
+                    .ok_or(TryReserveError::AllocError { layout })?
+            };
+
+            Ok(Box {
+                _allocator: PhantomData,
+                ptr,
+            })
+        }
+    }
+}
+
+impl<A, T> Box<A, T>
+where
+    A: Alloc + 'static,
+{
+    /// Moves the boxed value into the allocator `B`.
+    ///
+    /// If `B` is `A` the allocation is reused.
+    pub fn move_to<B>(self) -> Box<B, T>
+    where
+        B: Alloc + 'static,
+    {
+        self.try_move_to()
+            .unwrap_or_else(|(_, e)| crate::handle_reserve_error::<B>(e))
+    }
+
+    /// Moves the boxed value into the allocator `B`, returning an error if the allocation fails
+    ///
+    /// If `B` is `A` the allocation is reused. On error the original box is returned along with
+    /// the error.
+    pub fn try_move_to<B>(self) -> Result<Box<B, T>, (Self, TryReserveError)>
+    where
+        B: Alloc + 'static,
+    {
+        if crate::same_alloc::<A, B>() {
+            return unsafe { Ok(Box::from_raw(Box::into_raw(self))) };
+        }
+
+        let mut b = match Box::<B, T>::try_new_uninit() {
+            Ok(b) => b,
+            Err(e) => return Err((self, e)),
+        };
+
+        unsafe {
+            let raw = Box::into_raw(self);
+            b.as_mut_ptr().copy_from_nonoverlapping(raw, 1);
+            // frees the old allocation without dropping the value
+            drop(Box::<A, MaybeUninit<T>>::from_raw(
+                raw as *mut MaybeUninit<T>,
+            ));
+            Ok(b.assume_init())
+        }
+    }
+
+    /// Clones the boxed value into a new allocation on the allocator `B`.
+    pub fn clone_in<B>(&self) -> Box<B, T>
+    where
+        B: Alloc,
+        T: Clone,
+    {
+        Box::new((**self).clone())
+    }
+
+    /// Clones the boxed value into a new allocation on the allocator `B`, returning an error if
+    /// the allocation fails
+    pub fn try_clone_in<B>(&self) -> Result<Box<B, T>, TryReserveError>
+    where
+        B: Alloc,
+        T: Clone,
+    {
+        Box::try_new((**self).clone())
+    }
+}
+
+impl<A, T> Box<A, MaybeUninit<T>>
+where
+    A: Alloc,
+{
+    /// Converts to `Box<A, T>`.
+    ///
+    /// # Safety
+    ///
+    /// The value must be initialized.
+    pub unsafe fn assume_init(self) -> Box<A, T> {
+        Box::from_raw(Box::into_raw(self) as *mut T)
+    }
+}
+
+impl<A, T> Box<A, T>
+where
+    A: Alloc,
+    T: ?Sized,
+{
+    /// Constructs a box from a raw pointer.
+    ///
+    /// After calling this function, the raw pointer is owned by the resulting `Box`.
+    ///
+    /// # Safety
+    ///
+    /// `raw` must have been allocated on the allocator `A` with the layout of `*raw` (e.g. it
+    /// comes from `Box::<A, T>::into_raw`), or be a dangling, well-aligned pointer if that layout
+    /// is zero-sized. `*raw` must be initialized.
+    pub unsafe fn from_raw(raw: *mut T) -> Self {
+        Box {
+            _allocator: PhantomData,
+            ptr: Unique::new_unchecked(raw),
         }
     }
+
+    /// Consumes the `Box`, returning a wrapped raw pointer.
+    ///
+    /// The caller becomes responsible for the memory; use `from_raw` to free it.
+    pub fn into_raw(b: Self) -> *mut T {
+        let ptr = b.ptr.as_ptr();
+        mem::forget(b);
+        ptr
+    }
+
+    /// Consumes and leaks the `Box`, returning a mutable reference, `&'a mut T`.
+    ///
+    /// The memory is never freed; if `T: 'static` the reference can be `&'static mut T`.
+    pub fn leak<'a>(b: Self) -> &'a mut T
+    where
+        T: 'a,
+    {
+        unsafe { &mut *Box::into_raw(b) }
+    }
+}
+
+impl<A, T> Box<A, T>
+where
+    A: Alloc,
+{
+    // NOTE implementation detail of `unsize_box!`; `f` must return its argument, coerced
+    #[doc(hidden)]
+    pub unsafe fn __unsize<U, F>(b: Self, f: F) -> Box<A, U>
+    where
+        U: ?Sized,
+        F: FnOnce(*mut T) -> *mut U,
+    {
+        Box::from_raw(f(Box::into_raw(b)))
+    }
+}
+
+/// Converts a `Box<A, T>` into a `Box<A, U>` where `U` is an unsized type (e.g. a trait object)
+/// that `T` can be coerced to
+///
+/// This is the stable counterpart of the implicit unsized coercion that the `nightly` feature
+/// enables.
+///
+/// # Example
+///
+/// ``` ignore
+/// use alloc_many_collections::{boxed::Box, unsize_box};
+///
+/// let x: Box<A, _> = Box::new(|| {});
+/// let f: Box<A, dyn Fn()> = unsize_box!(x as dyn Fn());
+/// f();
+///
+/// let g: Box<A, dyn Fn()> = unsize_box!(Box::new(|| {}) as dyn Fn());
+/// ```
+///
+/// Only unsized coercions are performed. In particular, the contents of the box are not
+/// dereferenced:
+///
+/// ``` compile_fail
+/// # use alloc_many::allocator;
+/// # use alloc_many_bump::{consts, BumpAlloc};
+/// # use alloc_many_collections::{boxed::Box, unsize_box};
+/// # #[allocator]
+/// # static A: BumpAlloc<consts::U128> = BumpAlloc::new();
+/// # #[allocator]
+/// # static B: BumpAlloc<consts::U128> = BumpAlloc::new();
+/// let outer: Box<A, Box<B, [u64; 4]>> = Box::new(Box::new([0; 4]));
+/// // this would point into memory owned by `B`
+/// let inner: Box<A, [u64; 4]> = unsize_box!(outer as [u64; 4]);
+/// ```
+#[macro_export]
+macro_rules! unsize_box {
+    // NOTE an `expr` fragment can't be followed by `as` so the expression is collected token by
+    // token
+    (@expr [$($box:tt)+] as $ty:ty) => {{
+        let b = $($box)+;
+        unsafe { $crate::boxed::Box::__unsize::<$ty, _>(b, |p| -> *mut $ty { p }) }
+    }};
+    (@expr [$($box:tt)*] $next:tt $($rest:tt)*) => {
+        $crate::unsize_box!(@expr [$($box)* $next] $($rest)*)
+    };
+    ($($tokens:tt)+) => {
+        $crate::unsize_box!(@expr [] $($tokens)+)
+    };
+}
+
+impl<A, T> Box<A, [T]>
+where
+    A: Alloc,
+{
+    /// Allocates a slice on the allocator `A` and clones the elements of `slice` into it.
+    pub fn from_slice(slice: &[T]) -> Self
+    where
+        T: Clone,
+    {
+        Self::try_from_slice(slice).unwrap_or_else(|e| crate::handle_reserve_error::<A>(e))
+    }
+
+    /// Allocates a slice on the allocator `A` and clones the elements of `slice` into it,
+    /// returning an error if the allocation fails
+    pub fn try_from_slice(slice: &[T]) -> Result<Self, TryReserveError>
+    where
+        T: Clone,
+    {
+        let mut v = Vec::try_with_capacity(slice.len())?;
+        v.extend_from_slice(slice);
+        Ok(v.into_boxed_slice())
+    }
+
+    /// Allocates a slice of `len` uninitialized elements on the allocator `A`.
+    pub fn new_uninit_slice(len: usize) -> Box<A, [MaybeUninit<T>]> {
+        Self::try_new_uninit_slice(len).unwrap_or_else(|e| crate::handle_reserve_error::<A>(e))
+    }
+
+    /// Allocates a slice of `len` uninitialized elements on the allocator `A`, returning an error
+    /// if the allocation fails
+    pub fn try_new_uninit_slice(len: usize) -> Result<Box<A, [MaybeUninit<T>]>, TryReserveError> {
+        let mut v = Vec::try_with_capacity(len)?;
+        // NOTE `MaybeUninit` needs no initialization
+        unsafe { v.set_len(len) }
+        Ok(v.into_boxed_slice())
+    }
+
+    /// Converts the boxed slice into a vector without reallocating.
+    pub fn into_vec(self) -> Vec<A, T> {
+        let len = self.len();
+        unsafe { Vec::from_raw_parts(Box::into_raw(self) as *mut T, len, len) }
+    }
+}
+
+impl<A, T> Box<A, [MaybeUninit<T>]>
+where
+    A: Alloc,
+{
+    /// Converts to `Box<A, [T]>`.
+    ///
+    /// # Safety
+    ///
+    /// All the elements of the slice must be initialized.
+    pub unsafe fn assume_init(self) -> Box<A, [T]> {
+        Box::from_raw(Box::into_raw(self) as *mut [T])
+    }
+}
+
+impl<A> Box<A, str>
+where
+    A: Alloc,
+{
+    /// Allocates a string slice on the allocator `A` and copies `s` into it.
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Self {
+        Self::try_from_str(s).unwrap_or_else(|e| crate::handle_reserve_error::<A>(e))
+    }
+
+    /// Allocates a string slice on the allocator `A` and copies `s` into it, returning an error if
+    /// the allocation fails
+    pub fn try_from_str(s: &str) -> Result<Self, TryReserveError> {
+        let bytes = Box::<A, [u8]>::try_from_slice(s.as_bytes())?;
+        unsafe { Ok(Box::from_raw(Box::into_raw(bytes) as *mut str)) }
+    }
 }
 
 impl<A, T> ops::Deref for Box<A, T>
@@ -70,11 +399,88 @@ where
             let layout = Layout::for_value(self.ptr.as_ref());
             let ptr = self.ptr.as_ptr();
             ptr::drop_in_place(ptr);
-            A::dealloc(ptr as *mut u8, layout)
+
+            // NOTE zero-sized values (e.g. empty slices) use a dangling pointer
+            if layout.size() != 0 {
+                A::dealloc(ptr as *mut u8, layout)
+            }
         }
     }
 }
 
+#[cfg(feature = "nightly")]
+impl<A, T, U> ops::CoerceUnsized<Box<A, U>> for Box<A, T>
+where
+    A: Alloc,
+    T: ?Sized + Unsize<U>,
+    U: ?Sized,
+{
+}
+
+impl<A, T> Clone for Box<A, [T]>
+where
+    A: Alloc,
+    T: Clone,
+{
+    fn clone(&self) -> Self {
+        Box::from_slice(self)
+    }
+}
+
+impl<A> Clone for Box<A, str>
+where
+    A: Alloc,
+{
+    fn clone(&self) -> Self {
+        Box::from_str(self)
+    }
+}
+
+impl<A, T> From<Vec<A, T>> for Box<A, [T]>
+where
+    A: Alloc,
+{
+    fn from(v: Vec<A, T>) -> Self {
+        v.into_boxed_slice()
+    }
+}
+
+impl<A, T> IntoIterator for Box<A, [T]>
+where
+    A: Alloc,
+{
+    type Item = T;
+    type IntoIter = vec::IntoIter<A, T>;
+
+    fn into_iter(self) -> vec::IntoIter<A, T> {
+        self.into_vec().into_iter()
+    }
+}
+
+impl<'a, A, T> IntoIterator for &'a Box<A, [T]>
+where
+    A: Alloc,
+{
+    type Item = &'a T;
+    type IntoIter = slice::Iter<'a, T>;
+
+    fn into_iter(self) -> slice::Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, A, T> IntoIterator for &'a mut Box<A, [T]>
+where
+    A: Alloc,
+{
+    type Item = &'a mut T;
+    type IntoIter = slice::IterMut<'a, T>;
+
+    fn into_iter(self) -> slice::IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
 impl<A, T> fmt::Debug for Box<A, T>
 where
     T: ?Sized + fmt::Debug,
@@ -123,3 +529,273 @@ where
         <T as PartialOrd>::partial_cmp(self, other)
     }
 }
+
+impl<A, T> Ord for Box<A, T>
+where
+    T: ?Sized + Ord,
+    A: Alloc,
+{
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        <T as Ord>::cmp(self, other)
+    }
+}
+
+impl<A, T> Hash for Box<A, T>
+where
+    T: ?Sized + Hash,
+    A: Alloc,
+{
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: Hasher,
+    {
+        <T as Hash>::hash(self, state)
+    }
+}
+
+impl<A, T> Clone for Box<A, T>
+where
+    T: Clone,
+    A: Alloc,
+{
+    fn clone(&self) -> Self {
+        Box::new((**self).clone())
+    }
+}
+
+impl<A, T> Default for Box<A, T>
+where
+    T: Default,
+    A: Alloc,
+{
+    fn default() -> Self {
+        Box::new(T::default())
+    }
+}
+
+impl<A, T> Default for Box<A, [T]>
+where
+    A: Alloc,
+{
+    fn default() -> Self {
+        Vec::new().into_boxed_slice()
+    }
+}
+
+impl<A> Default for Box<A, str>
+where
+    A: Alloc,
+{
+    fn default() -> Self {
+        Box::from_str("")
+    }
+}
+
+impl<A, T> From<T> for Box<A, T>
+where
+    A: Alloc,
+{
+    fn from(x: T) -> Self {
+        Box::new(x)
+    }
+}
+
+impl<A, T> From<Box<A, T>> for Pin<Box<A, T>>
+where
+    T: ?Sized,
+    A: Alloc,
+{
+    fn from(b: Box<A, T>) -> Self {
+        // NOTE the contents can't be moved out of the allocation without a `&mut T`
+        unsafe { Pin::new_unchecked(b) }
+    }
+}
+
+impl<A, T> Borrow<T> for Box<A, T>
+where
+    T: ?Sized,
+    A: Alloc,
+{
+    fn borrow(&self) -> &T {
+        self
+    }
+}
+
+impl<A, T> BorrowMut<T> for Box<A, T>
+where
+    T: ?Sized,
+    A: Alloc,
+{
+    fn borrow_mut(&mut self) -> &mut T {
+        self
+    }
+}
+
+impl<A, T> AsRef<T> for Box<A, T>
+where
+    T: ?Sized,
+    A: Alloc,
+{
+    fn as_ref(&self) -> &T {
+        self
+    }
+}
+
+impl<A, T> AsMut<T> for Box<A, T>
+where
+    T: ?Sized,
+    A: Alloc,
+{
+    fn as_mut(&mut self) -> &mut T {
+        self
+    }
+}
+
+impl<A, T> fmt::Pointer for Box<A, T>
+where
+    T: ?Sized,
+    A: Alloc,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Pointer::fmt(&self.ptr.as_ptr(), f)
+    }
+}
+
+impl<A, T> Unpin for Box<A, T>
+where
+    T: ?Sized,
+    A: Alloc,
+{
+}
+
+// NOTE a blanket `I: ?Sized + Iterator` implementation would overlap with `IntoIterator for
+// Box<A, [T]>` so trait objects get their own implementations
+impl<A, I> Iterator for Box<A, I>
+where
+    I: Iterator,
+    A: Alloc,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        (**self).next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (**self).size_hint()
+    }
+
+    fn nth(&mut self, n: usize) -> Option<I::Item> {
+        (**self).nth(n)
+    }
+}
+
+macro_rules! dyn_iterator {
+    ($($ty:ty),*) => {
+        $(
+            impl<'a, A, T> Iterator for Box<A, $ty>
+            where
+                A: Alloc,
+            {
+                type Item = T;
+
+                fn next(&mut self) -> Option<T> {
+                    (**self).next()
+                }
+
+                fn size_hint(&self) -> (usize, Option<usize>) {
+                    (**self).size_hint()
+                }
+
+                fn nth(&mut self, n: usize) -> Option<T> {
+                    (**self).nth(n)
+                }
+            }
+        )*
+    };
+}
+
+dyn_iterator!(
+    dyn Iterator<Item = T> + 'a,
+    dyn Iterator<Item = T> + Send + 'a,
+    dyn DoubleEndedIterator<Item = T> + 'a,
+    dyn DoubleEndedIterator<Item = T> + Send + 'a,
+    dyn ExactSizeIterator<Item = T> + 'a,
+    dyn ExactSizeIterator<Item = T> + Send + 'a
+);
+
+impl<A, I> DoubleEndedIterator for Box<A, I>
+where
+    I: DoubleEndedIterator,
+    A: Alloc,
+{
+    fn next_back(&mut self) -> Option<I::Item> {
+        (**self).next_back()
+    }
+}
+
+impl<A, I> ExactSizeIterator for Box<A, I>
+where
+    I: ExactSizeIterator,
+    A: Alloc,
+{
+}
+
+impl<A, I> FusedIterator for Box<A, I>
+where
+    I: FusedIterator,
+    A: Alloc,
+{
+}
+
+impl<A, F> Future for Box<A, F>
+where
+    F: ?Sized + Future + Unpin,
+    A: Alloc,
+{
+    type Output = F::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<F::Output> {
+        F::poll(Pin::new(&mut *self), cx)
+    }
+}
+
+// NOTE `Box<A, dyn FnOnce()>` can't be called: moving an unsized value out of a user-defined box
+// is not possible. Boxed `FnMut` and `Fn` closures can be called through `FnOnce`
+#[cfg(feature = "nightly")]
+impl<A, Args, F> FnOnce<Args> for Box<A, F>
+where
+    Args: Tuple,
+    F: ?Sized + FnMut<Args>,
+    A: Alloc,
+{
+    type Output = F::Output;
+
+    extern "rust-call" fn call_once(mut self, args: Args) -> F::Output {
+        <F as FnMut<Args>>::call_mut(&mut *self, args)
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<A, Args, F> FnMut<Args> for Box<A, F>
+where
+    Args: Tuple,
+    F: ?Sized + FnMut<Args>,
+    A: Alloc,
+{
+    extern "rust-call" fn call_mut(&mut self, args: Args) -> F::Output {
+        <F as FnMut<Args>>::call_mut(self, args)
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<A, Args, F> Fn<Args> for Box<A, F>
+where
+    Args: Tuple,
+    F: ?Sized + Fn<Args>,
+    A: Alloc,
+{
+    extern "rust-call" fn call(&self, args: Args) -> F::Output {
+        <F as Fn<Args>>::call(self, args)
+    }
+}
@@ -0,0 +1,2184 @@
+//! A map based on a B-Tree.
+
+use core::{
+    alloc::Layout,
+    borrow::Borrow,
+    cmp::{self, Ordering},
+    fmt,
+    hash::{Hash, Hasher},
+    iter::{FromIterator, Peekable},
+    marker::PhantomData,
+    mem::{self, MaybeUninit},
+    ops::{self, Bound, RangeBounds},
+    ptr,
+};
+
+use alloc_many::Alloc;
+
+use crate::TryReserveError;
+
+const B: usize = 6;
+const CAPACITY: usize = 2 * B - 1;
+const MIN_LEN: usize = B - 1;
+
+#[repr(C)]
+struct LeafNode<K, V> {
+    parent: *mut InternalNode<K, V>,
+    parent_idx: u16,
+    len: u16,
+    keys: [MaybeUninit<K>; CAPACITY],
+    vals: [MaybeUninit<V>; CAPACITY],
+}
+
+// NOTE `repr(C)` so that a pointer to an `InternalNode` can be used as a pointer to a `LeafNode`
+#[repr(C)]
+struct InternalNode<K, V> {
+    data: LeafNode<K, V>,
+    edges: [*mut LeafNode<K, V>; CAPACITY + 1],
+}
+
+/// A map based on a B-Tree.
+///
+/// Nodes are allocated on the allocator `A`.
+pub struct BTreeMap<A, K, V>
+where
+    A: Alloc,
+{
+    // NOTE null when the map is empty
+    root: *mut LeafNode<K, V>,
+    height: usize,
+    length: usize,
+    _allocator: PhantomData<A>,
+    _marker: PhantomData<(K, V)>,
+}
+
+unsafe impl<A, K, V> Send for BTreeMap<A, K, V>
+where
+    A: Alloc,
+    K: Send,
+    V: Send,
+{
+}
+
+unsafe impl<A, K, V> Sync for BTreeMap<A, K, V>
+where
+    A: Alloc,
+    K: Sync,
+    V: Sync,
+{
+}
+
+/* Node helpers */
+// NOTE nodes are only accessed through raw pointers; references are only created to individual
+// keys and values
+
+unsafe fn len<K, V>(node: *const LeafNode<K, V>) -> usize {
+    usize::from((*node).len)
+}
+
+unsafe fn key<'a, K, V>(node: *const LeafNode<K, V>, idx: usize) -> &'a K {
+    &*(*node).keys[idx].as_ptr()
+}
+
+unsafe fn val<'a, K, V>(node: *const LeafNode<K, V>, idx: usize) -> &'a V {
+    &*(*node).vals[idx].as_ptr()
+}
+
+unsafe fn val_mut<'a, K, V>(node: *mut LeafNode<K, V>, idx: usize) -> &'a mut V {
+    &mut *(*node).vals[idx].as_mut_ptr()
+}
+
+unsafe fn edge<K, V>(node: *const LeafNode<K, V>, idx: usize) -> *mut LeafNode<K, V> {
+    (*(node as *const InternalNode<K, V>)).edges[idx]
+}
+
+unsafe fn read_kv<K, V>(node: *const LeafNode<K, V>, idx: usize) -> (K, V) {
+    (
+        (*node).keys[idx].as_ptr().read(),
+        (*node).vals[idx].as_ptr().read(),
+    )
+}
+
+unsafe fn write_kv<K, V>(node: *mut LeafNode<K, V>, idx: usize, k: K, v: V) {
+    (*node).keys[idx].as_mut_ptr().write(k);
+    (*node).vals[idx].as_mut_ptr().write(v);
+}
+
+// moves `count` KVs from `src[src_idx..]` to `dst[dst_idx..]`; the ranges may overlap
+unsafe fn move_kvs<K, V>(
+    src: *mut LeafNode<K, V>,
+    src_idx: usize,
+    dst: *mut LeafNode<K, V>,
+    dst_idx: usize,
+    count: usize,
+) {
+    // NOTE when moving within a node both pointers must be derived from the same borrow
+    let src_keys = (*src).keys.as_mut_ptr();
+    let dst_keys = if src == dst {
+        src_keys
+    } else {
+        (*dst).keys.as_mut_ptr()
+    };
+    ptr::copy(src_keys.add(src_idx), dst_keys.add(dst_idx), count);
+
+    let src_vals = (*src).vals.as_mut_ptr();
+    let dst_vals = if src == dst {
+        src_vals
+    } else {
+        (*dst).vals.as_mut_ptr()
+    };
+    ptr::copy(src_vals.add(src_idx), dst_vals.add(dst_idx), count);
+}
+
+// moves `count` edges from `src[src_idx..]` to `dst[dst_idx..]`; the ranges may overlap
+unsafe fn move_edges<K, V>(
+    src: *mut LeafNode<K, V>,
+    src_idx: usize,
+    dst: *mut LeafNode<K, V>,
+    dst_idx: usize,
+    count: usize,
+) {
+    let src_edges = (*(src as *mut InternalNode<K, V>)).edges.as_mut_ptr();
+    let dst_edges = if src == dst {
+        src_edges
+    } else {
+        (*(dst as *mut InternalNode<K, V>)).edges.as_mut_ptr()
+    };
+    ptr::copy(src_edges.add(src_idx), dst_edges.add(dst_idx), count);
+}
+
+// updates the parent links of `node`'s children at `start..end`
+unsafe fn correct_parent_links<K, V>(node: *mut LeafNode<K, V>, start: usize, end: usize) {
+    for i in start..end {
+        let child = edge(node, i);
+        (*child).parent = node as *mut InternalNode<K, V>;
+        (*child).parent_idx = i as u16;
+    }
+}
+
+fn leaf_layout<K, V>() -> Layout {
+    Layout::new::<LeafNode<K, V>>()
+}
+
+fn internal_layout<K, V>() -> Layout {
+    Layout::new::<InternalNode<K, V>>()
+}
+
+unsafe fn alloc_node<A, K, V>(layout: Layout) -> Result<*mut LeafNode<K, V>, TryReserveError>
+where
+    A: Alloc,
+{
+    let node = A::alloc(layout) as *mut LeafNode<K, V>;
+
+    if node.is_null() {
+        Err(TryReserveError::AllocError { layout })
+    } else {
+        (*node).parent = ptr::null_mut();
+        (*node).len = 0;
+        Ok(node)
+    }
+}
+
+unsafe fn dealloc_node<A, K, V>(node: *mut LeafNode<K, V>, height: usize)
+where
+    A: Alloc,
+{
+    let layout = if height == 0 {
+        leaf_layout::<K, V>()
+    } else {
+        internal_layout::<K, V>()
+    };
+
+    A::dealloc(node as *mut u8, layout)
+}
+
+// Allocates `count` nodes for the given `height`, chained through their `parent` field
+unsafe fn alloc_chain<A, K, V>(
+    count: usize,
+    height: usize,
+) -> Result<*mut LeafNode<K, V>, TryReserveError>
+where
+    A: Alloc,
+{
+    let layout = if height == 0 {
+        leaf_layout::<K, V>()
+    } else {
+        internal_layout::<K, V>()
+    };
+
+    let mut chain: *mut LeafNode<K, V> = ptr::null_mut();
+    for _ in 0..count {
+        match alloc_node::<A, K, V>(layout) {
+            Ok(node) => {
+                (*node).parent = chain as *mut InternalNode<K, V>;
+                chain = node;
+            }
+            Err(e) => {
+                dealloc_chain::<A, K, V>(chain, height);
+                return Err(e);
+            }
+        }
+    }
+
+    Ok(chain)
+}
+
+unsafe fn dealloc_chain<A, K, V>(mut chain: *mut LeafNode<K, V>, height: usize)
+where
+    A: Alloc,
+{
+    while !chain.is_null() {
+        let next = (*chain).parent as *mut LeafNode<K, V>;
+        dealloc_node::<A, K, V>(chain, height);
+        chain = next;
+    }
+}
+
+// Takes the first node of a non-empty `chain`
+unsafe fn pop_chain<K, V>(chain: &mut *mut LeafNode<K, V>) -> *mut LeafNode<K, V> {
+    let node = *chain;
+    *chain = (*node).parent as *mut LeafNode<K, V>;
+    (*node).parent = ptr::null_mut();
+    node
+}
+
+// Makes `child` the only edge of the empty internal `node`
+unsafe fn push_level<K, V>(node: *mut LeafNode<K, V>, child: *mut LeafNode<K, V>) {
+    (*(node as *mut InternalNode<K, V>)).edges[0] = child;
+    correct_parent_links(node, 0, 1);
+}
+
+// The number of KVs in the subtree rooted at `node`
+unsafe fn subtree_len<K, V>(node: *mut LeafNode<K, V>, height: usize) -> usize {
+    let len = len(node);
+
+    if height == 0 {
+        len
+    } else {
+        (0..=len).fold(len, |sum, i| sum + subtree_len(edge(node, i), height - 1))
+    }
+}
+
+/* Navigation */
+
+// A position between two adjacent KVs, always at the leaf level. In a B-Tree there's exactly one
+// leaf edge between each pair of adjacent KVs so two `Edge`s are equal iff they are the same
+// position
+struct Edge<K, V> {
+    node: *mut LeafNode<K, V>,
+    idx: usize,
+}
+
+impl<K, V> Clone for Edge<K, V> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<K, V> Copy for Edge<K, V> {}
+
+impl<K, V> PartialEq for Edge<K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.node == other.node && self.idx == other.idx
+    }
+}
+
+impl<K, V> Edge<K, V> {
+    fn dangling() -> Self {
+        Edge {
+            node: ptr::null_mut(),
+            idx: 0,
+        }
+    }
+}
+
+unsafe fn first_leaf_edge<K, V>(mut node: *mut LeafNode<K, V>, mut height: usize) -> Edge<K, V> {
+    while height > 0 {
+        node = edge(node, 0);
+        height -= 1;
+    }
+
+    Edge { node, idx: 0 }
+}
+
+unsafe fn last_leaf_edge<K, V>(mut node: *mut LeafNode<K, V>, mut height: usize) -> Edge<K, V> {
+    while height > 0 {
+        node = edge(node, len(node));
+        height -= 1;
+    }
+
+    Edge {
+        node,
+        idx: len(node),
+    }
+}
+
+type DeallocNode<K, V> = unsafe fn(*mut LeafNode<K, V>, usize);
+
+// Returns the KV right after `edge` and the leaf edge that follows that KV
+//
+// If `dealloc` is given the nodes that are left behind while ascending the tree are freed
+unsafe fn next_kv<K, V>(
+    edge: Edge<K, V>,
+    dealloc: Option<DeallocNode<K, V>>,
+) -> (*mut LeafNode<K, V>, usize, Edge<K, V>) {
+    let (mut node, mut idx, mut height) = (edge.node, edge.idx, 0);
+
+    while idx >= len(node) {
+        let parent = (*node).parent as *mut LeafNode<K, V>;
+        idx = usize::from((*node).parent_idx);
+        if let Some(dealloc) = dealloc {
+            dealloc(node, height);
+        }
+        node = parent;
+        height += 1;
+    }
+
+    let next = if height == 0 {
+        Edge { node, idx: idx + 1 }
+    } else {
+        first_leaf_edge(self::edge(node, idx + 1), height - 1)
+    };
+
+    (node, idx, next)
+}
+
+// Returns the KV right before `edge` and the leaf edge that precedes that KV
+//
+// If `dealloc` is given the nodes that are left behind while ascending the tree are freed
+unsafe fn next_back_kv<K, V>(
+    edge: Edge<K, V>,
+    dealloc: Option<DeallocNode<K, V>>,
+) -> (*mut LeafNode<K, V>, usize, Edge<K, V>) {
+    let (mut node, mut idx, mut height) = (edge.node, edge.idx, 0);
+
+    while idx == 0 {
+        let parent = (*node).parent as *mut LeafNode<K, V>;
+        idx = usize::from((*node).parent_idx);
+        if let Some(dealloc) = dealloc {
+            dealloc(node, height);
+        }
+        node = parent;
+        height += 1;
+    }
+
+    let idx = idx - 1;
+    let prev = if height == 0 {
+        Edge { node, idx }
+    } else {
+        last_leaf_edge(self::edge(node, idx), height - 1)
+    };
+
+    (node, idx, prev)
+}
+
+enum Search<K, V> {
+    Found {
+        node: *mut LeafNode<K, V>,
+        height: usize,
+        idx: usize,
+    },
+    GoDown(Edge<K, V>),
+}
+
+unsafe fn search_tree<K, V, Q>(
+    mut node: *mut LeafNode<K, V>,
+    mut height: usize,
+    q: &Q,
+) -> Search<K, V>
+where
+    K: Borrow<Q>,
+    Q: ?Sized + Ord,
+{
+    loop {
+        let len = len(node);
+        let mut idx = len;
+        for i in 0..len {
+            match q.cmp(key(node, i).borrow()) {
+                Ordering::Greater => {}
+                Ordering::Equal => {
+                    return Search::Found {
+                        node,
+                        height,
+                        idx: i,
+                    }
+                }
+                Ordering::Less => {
+                    idx = i;
+                    break;
+                }
+            }
+        }
+
+        if height == 0 {
+            return Search::GoDown(Edge { node, idx });
+        }
+
+        node = edge(node, idx);
+        height -= 1;
+    }
+}
+
+// the leaf edge right before the first key that satisfies the lower `bound`
+unsafe fn lower_bound_edge<K, V, Q>(
+    mut node: *mut LeafNode<K, V>,
+    mut height: usize,
+    bound: Bound<&Q>,
+) -> Edge<K, V>
+where
+    K: Borrow<Q>,
+    Q: ?Sized + Ord,
+{
+    loop {
+        let len = len(node);
+        let idx = match bound {
+            Bound::Unbounded => Some(0),
+            Bound::Included(q) => (0..len).find(|&i| key(node, i).borrow() >= q),
+            Bound::Excluded(q) => (0..len).find(|&i| key(node, i).borrow() > q),
+        }
+        .unwrap_or(len);
+
+        if height == 0 {
+            return Edge { node, idx };
+        }
+
+        node = edge(node, idx);
+        height -= 1;
+    }
+}
+
+// the leaf edge right after the last key that satisfies the upper `bound`
+unsafe fn upper_bound_edge<K, V, Q>(
+    mut node: *mut LeafNode<K, V>,
+    mut height: usize,
+    bound: Bound<&Q>,
+) -> Edge<K, V>
+where
+    K: Borrow<Q>,
+    Q: ?Sized + Ord,
+{
+    loop {
+        let len = len(node);
+        let idx = match bound {
+            Bound::Unbounded => None,
+            Bound::Included(q) => (0..len).find(|&i| key(node, i).borrow() > q),
+            Bound::Excluded(q) => (0..len).find(|&i| key(node, i).borrow() >= q),
+        }
+        .unwrap_or(len);
+
+        if height == 0 {
+            return Edge { node, idx };
+        }
+
+        node = edge(node, idx);
+        height -= 1;
+    }
+}
+
+/* Insertion */
+
+unsafe fn leaf_insert_fit<K, V>(node: *mut LeafNode<K, V>, idx: usize, k: K, v: V) {
+    let len = len(node);
+    move_kvs(node, idx, node, idx + 1, len - idx);
+    write_kv(node, idx, k, v);
+    (*node).len += 1;
+}
+
+unsafe fn internal_insert_fit<K, V>(
+    node: *mut LeafNode<K, V>,
+    idx: usize,
+    k: K,
+    v: V,
+    right: *mut LeafNode<K, V>,
+) {
+    let len = len(node);
+    move_kvs(node, idx, node, idx + 1, len - idx);
+    move_edges(node, idx + 1, node, idx + 2, len - idx);
+    write_kv(node, idx, k, v);
+    (*(node as *mut InternalNode<K, V>)).edges[idx + 1] = right;
+    (*node).len += 1;
+
+    correct_parent_links(node, idx + 1, len + 2);
+}
+
+// moves the upper half of a full `node` into the empty `right` node and returns the median KV
+unsafe fn split<K, V>(
+    node: *mut LeafNode<K, V>,
+    right: *mut LeafNode<K, V>,
+    height: usize,
+) -> (K, V) {
+    let median = read_kv(node, B - 1);
+
+    move_kvs(node, B, right, 0, CAPACITY - B);
+    if height > 0 {
+        move_edges(node, B, right, 0, CAPACITY - B + 1);
+        correct_parent_links(right, 0, CAPACITY - B + 1);
+    }
+
+    (*node).len = (B - 1) as u16;
+    (*right).len = (CAPACITY - B) as u16;
+
+    median
+}
+
+/* Removal */
+
+unsafe fn leaf_remove<K, V>(node: *mut LeafNode<K, V>, idx: usize) -> (K, V) {
+    let len = len(node);
+    let kv = read_kv(node, idx);
+    move_kvs(node, idx + 1, node, idx, len - idx - 1);
+    (*node).len -= 1;
+    kv
+}
+
+// restores the invariants of the underfull child `idx` of `parent`
+unsafe fn fix_underflow<A, K, V>(parent: *mut LeafNode<K, V>, height: usize, idx: usize)
+where
+    A: Alloc,
+{
+    let child = edge(parent, idx);
+    let child_height = height - 1;
+
+    if idx > 0 && len(edge(parent, idx - 1)) > MIN_LEN {
+        steal_left(parent, idx, child_height);
+    } else if idx < len(parent) && len(edge(parent, idx + 1)) > MIN_LEN {
+        steal_right(parent, idx, child_height);
+    } else if idx > 0 {
+        merge::<A, K, V>(parent, idx - 1, child_height);
+    } else {
+        debug_assert!(len(child) < MIN_LEN);
+        merge::<A, K, V>(parent, idx, child_height);
+    }
+}
+
+// rotates the last KV of the left sibling of child `idx` through `parent`
+unsafe fn steal_left<K, V>(parent: *mut LeafNode<K, V>, idx: usize, child_height: usize) {
+    let left = edge(parent, idx - 1);
+    let child = edge(parent, idx);
+    let left_len = len(left);
+    let child_len = len(child);
+
+    move_kvs(child, 0, child, 1, child_len);
+    let (k, v) = read_kv(left, left_len - 1);
+    let (pk, pv) = read_kv(parent, idx - 1);
+    write_kv(parent, idx - 1, k, v);
+    write_kv(child, 0, pk, pv);
+
+    if child_height > 0 {
+        move_edges(child, 0, child, 1, child_len + 1);
+        move_edges(left, left_len, child, 0, 1);
+        correct_parent_links(child, 0, child_len + 2);
+    }
+
+    (*left).len -= 1;
+    (*child).len += 1;
+}
+
+// rotates the first KV of the right sibling of child `idx` through `parent`
+unsafe fn steal_right<K, V>(parent: *mut LeafNode<K, V>, idx: usize, child_height: usize) {
+    let child = edge(parent, idx);
+    let right = edge(parent, idx + 1);
+    let right_len = len(right);
+    let child_len = len(child);
+
+    let (k, v) = read_kv(right, 0);
+    let (pk, pv) = read_kv(parent, idx);
+    write_kv(parent, idx, k, v);
+    write_kv(child, child_len, pk, pv);
+    move_kvs(right, 1, right, 0, right_len - 1);
+
+    if child_height > 0 {
+        move_edges(right, 0, child, child_len + 1, 1);
+        move_edges(right, 1, right, 0, right_len);
+        correct_parent_links(child, child_len + 1, child_len + 2);
+        correct_parent_links(right, 0, right_len);
+    }
+
+    (*right).len -= 1;
+    (*child).len += 1;
+}
+
+// merges the children `idx` and `idx + 1` of `parent`, and the KV that separates them, into
+// child `idx`
+unsafe fn merge<A, K, V>(parent: *mut LeafNode<K, V>, idx: usize, child_height: usize)
+where
+    A: Alloc,
+{
+    let left = edge(parent, idx);
+    let right = edge(parent, idx + 1);
+    let left_len = len(left);
+    let right_len = len(right);
+    let parent_len = len(parent);
+
+    let (pk, pv) = read_kv(parent, idx);
+    write_kv(left, left_len, pk, pv);
+    move_kvs(right, 0, left, left_len + 1, right_len);
+    if child_height > 0 {
+        move_edges(right, 0, left, left_len + 1, right_len + 1);
+        correct_parent_links(left, left_len + 1, left_len + right_len + 2);
+    }
+    (*left).len = (left_len + 1 + right_len) as u16;
+
+    move_kvs(parent, idx + 1, parent, idx, parent_len - idx - 1);
+    move_edges(parent, idx + 2, parent, idx + 1, parent_len - idx - 1);
+    correct_parent_links(parent, idx + 1, parent_len);
+    (*parent).len -= 1;
+
+    dealloc_node::<A, K, V>(right, child_height);
+}
+
+impl<A, K, V> BTreeMap<A, K, V>
+where
+    A: Alloc,
+{
+    /// Makes a new, empty `BTreeMap`.
+    ///
+    /// Does not allocate anything on its own.
+    pub fn new() -> Self {
+        BTreeMap {
+            root: ptr::null_mut(),
+            height: 0,
+            length: 0,
+            _allocator: PhantomData,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Clears the map, removing all elements.
+    pub fn clear(&mut self) {
+        *self = BTreeMap::new();
+    }
+
+    /// Returns the number of elements in the map.
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    /// Returns `true` if the map contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    fn search<Q>(&self, key: &Q) -> Option<Search<K, V>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Ord,
+    {
+        if self.root.is_null() {
+            None
+        } else {
+            unsafe { Some(search_tree(self.root, self.height, key)) }
+        }
+    }
+
+    /// Returns a reference to the value corresponding to the key.
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Ord,
+    {
+        self.get_key_value(key).map(|(_, v)| v)
+    }
+
+    /// Returns the key-value pair corresponding to the supplied key.
+    pub fn get_key_value<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Ord,
+    {
+        match self.search(key) {
+            Some(Search::Found { node, idx, .. }) => unsafe {
+                Some((self::key(node, idx), val(node, idx)))
+            },
+            _ => None,
+        }
+    }
+
+    /// Returns a mutable reference to the value corresponding to the key.
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Ord,
+    {
+        match self.search(key) {
+            Some(Search::Found { node, idx, .. }) => unsafe { Some(val_mut(node, idx)) },
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if the map contains a value for the specified key.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Ord,
+    {
+        self.get(key).is_some()
+    }
+
+    /// Inserts a key-value pair into the map.
+    ///
+    /// If the map did not have this key present, `None` is returned. If the map did have this key
+    /// present, the value is updated, and the old value is returned. The key is not updated.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V>
+    where
+        K: Ord,
+    {
+        match self.entry(key) {
+            Entry::Occupied(mut entry) => Some(entry.insert(value)),
+            Entry::Vacant(entry) => {
+                entry.insert(value);
+                None
+            }
+        }
+    }
+
+    /// Removes a key from the map, returning the value at the key if the key was previously in
+    /// the map.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Ord,
+    {
+        self.remove_entry(key).map(|(_, v)| v)
+    }
+
+    /// Removes a key from the map, returning the stored key and value if the key was previously
+    /// in the map.
+    pub fn remove_entry<Q>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Ord,
+    {
+        match self.search(key) {
+            Some(Search::Found { node, height, idx }) => unsafe {
+                Some(self.remove_kv(node, height, idx))
+            },
+            _ => None,
+        }
+    }
+
+    /// Gets the given key's corresponding entry in the map for in-place manipulation.
+    pub fn entry(&mut self, key: K) -> Entry<'_, A, K, V>
+    where
+        K: Ord,
+    {
+        match self.search(&key) {
+            Some(Search::Found { node, height, idx }) => Entry::Occupied(OccupiedEntry {
+                node,
+                height,
+                idx,
+                map: self,
+            }),
+            Some(Search::GoDown(edge)) => Entry::Vacant(VacantEntry {
+                key,
+                edge,
+                map: self,
+            }),
+            None => Entry::Vacant(VacantEntry {
+                key,
+                edge: Edge::dangling(),
+                map: self,
+            }),
+        }
+    }
+
+    /// Returns the first key-value pair in the map. The key in this pair is the minimum key in
+    /// the map.
+    pub fn first_key_value(&self) -> Option<(&K, &V)> {
+        self.iter().next()
+    }
+
+    /// Returns the last key-value pair in the map. The key in this pair is the maximum key in
+    /// the map.
+    pub fn last_key_value(&self) -> Option<(&K, &V)> {
+        self.iter().next_back()
+    }
+
+    /// Removes and returns the first element in the map.
+    pub fn pop_first(&mut self) -> Option<(K, V)> {
+        if self.root.is_null() {
+            None
+        } else {
+            unsafe {
+                let edge = first_leaf_edge(self.root, self.height);
+                Some(self.remove_kv(edge.node, 0, 0))
+            }
+        }
+    }
+
+    /// Removes and returns the last element in the map.
+    pub fn pop_last(&mut self) -> Option<(K, V)> {
+        if self.root.is_null() {
+            None
+        } else {
+            unsafe {
+                let edge = last_leaf_edge(self.root, self.height);
+                Some(self.remove_kv(edge.node, 0, edge.idx - 1))
+            }
+        }
+    }
+
+    /// Moves all elements from `other` into `self`, leaving `other` empty.
+    ///
+    /// If a key from `other` is already present in `self`, the respective value from `self` will
+    /// be overwritten with the respective value from `other`.
+    ///
+    /// The merged map is built from scratch in linear time. All its nodes are allocated before any
+    /// element is moved so both maps are left untouched if the allocator runs out of memory.
+    pub fn append(&mut self, other: &mut Self)
+    where
+        K: Ord,
+    {
+        if other.is_empty() {
+            return;
+        }
+
+        if self.is_empty() {
+            mem::swap(self, other);
+            return;
+        }
+
+        let (leaves, internals) = bulk_nodes(self.length + other.length);
+        unsafe {
+            let leaves = alloc_chain::<A, K, V>(leaves, 0)
+                .unwrap_or_else(|e| crate::handle_reserve_error::<A>(e));
+            let internals = match alloc_chain::<A, K, V>(internals, 1) {
+                Ok(internals) => internals,
+                Err(e) => {
+                    dealloc_chain::<A, K, V>(leaves, 0);
+                    crate::handle_reserve_error::<A>(e)
+                }
+            };
+
+            let (mut left, mut right) = (BTreeMap::new(), BTreeMap::new());
+            mem::swap(self, &mut left);
+            mem::swap(other, &mut right);
+            let merge = MergeIter {
+                left: left.into_iter().peekable(),
+                right: right.into_iter().peekable(),
+            };
+
+            let mut builder = BulkPush {
+                map: self,
+                leaf: ptr::null_mut(),
+                leaves,
+                internals,
+            };
+            for (k, v) in merge {
+                builder.push(k, v);
+            }
+        }
+    }
+
+    /// Splits the collection into two at the given key. Returns everything after the given key,
+    /// including the key.
+    ///
+    /// The tree is split along the search path of `key`, which needs one new node per level of the
+    /// tree. These nodes are allocated before the tree is modified so the map is left untouched if
+    /// the allocator runs out of memory.
+    pub fn split_off<Q>(&mut self, key: &Q) -> Self
+    where
+        K: Borrow<Q> + Ord,
+        Q: ?Sized + Ord,
+    {
+        let mut right = BTreeMap::new();
+
+        if self.root.is_null() {
+            return right;
+        }
+
+        unsafe {
+            // the left border of the new tree
+            let mut spine = alloc_node::<A, K, V>(leaf_layout::<K, V>())
+                .unwrap_or_else(|e| crate::handle_reserve_error::<A>(e));
+            let mut internals = match alloc_chain::<A, K, V>(self.height, 1) {
+                Ok(internals) => internals,
+                Err(e) => {
+                    dealloc_node::<A, K, V>(spine, 0);
+                    crate::handle_reserve_error::<A>(e)
+                }
+            };
+            for _ in 0..self.height {
+                let node = pop_chain(&mut internals);
+                push_level(node, spine);
+                spine = node;
+            }
+            right.root = spine;
+            right.height = self.height;
+
+            // move the KVs at or after `key`, and the edges between them, level by level
+            let (mut node, mut rnode, mut height) = (self.root, right.root, self.height);
+            loop {
+                let len = len(node);
+                let idx = (0..len)
+                    .find(|&i| self::key(node, i).borrow() >= key)
+                    .unwrap_or(len);
+
+                move_kvs(node, idx, rnode, 0, len - idx);
+                (*node).len = idx as u16;
+                (*rnode).len = (len - idx) as u16;
+
+                if height == 0 {
+                    break;
+                }
+
+                // the edge at `idx` is split at the next level; its right half is `rnode`'s first
+                // edge
+                move_edges(node, idx + 1, rnode, 1, len - idx);
+                correct_parent_links(rnode, 1, len - idx + 1);
+
+                node = edge(node, idx);
+                rnode = edge(rnode, 0);
+                height -= 1;
+            }
+
+            right.length = subtree_len(right.root, right.height);
+            self.length -= right.length;
+
+            self.fix_right_border();
+            right.fix_left_border();
+        }
+
+        right
+    }
+
+    /// Constructs a double-ended iterator over a sub-range of elements in the map.
+    ///
+    /// # Panics
+    ///
+    /// Panics if range `start > end`, or if range `start == end` and both bounds are `Excluded`.
+    pub fn range<T, R>(&self, range: R) -> Range<'_, K, V>
+    where
+        T: ?Sized + Ord,
+        K: Borrow<T>,
+        R: RangeBounds<T>,
+    {
+        let (front, back) = self.range_edges(range);
+
+        Range {
+            front,
+            back,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Constructs a mutable double-ended iterator over a sub-range of elements in the map.
+    ///
+    /// # Panics
+    ///
+    /// Panics if range `start > end`, or if range `start == end` and both bounds are `Excluded`.
+    pub fn range_mut<T, R>(&mut self, range: R) -> RangeMut<'_, K, V>
+    where
+        T: ?Sized + Ord,
+        K: Borrow<T>,
+        R: RangeBounds<T>,
+    {
+        let (front, back) = self.range_edges(range);
+
+        RangeMut {
+            front,
+            back,
+            _marker: PhantomData,
+        }
+    }
+
+    fn range_edges<T, R>(&self, range: R) -> (Edge<K, V>, Edge<K, V>)
+    where
+        T: ?Sized + Ord,
+        K: Borrow<T>,
+        R: RangeBounds<T>,
+    {
+        match (range.start_bound(), range.end_bound()) {
+            (Bound::Excluded(s), Bound::Excluded(e)) if s == e => {
+                panic!("range start and end are equal and excluded in BTreeMap")
+            }
+            (Bound::Included(s), Bound::Included(e))
+            | (Bound::Included(s), Bound::Excluded(e))
+            | (Bound::Excluded(s), Bound::Included(e))
+            | (Bound::Excluded(s), Bound::Excluded(e))
+                if s > e =>
+            {
+                panic!("range start is greater than range end in BTreeMap")
+            }
+            _ => {}
+        }
+
+        if self.root.is_null() {
+            (Edge::dangling(), Edge::dangling())
+        } else {
+            unsafe {
+                (
+                    lower_bound_edge(self.root, self.height, range.start_bound()),
+                    upper_bound_edge(self.root, self.height, range.end_bound()),
+                )
+            }
+        }
+    }
+
+    fn full_range(&self) -> (Edge<K, V>, Edge<K, V>) {
+        if self.root.is_null() {
+            (Edge::dangling(), Edge::dangling())
+        } else {
+            unsafe {
+                (
+                    first_leaf_edge(self.root, self.height),
+                    last_leaf_edge(self.root, self.height),
+                )
+            }
+        }
+    }
+
+    /// Gets an iterator over the entries of the map, sorted by key.
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        let (front, back) = self.full_range();
+
+        Iter {
+            range: Range {
+                front,
+                back,
+                _marker: PhantomData,
+            },
+            length: self.length,
+        }
+    }
+
+    /// Gets a mutable iterator over the entries of the map, sorted by key.
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+        let (front, back) = self.full_range();
+
+        IterMut {
+            range: RangeMut {
+                front,
+                back,
+                _marker: PhantomData,
+            },
+            length: self.length,
+        }
+    }
+
+    /// Gets an iterator over the keys of the map, in sorted order.
+    pub fn keys(&self) -> Keys<'_, K, V> {
+        Keys { inner: self.iter() }
+    }
+
+    /// Gets an iterator over the values of the map, in order by key.
+    pub fn values(&self) -> Values<'_, K, V> {
+        Values { inner: self.iter() }
+    }
+
+    /// Gets a mutable iterator over the values of the map, in order by key.
+    pub fn values_mut(&mut self) -> ValuesMut<'_, K, V> {
+        ValuesMut {
+            inner: self.iter_mut(),
+        }
+    }
+
+    // Inserts a new KV at the leaf `edge`
+    //
+    // All the nodes that the insertion may need are allocated upfront so the map is left untouched
+    // if any allocation fails
+    unsafe fn insert_at(&mut self, edge: Edge<K, V>, key: K, value: V) -> *mut V {
+        if self.root.is_null() {
+            let root = alloc_node::<A, K, V>(leaf_layout::<K, V>())
+                .unwrap_or_else(|e| crate::handle_reserve_error::<A>(e));
+
+            write_kv(root, 0, key, value);
+            (*root).len = 1;
+
+            self.root = root;
+            self.height = 0;
+            self.length = 1;
+
+            return val_mut(root, 0);
+        }
+
+        let leaf = edge.node;
+        if len(leaf) < CAPACITY {
+            leaf_insert_fit(leaf, edge.idx, key, value);
+            self.length += 1;
+            return val_mut(leaf, edge.idx);
+        }
+
+        // the leaf is full: count the splits we'll need and allocate the nodes
+        let mut internals = 0;
+        let mut node = leaf;
+        loop {
+            let parent = (*node).parent as *mut LeafNode<K, V>;
+            if parent.is_null() {
+                // new root
+                internals += 1;
+                break;
+            } else if len(parent) < CAPACITY {
+                break;
+            }
+
+            internals += 1;
+            node = parent;
+        }
+
+        let new_leaf = alloc_node::<A, K, V>(leaf_layout::<K, V>())
+            .unwrap_or_else(|e| crate::handle_reserve_error::<A>(e));
+        let mut spare = match alloc_chain::<A, K, V>(internals, 1) {
+            Ok(spare) => spare,
+            Err(e) => {
+                dealloc_node::<A, K, V>(new_leaf, 0);
+                crate::handle_reserve_error::<A>(e)
+            }
+        };
+
+        // split the leaf and insert the new KV in the right half
+        let (mut k, mut v) = split(leaf, new_leaf, 0);
+        let val = if edge.idx < B {
+            leaf_insert_fit(leaf, edge.idx, key, value);
+            val_mut(leaf, edge.idx) as *mut V
+        } else {
+            leaf_insert_fit(new_leaf, edge.idx - B, key, value);
+            val_mut(new_leaf, edge.idx - B) as *mut V
+        };
+
+        // propagate the median KV upwards
+        let mut node = leaf;
+        let mut right = new_leaf;
+        let mut height = 0;
+        loop {
+            let parent = (*node).parent as *mut LeafNode<K, V>;
+
+            if parent.is_null() {
+                let root = pop_chain(&mut spare);
+                write_kv(root, 0, k, v);
+                (*(root as *mut InternalNode<K, V>)).edges[0] = node;
+                (*(root as *mut InternalNode<K, V>)).edges[1] = right;
+                (*root).len = 1;
+                correct_parent_links(root, 0, 2);
+
+                self.root = root;
+                self.height += 1;
+                break;
+            }
+
+            let idx = usize::from((*node).parent_idx);
+            if len(parent) < CAPACITY {
+                internal_insert_fit(parent, idx, k, v, right);
+                break;
+            }
+
+            let new_internal = pop_chain(&mut spare);
+            let (mk, mv) = split(parent, new_internal, height + 1);
+            if idx < B {
+                internal_insert_fit(parent, idx, k, v, right);
+            } else {
+                internal_insert_fit(new_internal, idx - B, k, v, right);
+            }
+
+            k = mk;
+            v = mv;
+            node = parent;
+            right = new_internal;
+            height += 1;
+        }
+
+        self.length += 1;
+        val
+    }
+
+    // Removes the KV at `idx` in `node`, which is at the given `height`
+    unsafe fn remove_kv(&mut self, node: *mut LeafNode<K, V>, height: usize, idx: usize) -> (K, V) {
+        let (leaf, leaf_idx) = if height == 0 {
+            (node, idx)
+        } else {
+            // swap the KV with its predecessor, which lives in a leaf
+            let edge = last_leaf_edge(edge(node, idx), height - 1);
+            let leaf_idx = edge.idx - 1;
+            ptr::swap(
+                (*node).keys[idx].as_mut_ptr(),
+                (*edge.node).keys[leaf_idx].as_mut_ptr(),
+            );
+            ptr::swap(
+                (*node).vals[idx].as_mut_ptr(),
+                (*edge.node).vals[leaf_idx].as_mut_ptr(),
+            );
+            (edge.node, leaf_idx)
+        };
+
+        let kv = leaf_remove(leaf, leaf_idx);
+        self.length -= 1;
+
+        // rebalance the tree from the leaf upwards
+        let mut node = leaf;
+        let mut height = 0;
+        loop {
+            let parent = (*node).parent as *mut LeafNode<K, V>;
+            if parent.is_null() || len(node) >= MIN_LEN {
+                break;
+            }
+
+            fix_underflow::<A, K, V>(parent, height + 1, usize::from((*node).parent_idx));
+            node = parent;
+            height += 1;
+        }
+
+        // shrink the tree
+        let root = self.root;
+        if len(root) == 0 {
+            let height = self.height;
+            if height == 0 {
+                self.root = ptr::null_mut();
+            } else {
+                self.root = edge(root, 0);
+                (*self.root).parent = ptr::null_mut();
+                self.height -= 1;
+            }
+
+            dealloc_node::<A, K, V>(root, height);
+        }
+
+        kv
+    }
+
+    // Removes the empty nodes at the top of the tree
+    unsafe fn trim_root(&mut self) {
+        if self.root.is_null() {
+            return;
+        }
+
+        while self.height > 0 && len(self.root) == 0 {
+            let root = self.root;
+            self.root = edge(root, 0);
+            (*self.root).parent = ptr::null_mut();
+            self.height -= 1;
+
+            dealloc_node::<A, K, V>(root, self.height + 1);
+        }
+
+        if self.height == 0 && len(self.root) == 0 {
+            dealloc_node::<A, K, V>(self.root, 0);
+            self.root = ptr::null_mut();
+        }
+    }
+
+    // Restores the invariants of the nodes on the right border of the tree, which may be underfull
+    // or even empty, as long as all the other nodes are valid
+    //
+    // Every border node below the root is given more than `MIN_LEN` KVs so that merging one of its
+    // children doesn't leave it underfull
+    unsafe fn fix_right_border(&mut self) {
+        self.trim_root();
+
+        let (mut node, mut height) = (self.root, self.height);
+        while height > 0 {
+            let idx = len(node);
+            let child = edge(node, idx);
+
+            if len(child) <= MIN_LEN {
+                if len(edge(node, idx - 1)) + 1 + len(child) <= CAPACITY {
+                    merge::<A, K, V>(node, idx - 1, height - 1);
+
+                    if node == self.root && len(node) == 0 {
+                        self.trim_root();
+                        node = self.root;
+                        height = self.height;
+                        continue;
+                    }
+                } else {
+                    while len(child) <= MIN_LEN {
+                        steal_left(node, idx, height - 1);
+                    }
+                }
+            }
+
+            node = edge(node, len(node));
+            height -= 1;
+        }
+    }
+
+    // Like `fix_right_border` but for the left border of the tree
+    unsafe fn fix_left_border(&mut self) {
+        self.trim_root();
+
+        let (mut node, mut height) = (self.root, self.height);
+        while height > 0 {
+            let child = edge(node, 0);
+
+            if len(child) <= MIN_LEN {
+                if len(child) + 1 + len(edge(node, 1)) <= CAPACITY {
+                    merge::<A, K, V>(node, 0, height - 1);
+
+                    if node == self.root && len(node) == 0 {
+                        self.trim_root();
+                        node = self.root;
+                        height = self.height;
+                        continue;
+                    }
+                } else {
+                    while len(child) <= MIN_LEN {
+                        steal_right(node, 0, height - 1);
+                    }
+                }
+            }
+
+            node = edge(node, 0);
+            height -= 1;
+        }
+    }
+}
+
+// The number of leaves and internal nodes that `BulkPush` needs to build a tree of `n` KVs
+fn bulk_nodes(n: usize) -> (usize, usize) {
+    // the lengths of the nodes on the right border, from the leaf up
+    let mut lens = [0; mem::size_of::<usize>() * 8];
+    let mut height = 0;
+    let (mut leaves, mut internals) = (0, 0);
+
+    for i in 0..n {
+        if i == 0 {
+            leaves += 1;
+        }
+
+        if lens[0] < CAPACITY {
+            lens[0] += 1;
+            continue;
+        }
+
+        // the KV goes into the lowest border node that has room, or a new root
+        let mut open = 1;
+        while open <= height && lens[open] == CAPACITY {
+            open += 1;
+        }
+        if open > height {
+            height += 1;
+            internals += 1;
+        }
+        lens[open] += 1;
+
+        // followed by a new right border of empty nodes
+        for len in &mut lens[..open] {
+            *len = 0;
+        }
+        internals += open - 1;
+        leaves += 1;
+    }
+
+    (leaves, internals)
+}
+
+// Appends sorted KVs to the right border of an initially empty map using preallocated nodes
+//
+// The right border is fixed when the builder is dropped, even if the source of the KVs panics
+struct BulkPush<'a, A, K, V>
+where
+    A: Alloc,
+{
+    map: &'a mut BTreeMap<A, K, V>,
+    // the last leaf of the tree
+    leaf: *mut LeafNode<K, V>,
+    leaves: *mut LeafNode<K, V>,
+    internals: *mut LeafNode<K, V>,
+}
+
+impl<A, K, V> BulkPush<'_, A, K, V>
+where
+    A: Alloc,
+{
+    unsafe fn push(&mut self, k: K, v: V) {
+        let map = &mut *self.map;
+
+        if map.root.is_null() {
+            self.leaf = pop_chain(&mut self.leaves);
+            map.root = self.leaf;
+            map.height = 0;
+        }
+
+        let len = len(self.leaf);
+        if len < CAPACITY {
+            write_kv(self.leaf, len, k, v);
+            (*self.leaf).len += 1;
+        } else {
+            // find the lowest node on the right border that has room, or add a new root
+            let mut open = (*self.leaf).parent as *mut LeafNode<K, V>;
+            let mut height = 1;
+            loop {
+                if open.is_null() {
+                    open = pop_chain(&mut self.internals);
+                    push_level(open, map.root);
+                    map.root = open;
+                    map.height += 1;
+                    break;
+                } else if self::len(open) < CAPACITY {
+                    break;
+                }
+
+                open = (*open).parent as *mut LeafNode<K, V>;
+                height += 1;
+            }
+
+            // hang a new right border of empty nodes from it
+            self.leaf = pop_chain(&mut self.leaves);
+            let mut right = self.leaf;
+            for _ in 1..height {
+                let node = pop_chain(&mut self.internals);
+                push_level(node, right);
+                right = node;
+            }
+
+            let idx = self::len(open);
+            write_kv(open, idx, k, v);
+            (*(open as *mut InternalNode<K, V>)).edges[idx + 1] = right;
+            (*open).len += 1;
+            correct_parent_links(open, idx + 1, idx + 2);
+        }
+
+        map.length += 1;
+    }
+}
+
+impl<A, K, V> Drop for BulkPush<'_, A, K, V>
+where
+    A: Alloc,
+{
+    fn drop(&mut self) {
+        unsafe {
+            self.map.fix_right_border();
+
+            dealloc_chain::<A, K, V>(self.leaves, 0);
+            dealloc_chain::<A, K, V>(self.internals, 1);
+        }
+    }
+}
+
+// Merges two sorted iterators; on equal keys the KV from `right` is yielded and the one from `left`
+// is dropped
+struct MergeIter<I>
+where
+    I: Iterator,
+{
+    left: Peekable<I>,
+    right: Peekable<I>,
+}
+
+impl<I, K, V> Iterator for MergeIter<I>
+where
+    I: Iterator<Item = (K, V)>,
+    K: Ord,
+{
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<(K, V)> {
+        let order = match (self.left.peek(), self.right.peek()) {
+            (Some((l, _)), Some((r, _))) => l.cmp(r),
+            (Some(_), None) => Ordering::Less,
+            (None, _) => Ordering::Greater,
+        };
+
+        match order {
+            Ordering::Less => self.left.next(),
+            Ordering::Greater => self.right.next(),
+            Ordering::Equal => {
+                drop(self.left.next());
+                self.right.next()
+            }
+        }
+    }
+}
+
+/// A view into a single entry in a map, which may either be vacant or occupied.
+///
+/// This `enum` is constructed from the `entry` method on `BTreeMap`.
+pub enum Entry<'a, A, K, V>
+where
+    A: Alloc,
+{
+    /// A vacant entry.
+    Vacant(VacantEntry<'a, A, K, V>),
+    /// An occupied entry.
+    Occupied(OccupiedEntry<'a, A, K, V>),
+}
+
+/// A view into a vacant entry in a `BTreeMap`. It is part of the `Entry` enum.
+pub struct VacantEntry<'a, A, K, V>
+where
+    A: Alloc,
+{
+    key: K,
+    edge: Edge<K, V>,
+    map: &'a mut BTreeMap<A, K, V>,
+}
+
+/// A view into an occupied entry in a `BTreeMap`. It is part of the `Entry` enum.
+pub struct OccupiedEntry<'a, A, K, V>
+where
+    A: Alloc,
+{
+    node: *mut LeafNode<K, V>,
+    height: usize,
+    idx: usize,
+    map: &'a mut BTreeMap<A, K, V>,
+}
+
+impl<'a, A, K, V> Entry<'a, A, K, V>
+where
+    A: Alloc,
+    K: Ord,
+{
+    /// Ensures a value is in the entry by inserting the default if empty, and returns a mutable
+    /// reference to the value in the entry.
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default),
+        }
+    }
+
+    /// Ensures a value is in the entry by inserting the result of the default function if empty,
+    /// and returns a mutable reference to the value in the entry.
+    pub fn or_insert_with<F>(self, default: F) -> &'a mut V
+    where
+        F: FnOnce() -> V,
+    {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    /// Ensures a value is in the entry by inserting the default value if empty, and returns a
+    /// mutable reference to the value in the entry.
+    pub fn or_default(self) -> &'a mut V
+    where
+        V: Default,
+    {
+        self.or_insert_with(V::default)
+    }
+
+    /// Returns a reference to this entry's key.
+    pub fn key(&self) -> &K {
+        match self {
+            Entry::Occupied(entry) => entry.key(),
+            Entry::Vacant(entry) => entry.key(),
+        }
+    }
+
+    /// Provides in-place mutable access to an occupied entry before any potential inserts into
+    /// the map.
+    pub fn and_modify<F>(self, f: F) -> Self
+    where
+        F: FnOnce(&mut V),
+    {
+        match self {
+            Entry::Occupied(mut entry) => {
+                f(entry.get_mut());
+                Entry::Occupied(entry)
+            }
+            Entry::Vacant(entry) => Entry::Vacant(entry),
+        }
+    }
+}
+
+impl<'a, A, K, V> VacantEntry<'a, A, K, V>
+where
+    A: Alloc,
+    K: Ord,
+{
+    /// Gets a reference to the key that would be used when inserting a value through the
+    /// `VacantEntry`.
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Take ownership of the key.
+    pub fn into_key(self) -> K {
+        self.key
+    }
+
+    /// Sets the value of the entry with the `VacantEntry`'s key, and returns a mutable reference
+    /// to it.
+    pub fn insert(self, value: V) -> &'a mut V {
+        unsafe { &mut *self.map.insert_at(self.edge, self.key, value) }
+    }
+}
+
+impl<'a, A, K, V> OccupiedEntry<'a, A, K, V>
+where
+    A: Alloc,
+{
+    /// Gets a reference to the key in the entry.
+    pub fn key(&self) -> &K {
+        unsafe { key(self.node, self.idx) }
+    }
+
+    /// Gets a reference to the value in the entry.
+    pub fn get(&self) -> &V {
+        unsafe { val(self.node, self.idx) }
+    }
+
+    /// Gets a mutable reference to the value in the entry.
+    pub fn get_mut(&mut self) -> &mut V {
+        unsafe { val_mut(self.node, self.idx) }
+    }
+
+    /// Converts the entry into a mutable reference to its value.
+    pub fn into_mut(self) -> &'a mut V {
+        unsafe { val_mut(self.node, self.idx) }
+    }
+
+    /// Sets the value of the entry with the `OccupiedEntry`'s key, and returns the entry's old
+    /// value.
+    pub fn insert(&mut self, value: V) -> V {
+        mem::replace(self.get_mut(), value)
+    }
+
+    /// Takes the value of the entry out of the map, and returns it.
+    pub fn remove(self) -> V {
+        self.remove_entry().1
+    }
+
+    /// Take ownership of the key and value from the map.
+    pub fn remove_entry(self) -> (K, V) {
+        unsafe { self.map.remove_kv(self.node, self.height, self.idx) }
+    }
+}
+
+/// An iterator over a sub-range of entries in a `BTreeMap`.
+///
+/// This `struct` is created by the `range` method on `BTreeMap`.
+pub struct Range<'a, K, V> {
+    front: Edge<K, V>,
+    back: Edge<K, V>,
+    _marker: PhantomData<&'a (K, V)>,
+}
+
+impl<'a, K, V> Clone for Range<'a, K, V> {
+    fn clone(&self) -> Self {
+        Range {
+            front: self.front,
+            back: self.back,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, K, V> Iterator for Range<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<(&'a K, &'a V)> {
+        if self.front == self.back {
+            None
+        } else {
+            unsafe {
+                let (node, idx, next) = next_kv(self.front, None);
+                self.front = next;
+                Some((key(node, idx), val(node, idx)))
+            }
+        }
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for Range<'a, K, V> {
+    fn next_back(&mut self) -> Option<(&'a K, &'a V)> {
+        if self.front == self.back {
+            None
+        } else {
+            unsafe {
+                let (node, idx, prev) = next_back_kv(self.back, None);
+                self.back = prev;
+                Some((key(node, idx), val(node, idx)))
+            }
+        }
+    }
+}
+
+/// A mutable iterator over a sub-range of entries in a `BTreeMap`.
+///
+/// This `struct` is created by the `range_mut` method on `BTreeMap`.
+pub struct RangeMut<'a, K, V> {
+    front: Edge<K, V>,
+    back: Edge<K, V>,
+    _marker: PhantomData<&'a mut (K, V)>,
+}
+
+impl<'a, K, V> Iterator for RangeMut<'a, K, V> {
+    type Item = (&'a K, &'a mut V);
+
+    fn next(&mut self) -> Option<(&'a K, &'a mut V)> {
+        if self.front == self.back {
+            None
+        } else {
+            unsafe {
+                let (node, idx, next) = next_kv(self.front, None);
+                self.front = next;
+                Some((key(node, idx), val_mut(node, idx)))
+            }
+        }
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for RangeMut<'a, K, V> {
+    fn next_back(&mut self) -> Option<(&'a K, &'a mut V)> {
+        if self.front == self.back {
+            None
+        } else {
+            unsafe {
+                let (node, idx, prev) = next_back_kv(self.back, None);
+                self.back = prev;
+                Some((key(node, idx), val_mut(node, idx)))
+            }
+        }
+    }
+}
+
+/// An iterator over the entries of a `BTreeMap`.
+///
+/// This `struct` is created by the `iter` method on `BTreeMap`.
+pub struct Iter<'a, K, V> {
+    range: Range<'a, K, V>,
+    length: usize,
+}
+
+impl<'a, K, V> Clone for Iter<'a, K, V> {
+    fn clone(&self) -> Self {
+        Iter {
+            range: self.range.clone(),
+            length: self.length,
+        }
+    }
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<(&'a K, &'a V)> {
+        if self.length == 0 {
+            None
+        } else {
+            self.length -= 1;
+            self.range.next()
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.length, Some(self.length))
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for Iter<'a, K, V> {
+    fn next_back(&mut self) -> Option<(&'a K, &'a V)> {
+        if self.length == 0 {
+            None
+        } else {
+            self.length -= 1;
+            self.range.next_back()
+        }
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for Iter<'a, K, V> {}
+
+/// A mutable iterator over the entries of a `BTreeMap`.
+///
+/// This `struct` is created by the `iter_mut` method on `BTreeMap`.
+pub struct IterMut<'a, K, V> {
+    range: RangeMut<'a, K, V>,
+    length: usize,
+}
+
+impl<'a, K, V> Iterator for IterMut<'a, K, V> {
+    type Item = (&'a K, &'a mut V);
+
+    fn next(&mut self) -> Option<(&'a K, &'a mut V)> {
+        if self.length == 0 {
+            None
+        } else {
+            self.length -= 1;
+            self.range.next()
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.length, Some(self.length))
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for IterMut<'a, K, V> {
+    fn next_back(&mut self) -> Option<(&'a K, &'a mut V)> {
+        if self.length == 0 {
+            None
+        } else {
+            self.length -= 1;
+            self.range.next_back()
+        }
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for IterMut<'a, K, V> {}
+
+/// An iterator over the keys of a `BTreeMap`.
+///
+/// This `struct` is created by the `keys` method on `BTreeMap`.
+pub struct Keys<'a, K, V> {
+    inner: Iter<'a, K, V>,
+}
+
+impl<'a, K, V> Clone for Keys<'a, K, V> {
+    fn clone(&self) -> Self {
+        Keys {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<'a, K, V> Iterator for Keys<'a, K, V> {
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<&'a K> {
+        self.inner.next().map(|(k, _)| k)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for Keys<'a, K, V> {
+    fn next_back(&mut self) -> Option<&'a K> {
+        self.inner.next_back().map(|(k, _)| k)
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for Keys<'a, K, V> {}
+
+/// An iterator over the values of a `BTreeMap`.
+///
+/// This `struct` is created by the `values` method on `BTreeMap`.
+pub struct Values<'a, K, V> {
+    inner: Iter<'a, K, V>,
+}
+
+impl<'a, K, V> Iterator for Values<'a, K, V> {
+    type Item = &'a V;
+
+    fn next(&mut self) -> Option<&'a V> {
+        self.inner.next().map(|(_, v)| v)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for Values<'a, K, V> {
+    fn next_back(&mut self) -> Option<&'a V> {
+        self.inner.next_back().map(|(_, v)| v)
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for Values<'a, K, V> {}
+
+/// A mutable iterator over the values of a `BTreeMap`.
+///
+/// This `struct` is created by the `values_mut` method on `BTreeMap`.
+pub struct ValuesMut<'a, K, V> {
+    inner: IterMut<'a, K, V>,
+}
+
+impl<'a, K, V> Iterator for ValuesMut<'a, K, V> {
+    type Item = &'a mut V;
+
+    fn next(&mut self) -> Option<&'a mut V> {
+        self.inner.next().map(|(_, v)| v)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for ValuesMut<'a, K, V> {
+    fn next_back(&mut self) -> Option<&'a mut V> {
+        self.inner.next_back().map(|(_, v)| v)
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for ValuesMut<'a, K, V> {}
+
+/// An owning iterator over the entries of a `BTreeMap`.
+///
+/// Nodes are freed as soon as all their entries have been yielded.
+///
+/// This `struct` is created by the `into_iter` method on `BTreeMap`.
+pub struct IntoIter<A, K, V>
+where
+    A: Alloc,
+{
+    front: Edge<K, V>,
+    back: Edge<K, V>,
+    length: usize,
+    _allocator: PhantomData<A>,
+}
+
+impl<A, K, V> Iterator for IntoIter<A, K, V>
+where
+    A: Alloc,
+{
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<(K, V)> {
+        if self.length == 0 {
+            None
+        } else {
+            self.length -= 1;
+
+            unsafe {
+                let (node, idx, next) = next_kv(self.front, Some(dealloc_node::<A, K, V>));
+                self.front = next;
+                Some(read_kv(node, idx))
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.length, Some(self.length))
+    }
+}
+
+impl<A, K, V> DoubleEndedIterator for IntoIter<A, K, V>
+where
+    A: Alloc,
+{
+    fn next_back(&mut self) -> Option<(K, V)> {
+        if self.length == 0 {
+            None
+        } else {
+            self.length -= 1;
+
+            unsafe {
+                let (node, idx, prev) = next_back_kv(self.back, Some(dealloc_node::<A, K, V>));
+                self.back = prev;
+                Some(read_kv(node, idx))
+            }
+        }
+    }
+}
+
+impl<A, K, V> ExactSizeIterator for IntoIter<A, K, V> where A: Alloc {}
+
+impl<A, K, V> Drop for IntoIter<A, K, V>
+where
+    A: Alloc,
+{
+    fn drop(&mut self) {
+        self.for_each(drop);
+
+        // free the nodes that are still allocated: the path from the last position to the root
+        unsafe {
+            let mut node = self.front.node;
+            let mut height = 0;
+            while !node.is_null() {
+                let parent = (*node).parent as *mut LeafNode<K, V>;
+                dealloc_node::<A, K, V>(node, height);
+                node = parent;
+                height += 1;
+            }
+        }
+    }
+}
+
+impl<A, K, V> Drop for BTreeMap<A, K, V>
+where
+    A: Alloc,
+{
+    fn drop(&mut self) {
+        unsafe { drop(ptr::read(self).into_iter()) }
+    }
+}
+
+impl<A, K, V> IntoIterator for BTreeMap<A, K, V>
+where
+    A: Alloc,
+{
+    type Item = (K, V);
+    type IntoIter = IntoIter<A, K, V>;
+
+    fn into_iter(self) -> IntoIter<A, K, V> {
+        let (front, back) = self.full_range();
+        let length = self.length;
+        mem::forget(self);
+
+        IntoIter {
+            front,
+            back,
+            length,
+            _allocator: PhantomData,
+        }
+    }
+}
+
+impl<'a, A, K, V> IntoIterator for &'a BTreeMap<A, K, V>
+where
+    A: Alloc,
+{
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Iter<'a, K, V> {
+        self.iter()
+    }
+}
+
+impl<'a, A, K, V> IntoIterator for &'a mut BTreeMap<A, K, V>
+where
+    A: Alloc,
+{
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = IterMut<'a, K, V>;
+
+    fn into_iter(self) -> IterMut<'a, K, V> {
+        self.iter_mut()
+    }
+}
+
+impl<A, K, V> Extend<(K, V)> for BTreeMap<A, K, V>
+where
+    A: Alloc,
+    K: Ord,
+{
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        for (k, v) in iter {
+            self.insert(k, v);
+        }
+    }
+}
+
+impl<'a, A, K, V> Extend<(&'a K, &'a V)> for BTreeMap<A, K, V>
+where
+    A: Alloc,
+    K: Ord + Copy,
+    V: Copy,
+{
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = (&'a K, &'a V)>,
+    {
+        self.extend(iter.into_iter().map(|(&k, &v)| (k, v)))
+    }
+}
+
+impl<A, K, V> FromIterator<(K, V)> for BTreeMap<A, K, V>
+where
+    A: Alloc,
+    K: Ord,
+{
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        let mut map = BTreeMap::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<A, K, V> Clone for BTreeMap<A, K, V>
+where
+    A: Alloc,
+    K: Clone + Ord,
+    V: Clone,
+{
+    fn clone(&self) -> Self {
+        self.iter().map(|(k, v)| (k.clone(), v.clone())).collect()
+    }
+}
+
+impl<A, K, V> Default for BTreeMap<A, K, V>
+where
+    A: Alloc,
+{
+    fn default() -> Self {
+        BTreeMap::new()
+    }
+}
+
+impl<A, K, V> fmt::Debug for BTreeMap<A, K, V>
+where
+    A: Alloc,
+    K: fmt::Debug,
+    V: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<A, K, V> Hash for BTreeMap<A, K, V>
+where
+    A: Alloc,
+    K: Hash,
+    V: Hash,
+{
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: Hasher,
+    {
+        self.length.hash(state);
+        for kv in self {
+            kv.hash(state);
+        }
+    }
+}
+
+impl<A, K, Q, V> ops::Index<&Q> for BTreeMap<A, K, V>
+where
+    A: Alloc,
+    K: Borrow<Q>,
+    Q: ?Sized + Ord,
+{
+    type Output = V;
+
+    fn index(&self, key: &Q) -> &V {
+        self.get(key).expect("no entry found for key")
+    }
+}
+
+impl<A, K, V> Eq for BTreeMap<A, K, V>
+where
+    A: Alloc,
+    K: Eq,
+    V: Eq,
+{
+}
+
+impl<A, B, K, V> PartialEq<BTreeMap<B, K, V>> for BTreeMap<A, K, V>
+where
+    A: Alloc,
+    B: Alloc,
+    K: PartialEq,
+    V: PartialEq,
+{
+    fn eq(&self, other: &BTreeMap<B, K, V>) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl<A, K, V> Ord for BTreeMap<A, K, V>
+where
+    A: Alloc,
+    K: Ord,
+    V: Ord,
+{
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<A, B, K, V> PartialOrd<BTreeMap<B, K, V>> for BTreeMap<A, K, V>
+where
+    A: Alloc,
+    B: Alloc,
+    K: PartialOrd,
+    V: PartialOrd,
+{
+    fn partial_cmp(&self, other: &BTreeMap<B, K, V>) -> Option<cmp::Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
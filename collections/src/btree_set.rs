@@ -0,0 +1,422 @@
+//! A set based on a B-Tree.
+
+use core::{
+    borrow::Borrow,
+    cmp, fmt,
+    hash::{Hash, Hasher},
+    iter::FromIterator,
+    ops::RangeBounds,
+};
+
+use alloc_many::Alloc;
+
+use crate::btree_map::{self, BTreeMap};
+
+/// A set based on a B-Tree.
+///
+/// Nodes are allocated on the allocator `A`.
+pub struct BTreeSet<A, T>
+where
+    A: Alloc,
+{
+    map: BTreeMap<A, T, ()>,
+}
+
+impl<A, T> BTreeSet<A, T>
+where
+    A: Alloc,
+{
+    /// Makes a new, empty `BTreeSet`.
+    ///
+    /// Does not allocate anything on its own.
+    pub fn new() -> Self {
+        BTreeSet {
+            map: BTreeMap::new(),
+        }
+    }
+
+    /// Clears the set, removing all values.
+    pub fn clear(&mut self) {
+        self.map.clear()
+    }
+
+    /// Returns the number of elements in the set.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns `true` if the set contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Returns `true` if the set contains a value.
+    pub fn contains<Q>(&self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: ?Sized + Ord,
+    {
+        self.map.contains_key(value)
+    }
+
+    /// Returns a reference to the value in the set, if any, that is equal to the given value.
+    pub fn get<Q>(&self, value: &Q) -> Option<&T>
+    where
+        T: Borrow<Q>,
+        Q: ?Sized + Ord,
+    {
+        self.map.get_key_value(value).map(|(k, _)| k)
+    }
+
+    /// Adds a value to the set.
+    ///
+    /// If the set did not have this value present, `true` is returned.
+    pub fn insert(&mut self, value: T) -> bool
+    where
+        T: Ord,
+    {
+        match self.map.entry(value) {
+            btree_map::Entry::Occupied(_) => false,
+            btree_map::Entry::Vacant(entry) => {
+                entry.insert(());
+                true
+            }
+        }
+    }
+
+    /// If the set contains a value equal to `value`, removes it from the set and drops it.
+    /// Returns whether such a value was present.
+    pub fn remove<Q>(&mut self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: ?Sized + Ord,
+    {
+        self.map.remove(value).is_some()
+    }
+
+    /// Removes and returns the value in the set, if any, that is equal to the given one.
+    pub fn take<Q>(&mut self, value: &Q) -> Option<T>
+    where
+        T: Borrow<Q>,
+        Q: ?Sized + Ord,
+    {
+        self.map.remove_entry(value).map(|(k, _)| k)
+    }
+
+    /// Returns a reference to the first value in the set, if any. This value is always the
+    /// minimum of all values in the set.
+    pub fn first(&self) -> Option<&T> {
+        self.map.first_key_value().map(|(k, _)| k)
+    }
+
+    /// Returns a reference to the last value in the set, if any. This value is always the
+    /// maximum of all values in the set.
+    pub fn last(&self) -> Option<&T> {
+        self.map.last_key_value().map(|(k, _)| k)
+    }
+
+    /// Removes the first value from the set and returns it, if any.
+    pub fn pop_first(&mut self) -> Option<T> {
+        self.map.pop_first().map(|(k, _)| k)
+    }
+
+    /// Removes the last value from the set and returns it, if any.
+    pub fn pop_last(&mut self) -> Option<T> {
+        self.map.pop_last().map(|(k, _)| k)
+    }
+
+    /// Moves all elements from `other` into `self`, leaving `other` empty.
+    pub fn append(&mut self, other: &mut Self)
+    where
+        T: Ord,
+    {
+        self.map.append(&mut other.map)
+    }
+
+    /// Splits the collection into two at the given value. Returns everything after the given
+    /// value, including the value.
+    pub fn split_off<Q>(&mut self, value: &Q) -> Self
+    where
+        T: Borrow<Q> + Ord,
+        Q: ?Sized + Ord,
+    {
+        BTreeSet {
+            map: self.map.split_off(value),
+        }
+    }
+
+    /// Constructs a double-ended iterator over a sub-range of elements in the set.
+    ///
+    /// # Panics
+    ///
+    /// Panics if range `start > end`, or if range `start == end` and both bounds are `Excluded`.
+    pub fn range<K, R>(&self, range: R) -> Range<'_, T>
+    where
+        K: ?Sized + Ord,
+        T: Borrow<K>,
+        R: RangeBounds<K>,
+    {
+        Range {
+            inner: self.map.range(range),
+        }
+    }
+
+    /// Gets an iterator that visits the values in the `BTreeSet` in ascending order.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            inner: self.map.keys(),
+        }
+    }
+}
+
+/// An iterator over the items of a `BTreeSet`.
+///
+/// This `struct` is created by the `iter` method on `BTreeSet`.
+pub struct Iter<'a, T> {
+    inner: btree_map::Keys<'a, T, ()>,
+}
+
+impl<'a, T> Clone for Iter<'a, T> {
+    fn clone(&self) -> Self {
+        Iter {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<&'a T> {
+        self.inner.next_back()
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {}
+
+/// An iterator over a sub-range of items in a `BTreeSet`.
+///
+/// This `struct` is created by the `range` method on `BTreeSet`.
+pub struct Range<'a, T> {
+    inner: btree_map::Range<'a, T, ()>,
+}
+
+impl<'a, T> Clone for Range<'a, T> {
+    fn clone(&self) -> Self {
+        Range {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<'a, T> Iterator for Range<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        self.inner.next().map(|(k, _)| k)
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Range<'a, T> {
+    fn next_back(&mut self) -> Option<&'a T> {
+        self.inner.next_back().map(|(k, _)| k)
+    }
+}
+
+/// An owning iterator over the items of a `BTreeSet`.
+///
+/// This `struct` is created by the `into_iter` method on `BTreeSet`.
+pub struct IntoIter<A, T>
+where
+    A: Alloc,
+{
+    inner: btree_map::IntoIter<A, T, ()>,
+}
+
+impl<A, T> Iterator for IntoIter<A, T>
+where
+    A: Alloc,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.inner.next().map(|(k, _)| k)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<A, T> DoubleEndedIterator for IntoIter<A, T>
+where
+    A: Alloc,
+{
+    fn next_back(&mut self) -> Option<T> {
+        self.inner.next_back().map(|(k, _)| k)
+    }
+}
+
+impl<A, T> ExactSizeIterator for IntoIter<A, T> where A: Alloc {}
+
+impl<A, T> IntoIterator for BTreeSet<A, T>
+where
+    A: Alloc,
+{
+    type Item = T;
+    type IntoIter = IntoIter<A, T>;
+
+    fn into_iter(self) -> IntoIter<A, T> {
+        IntoIter {
+            inner: self.map.into_iter(),
+        }
+    }
+}
+
+impl<'a, A, T> IntoIterator for &'a BTreeSet<A, T>
+where
+    A: Alloc,
+{
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<A, T> Extend<T> for BTreeSet<A, T>
+where
+    A: Alloc,
+    T: Ord,
+{
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        for value in iter {
+            self.insert(value);
+        }
+    }
+}
+
+impl<'a, A, T> Extend<&'a T> for BTreeSet<A, T>
+where
+    A: Alloc,
+    T: 'a + Ord + Copy,
+{
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = &'a T>,
+    {
+        self.extend(iter.into_iter().cloned())
+    }
+}
+
+impl<A, T> FromIterator<T> for BTreeSet<A, T>
+where
+    A: Alloc,
+    T: Ord,
+{
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let mut set = BTreeSet::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<A, T> Clone for BTreeSet<A, T>
+where
+    A: Alloc,
+    T: Clone + Ord,
+{
+    fn clone(&self) -> Self {
+        BTreeSet {
+            map: self.map.clone(),
+        }
+    }
+}
+
+impl<A, T> Default for BTreeSet<A, T>
+where
+    A: Alloc,
+{
+    fn default() -> Self {
+        BTreeSet::new()
+    }
+}
+
+impl<A, T> fmt::Debug for BTreeSet<A, T>
+where
+    A: Alloc,
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<A, T> Hash for BTreeSet<A, T>
+where
+    A: Alloc,
+    T: Hash,
+{
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: Hasher,
+    {
+        self.map.hash(state)
+    }
+}
+
+impl<A, T> Eq for BTreeSet<A, T>
+where
+    A: Alloc,
+    T: Eq,
+{
+}
+
+impl<A, B, T> PartialEq<BTreeSet<B, T>> for BTreeSet<A, T>
+where
+    A: Alloc,
+    B: Alloc,
+    T: PartialEq,
+{
+    fn eq(&self, other: &BTreeSet<B, T>) -> bool {
+        self.map == other.map
+    }
+}
+
+impl<A, T> Ord for BTreeSet<A, T>
+where
+    A: Alloc,
+    T: Ord,
+{
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        self.map.cmp(&other.map)
+    }
+}
+
+impl<A, B, T> PartialOrd<BTreeSet<B, T>> for BTreeSet<A, T>
+where
+    A: Alloc,
+    B: Alloc,
+    T: PartialOrd,
+{
+    fn partial_cmp(&self, other: &BTreeSet<B, T>) -> Option<cmp::Ordering> {
+        self.map.partial_cmp(&other.map)
+    }
+}
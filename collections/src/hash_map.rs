@@ -0,0 +1,1512 @@
+//! A hash map implemented with quadratic probing and SIMD-style (SWAR) lookup.
+//!
+//! The table follows the design of Google's SwissTable: a contiguous array of buckets plus one
+//! control byte per bucket. Lookups compare 8 control bytes at a time using plain `u64`
+//! arithmetic so no target specific instructions are needed.
+
+use core::{
+    alloc::Layout,
+    borrow::Borrow,
+    cmp, fmt,
+    hash::{BuildHasher, Hash, Hasher},
+    iter::FromIterator,
+    marker::PhantomData,
+    mem::{self, ManuallyDrop},
+    ops, ptr,
+};
+
+use alloc_many::Alloc;
+
+use crate::TryReserveError;
+
+/* Control bytes */
+
+// NOTE `EMPTY` is zero so a table can be allocated with `alloc_zeroed`
+const EMPTY: u8 = 0x00;
+const DELETED: u8 = 0x01;
+// full buckets have the top bit set and store the top 7 bits of the hash in the rest
+
+const GROUP_WIDTH: usize = mem::size_of::<u64>();
+
+const LO: u64 = 0x0101_0101_0101_0101;
+const HI: u64 = 0x8080_8080_8080_8080;
+
+fn is_full(ctrl: u8) -> bool {
+    ctrl & 0x80 != 0
+}
+
+// the position in the table
+fn h1(hash: u64) -> usize {
+    hash as usize
+}
+
+// the control byte of a full bucket
+fn h2(hash: u64) -> u8 {
+    0x80 | (hash >> 57) as u8
+}
+
+// a group of control bytes
+#[derive(Clone, Copy)]
+struct Group(u64);
+
+impl Group {
+    unsafe fn load(ctrl: *const u8) -> Self {
+        Group(u64::from_le(ptr::read_unaligned(ctrl as *const u64)))
+    }
+
+    // NOTE unlike the usual "has zero byte" trick this has no false positives
+    fn zero_bytes(x: u64) -> BitMask {
+        BitMask(!(((x & !HI).wrapping_add(!HI)) | x) & HI)
+    }
+
+    fn match_byte(self, byte: u8) -> BitMask {
+        Group::zero_bytes(self.0 ^ (LO * u64::from(byte)))
+    }
+
+    fn match_empty(self) -> BitMask {
+        Group::zero_bytes(self.0)
+    }
+
+    fn match_empty_or_deleted(self) -> BitMask {
+        BitMask(!self.0 & HI)
+    }
+}
+
+// one bit (the top one) per matching byte of a `Group`
+struct BitMask(u64);
+
+impl BitMask {
+    fn any(&self) -> bool {
+        self.0 != 0
+    }
+
+    fn lowest(&self) -> Option<usize> {
+        if self.any() {
+            Some(self.0.trailing_zeros() as usize / 8)
+        } else {
+            None
+        }
+    }
+
+    fn leading_zeros(&self) -> usize {
+        self.0.leading_zeros() as usize / 8
+    }
+
+    fn trailing_zeros(&self) -> usize {
+        self.0.trailing_zeros() as usize / 8
+    }
+}
+
+impl Iterator for BitMask {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        let bit = self.lowest()?;
+        self.0 &= self.0 - 1;
+        Some(bit)
+    }
+}
+
+// triangular probing over groups; visits every group when the number of buckets is a power of 2
+struct ProbeSeq {
+    pos: usize,
+    stride: usize,
+    mask: usize,
+}
+
+impl ProbeSeq {
+    fn new(hash: u64, mask: usize) -> Self {
+        ProbeSeq {
+            pos: h1(hash) & mask,
+            stride: 0,
+            mask,
+        }
+    }
+
+    fn move_next(&mut self) {
+        self.stride += GROUP_WIDTH;
+        self.pos = (self.pos + self.stride) & self.mask;
+    }
+}
+
+// maximum load factor: 7/8
+fn bucket_mask_to_capacity(bucket_mask: usize) -> usize {
+    (bucket_mask + 1) / 8 * 7
+}
+
+fn capacity_to_buckets(cap: usize) -> Option<usize> {
+    let adjusted = cap.checked_mul(8)? / 7;
+    cmp::max(adjusted, GROUP_WIDTH).checked_next_power_of_two()
+}
+
+// control bytes (plus a mirror of the first group) followed by the buckets
+fn table_layout<T>(buckets: usize) -> Option<(Layout, usize)> {
+    let align = mem::align_of::<T>();
+    let ctrl = buckets.checked_add(GROUP_WIDTH)?;
+    let offset = ctrl.checked_add(align - 1)? & !(align - 1);
+    let size = mem::size_of::<T>()
+        .checked_mul(buckets)?
+        .checked_add(offset)?;
+
+    Layout::from_size_align(size, align)
+        .ok()
+        .map(|layout| (layout, offset))
+}
+
+/* Raw table */
+
+struct RawTable<A, T>
+where
+    A: Alloc,
+{
+    // NOTE null when no memory has been allocated
+    ctrl: *mut u8,
+    data: *mut T,
+    bucket_mask: usize,
+    items: usize,
+    growth_left: usize,
+    _allocator: PhantomData<A>,
+    _marker: PhantomData<T>,
+}
+
+impl<A, T> RawTable<A, T>
+where
+    A: Alloc,
+{
+    fn new() -> Self {
+        RawTable {
+            ctrl: ptr::null_mut(),
+            data: ptr::null_mut(),
+            bucket_mask: 0,
+            items: 0,
+            growth_left: 0,
+            _allocator: PhantomData,
+            _marker: PhantomData,
+        }
+    }
+
+    fn try_with_capacity(cap: usize) -> Result<Self, TryReserveError> {
+        if cap == 0 {
+            Ok(RawTable::new())
+        } else {
+            let buckets = capacity_to_buckets(cap).ok_or(TryReserveError::CapacityOverflow)?;
+            RawTable::allocate(buckets)
+        }
+    }
+
+    fn allocate(buckets: usize) -> Result<Self, TryReserveError> {
+        let (layout, offset) =
+            table_layout::<T>(buckets).ok_or(TryReserveError::CapacityOverflow)?;
+
+        unsafe {
+            let ctrl = A::alloc_zeroed(layout);
+            if ctrl.is_null() {
+                return Err(TryReserveError::AllocError { layout });
+            }
+
+            Ok(RawTable {
+                ctrl,
+                data: ctrl.add(offset) as *mut T,
+                bucket_mask: buckets - 1,
+                items: 0,
+                growth_left: bucket_mask_to_capacity(buckets - 1),
+                _allocator: PhantomData,
+                _marker: PhantomData,
+            })
+        }
+    }
+
+    fn buckets(&self) -> usize {
+        if self.ctrl.is_null() {
+            0
+        } else {
+            self.bucket_mask + 1
+        }
+    }
+
+    fn capacity(&self) -> usize {
+        self.items + self.growth_left
+    }
+
+    unsafe fn bucket(&self, index: usize) -> *mut T {
+        self.data.add(index)
+    }
+
+    unsafe fn ctrl(&self, index: usize) -> u8 {
+        *self.ctrl.add(index)
+    }
+
+    // also updates the mirror of the first group that lives at the end of the control bytes
+    unsafe fn set_ctrl(&mut self, index: usize, ctrl: u8) {
+        let mirror = (index.wrapping_sub(GROUP_WIDTH) & self.bucket_mask) + GROUP_WIDTH;
+
+        *self.ctrl.add(index) = ctrl;
+        *self.ctrl.add(mirror) = ctrl;
+    }
+
+    fn find<F>(&self, hash: u64, mut eq: F) -> Option<usize>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        if self.ctrl.is_null() {
+            return None;
+        }
+
+        unsafe {
+            let mut probe = ProbeSeq::new(hash, self.bucket_mask);
+            loop {
+                let group = Group::load(self.ctrl.add(probe.pos));
+
+                for bit in group.match_byte(h2(hash)) {
+                    let index = (probe.pos + bit) & self.bucket_mask;
+                    if eq(&*self.bucket(index)) {
+                        return Some(index);
+                    }
+                }
+
+                if group.match_empty().any() {
+                    return None;
+                }
+
+                probe.move_next();
+            }
+        }
+    }
+
+    // NOTE the table must have at least one empty or deleted bucket
+    unsafe fn find_insert_slot(&self, hash: u64) -> usize {
+        let mut probe = ProbeSeq::new(hash, self.bucket_mask);
+        loop {
+            let group = Group::load(self.ctrl.add(probe.pos));
+
+            if let Some(bit) = group.match_empty_or_deleted().lowest() {
+                return (probe.pos + bit) & self.bucket_mask;
+            }
+
+            probe.move_next();
+        }
+    }
+
+    // NOTE the caller must have reserved space for the new element
+    unsafe fn insert_no_grow(&mut self, hash: u64, value: T) -> usize {
+        let index = self.find_insert_slot(hash);
+
+        if self.ctrl(index) == EMPTY {
+            self.growth_left -= 1;
+        }
+        self.set_ctrl(index, h2(hash));
+        self.bucket(index).write(value);
+        self.items += 1;
+
+        index
+    }
+
+    // removes the element at `index` from the table without dropping it
+    unsafe fn erase_no_drop(&mut self, index: usize) {
+        // if there's an empty bucket in every group that contains `index` then no probe sequence
+        // went past this bucket and it can be marked as empty
+        let before = Group::load(
+            self.ctrl
+                .add(index.wrapping_sub(GROUP_WIDTH) & self.bucket_mask),
+        );
+        let after = Group::load(self.ctrl.add(index));
+
+        let ctrl = if before.match_empty().leading_zeros() + after.match_empty().trailing_zeros()
+            >= GROUP_WIDTH
+        {
+            DELETED
+        } else {
+            self.growth_left += 1;
+            EMPTY
+        };
+
+        self.set_ctrl(index, ctrl);
+        self.items -= 1;
+    }
+
+    unsafe fn remove(&mut self, index: usize) -> T {
+        self.erase_no_drop(index);
+        self.bucket(index).read()
+    }
+
+    fn try_reserve<H>(&mut self, additional: usize, hasher: H) -> Result<(), TryReserveError>
+    where
+        H: Fn(&T) -> u64,
+    {
+        if additional <= self.growth_left {
+            return Ok(());
+        }
+
+        let new_items = self
+            .items
+            .checked_add(additional)
+            .ok_or(TryReserveError::CapacityOverflow)?;
+        let full_capacity = bucket_mask_to_capacity(self.bucket_mask);
+
+        if self.ctrl.is_null() {
+            self.resize(new_items, hasher)
+        } else if new_items <= full_capacity / 2 {
+            // plenty of tombstones: rebuild the table at the same size
+            self.resize(full_capacity, hasher)
+        } else {
+            self.resize(cmp::max(new_items, full_capacity + 1), hasher)
+        }
+    }
+
+    // moves all the elements into a new table with room for at least `capacity` elements
+    fn resize<H>(&mut self, capacity: usize, hasher: H) -> Result<(), TryReserveError>
+    where
+        H: Fn(&T) -> u64,
+    {
+        debug_assert!(capacity >= self.items);
+
+        let mut new_table = RawTable::try_with_capacity(capacity)?;
+
+        unsafe {
+            for index in self.full_buckets() {
+                let hash = hasher(&*self.bucket(index));
+
+                // NOTE `hasher` may panic so each element is removed from this table only after it
+                // has been hashed and before it's moved into the new one; that way every element
+                // is owned by exactly one of the tables if we unwind
+                new_table.insert_no_grow(hash, self.remove(index));
+            }
+        }
+
+        mem::swap(self, &mut new_table);
+        Ok(())
+    }
+
+    fn shrink_to_fit<H>(&mut self, hasher: H)
+    where
+        H: Fn(&T) -> u64,
+    {
+        if self.items == 0 {
+            *self = RawTable::new();
+        } else if capacity_to_buckets(self.items) != Some(self.buckets()) {
+            self.resize(self.items, hasher)
+                .unwrap_or_else(|e| crate::handle_reserve_error::<A>(e));
+        }
+    }
+
+    // marks all buckets as empty without dropping their contents
+    fn clear_no_drop(&mut self) {
+        if !self.ctrl.is_null() {
+            unsafe {
+                ptr::write_bytes(self.ctrl, EMPTY, self.buckets() + GROUP_WIDTH);
+            }
+        }
+
+        self.items = 0;
+        self.growth_left = bucket_mask_to_capacity(self.bucket_mask);
+    }
+
+    fn clear(&mut self) {
+        // NOTE the buckets are marked as empty even if dropping an element panics; the elements
+        // that haven't been dropped by then are leaked
+        struct Guard<'a, A, T>(&'a mut RawTable<A, T>)
+        where
+            A: Alloc;
+
+        impl<A, T> Drop for Guard<'_, A, T>
+        where
+            A: Alloc,
+        {
+            fn drop(&mut self) {
+                self.0.clear_no_drop();
+            }
+        }
+
+        let guard = Guard(self);
+        unsafe {
+            for index in guard.0.full_buckets() {
+                ptr::drop_in_place(guard.0.bucket(index));
+            }
+        }
+    }
+
+    // indices of the full buckets
+    fn full_buckets(&self) -> FullBuckets {
+        FullBuckets {
+            ctrl: self.ctrl,
+            index: 0,
+            items: self.items,
+        }
+    }
+
+    fn iter(&self) -> RawIter<T> {
+        RawIter {
+            buckets: self.full_buckets(),
+            data: self.data,
+        }
+    }
+
+    // frees the memory of the table without dropping the elements
+    unsafe fn free_buckets(&mut self) {
+        if !self.ctrl.is_null() {
+            let (layout, _) = table_layout::<T>(self.buckets()).unwrap();
+            A::dealloc(self.ctrl, layout);
+        }
+    }
+}
+
+impl<A, T> Drop for RawTable<A, T>
+where
+    A: Alloc,
+{
+    fn drop(&mut self) {
+        unsafe {
+            for index in self.full_buckets() {
+                ptr::drop_in_place(self.bucket(index));
+            }
+
+            self.free_buckets();
+        }
+    }
+}
+
+struct FullBuckets {
+    ctrl: *const u8,
+    index: usize,
+    items: usize,
+}
+
+impl Iterator for FullBuckets {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        while self.items != 0 {
+            let index = self.index;
+            self.index += 1;
+
+            if is_full(unsafe { *self.ctrl.add(index) }) {
+                self.items -= 1;
+                return Some(index);
+            }
+        }
+
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.items, Some(self.items))
+    }
+}
+
+struct RawIter<T> {
+    buckets: FullBuckets,
+    data: *mut T,
+}
+
+impl<T> Clone for RawIter<T> {
+    fn clone(&self) -> Self {
+        RawIter {
+            buckets: FullBuckets {
+                ctrl: self.buckets.ctrl,
+                index: self.buckets.index,
+                items: self.buckets.items,
+            },
+            data: self.data,
+        }
+    }
+}
+
+impl<T> Iterator for RawIter<T> {
+    type Item = *mut T;
+
+    fn next(&mut self) -> Option<*mut T> {
+        let data = self.data;
+        self.buckets.next().map(|index| unsafe { data.add(index) })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.buckets.size_hint()
+    }
+}
+
+/* Hasher */
+
+const SEED: u64 = 0x243f_6a88_85a3_08d3;
+
+/// The default hasher of `HashMap` and `HashSet`
+///
+/// A fast, non-cryptographic hasher (FxHash with a final mixing step) that always starts from the
+/// same seed. It's *not* resistant to HashDoS attacks.
+#[derive(Clone, Debug)]
+pub struct DefaultHasher {
+    hash: u64,
+}
+
+impl DefaultHasher {
+    /// Creates a new hasher
+    pub fn new() -> Self {
+        DefaultHasher { hash: SEED }
+    }
+
+    fn add(&mut self, word: u64) {
+        self.hash = (self.hash.rotate_left(5) ^ word).wrapping_mul(0x517c_c1b7_2722_0a95);
+    }
+}
+
+impl Default for DefaultHasher {
+    fn default() -> Self {
+        DefaultHasher::new()
+    }
+}
+
+impl Hasher for DefaultHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for chunk in bytes.chunks(8) {
+            let mut word = 0;
+            for (i, byte) in chunk.iter().enumerate() {
+                word |= u64::from(*byte) << (8 * i);
+            }
+            self.add(word);
+        }
+    }
+
+    fn write_u8(&mut self, i: u8) {
+        self.add(u64::from(i))
+    }
+
+    fn write_u16(&mut self, i: u16) {
+        self.add(u64::from(i))
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.add(u64::from(i))
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.add(i)
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.add(i as u64)
+    }
+
+    fn finish(&self) -> u64 {
+        // splitmix64 finalizer; spreads the entropy over all the bits
+        let mut z = self.hash;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+}
+
+/// The default `BuildHasher` of `HashMap` and `HashSet`
+///
+/// Unlike `std`'s `RandomState` it's not randomly seeded: all the hashers it builds are the same.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FixedState;
+
+impl BuildHasher for FixedState {
+    type Hasher = DefaultHasher;
+
+    fn build_hasher(&self) -> DefaultHasher {
+        DefaultHasher::new()
+    }
+}
+
+// NOTE `BuildHasher::hash_one` is newer than our MSRV
+#[allow(clippy::manual_hash_one)]
+fn make_hash<S, Q>(hash_builder: &S, value: &Q) -> u64
+where
+    S: BuildHasher,
+    Q: ?Sized + Hash,
+{
+    let mut state = hash_builder.build_hasher();
+    value.hash(&mut state);
+    state.finish()
+}
+
+/* Map */
+
+/// A hash map implemented with quadratic probing and SIMD-style lookup.
+///
+/// The table is allocated on the allocator `A`.
+pub struct HashMap<A, K, V, S = FixedState>
+where
+    A: Alloc,
+{
+    hash_builder: S,
+    table: RawTable<A, (K, V)>,
+}
+
+unsafe impl<A, K, V, S> Send for HashMap<A, K, V, S>
+where
+    A: Alloc,
+    K: Send,
+    V: Send,
+    S: Send,
+{
+}
+
+unsafe impl<A, K, V, S> Sync for HashMap<A, K, V, S>
+where
+    A: Alloc,
+    K: Sync,
+    V: Sync,
+    S: Sync,
+{
+}
+
+impl<A, K, V> HashMap<A, K, V, FixedState>
+where
+    A: Alloc,
+{
+    /// Creates an empty `HashMap`.
+    ///
+    /// Does not allocate anything on its own.
+    pub fn new() -> Self {
+        HashMap::with_hasher(FixedState)
+    }
+
+    /// Creates an empty `HashMap` with the specified capacity.
+    pub fn with_capacity(capacity: usize) -> Self {
+        HashMap::with_capacity_and_hasher(capacity, FixedState)
+    }
+}
+
+impl<A, K, V, S> HashMap<A, K, V, S>
+where
+    A: Alloc,
+{
+    /// Creates an empty `HashMap` which will use the given hash builder to hash keys.
+    pub fn with_hasher(hash_builder: S) -> Self {
+        HashMap {
+            hash_builder,
+            table: RawTable::new(),
+        }
+    }
+
+    /// Creates an empty `HashMap` with the specified capacity, using `hash_builder` to hash the
+    /// keys.
+    pub fn with_capacity_and_hasher(capacity: usize, hash_builder: S) -> Self {
+        HashMap {
+            hash_builder,
+            table: RawTable::try_with_capacity(capacity)
+                .unwrap_or_else(|e| crate::handle_reserve_error::<A>(e)),
+        }
+    }
+
+    /// Returns a reference to the map's `BuildHasher`.
+    pub fn hasher(&self) -> &S {
+        &self.hash_builder
+    }
+
+    /// Returns the number of elements the map can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.table.capacity()
+    }
+
+    /// Returns the number of elements in the map.
+    pub fn len(&self) -> usize {
+        self.table.items
+    }
+
+    /// Returns `true` if the map contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Clears the map, removing all key-value pairs. Keeps the allocated memory for reuse.
+    pub fn clear(&mut self) {
+        self.table.clear()
+    }
+
+    /// An iterator visiting all key-value pairs in arbitrary order.
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            inner: self.table.iter(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// An iterator visiting all key-value pairs in arbitrary order, with mutable references to
+    /// the values.
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+        IterMut {
+            inner: self.table.iter(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// An iterator visiting all keys in arbitrary order.
+    pub fn keys(&self) -> Keys<'_, K, V> {
+        Keys { inner: self.iter() }
+    }
+
+    /// An iterator visiting all values in arbitrary order.
+    pub fn values(&self) -> Values<'_, K, V> {
+        Values { inner: self.iter() }
+    }
+
+    /// An iterator visiting all values mutably in arbitrary order.
+    pub fn values_mut(&mut self) -> ValuesMut<'_, K, V> {
+        ValuesMut {
+            inner: self.iter_mut(),
+        }
+    }
+
+    /// Clears the map, returning all key-value pairs as an iterator. Keeps the allocated memory
+    /// for reuse.
+    pub fn drain(&mut self) -> Drain<'_, A, K, V> {
+        // NOTE the table is put back when the iterator is dropped; if the iterator is leaked the
+        // map is left empty
+        let table = mem::replace(&mut self.table, RawTable::new());
+
+        Drain {
+            inner: table.iter(),
+            table: ManuallyDrop::new(table),
+            orig: &mut self.table,
+        }
+    }
+
+    /// Retains only the elements specified by the predicate.
+    ///
+    /// In other words, remove all pairs `(k, v)` such that `f(&k, &mut v)` returns `false`.
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        unsafe {
+            for index in self.table.full_buckets() {
+                let elem = &mut *self.table.bucket(index);
+                if !f(&elem.0, &mut elem.1) {
+                    self.table.erase_no_drop(index);
+                    ptr::drop_in_place(elem);
+                }
+            }
+        }
+    }
+}
+
+impl<A, K, V, S> HashMap<A, K, V, S>
+where
+    A: Alloc,
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    /// Reserves capacity for at least `additional` more elements to be inserted in the
+    /// `HashMap`.
+    pub fn reserve(&mut self, additional: usize) {
+        self.try_reserve(additional)
+            .unwrap_or_else(|e| crate::handle_reserve_error::<A>(e))
+    }
+
+    /// Tries to reserve capacity for at least `additional` more elements to be inserted in the
+    /// `HashMap`.
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        let hash_builder = &self.hash_builder;
+        self.table
+            .try_reserve(additional, |x| make_hash(hash_builder, &x.0))
+    }
+
+    /// Shrinks the capacity of the map as much as possible.
+    pub fn shrink_to_fit(&mut self) {
+        let hash_builder = &self.hash_builder;
+        self.table.shrink_to_fit(|x| make_hash(hash_builder, &x.0))
+    }
+
+    fn find<Q>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        let hash = make_hash(&self.hash_builder, key);
+        self.table.find(hash, |x| key.eq(x.0.borrow()))
+    }
+
+    /// Returns a reference to the value corresponding to the key.
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.get_key_value(key).map(|(_, v)| v)
+    }
+
+    /// Returns the key-value pair corresponding to the supplied key.
+    pub fn get_key_value<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.find(key).map(|index| unsafe {
+            let elem = &*self.table.bucket(index);
+            (&elem.0, &elem.1)
+        })
+    }
+
+    /// Returns a mutable reference to the value corresponding to the key.
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.find(key)
+            .map(|index| unsafe { &mut (*self.table.bucket(index)).1 })
+    }
+
+    /// Returns `true` if the map contains a value for the specified key.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.find(key).is_some()
+    }
+
+    /// Inserts a key-value pair into the map.
+    ///
+    /// If the map did not have this key present, `None` is returned. If the map did have this key
+    /// present, the value is updated, and the old value is returned. The key is not updated.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        match self.entry(key) {
+            Entry::Occupied(mut entry) => Some(entry.insert(value)),
+            Entry::Vacant(entry) => {
+                entry.insert(value);
+                None
+            }
+        }
+    }
+
+    /// Removes a key from the map, returning the value at the key if the key was previously in
+    /// the map.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.remove_entry(key).map(|(_, v)| v)
+    }
+
+    /// Removes a key from the map, returning the stored key and value if the key was previously
+    /// in the map.
+    pub fn remove_entry<Q>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.find(key)
+            .map(|index| unsafe { self.table.remove(index) })
+    }
+
+    /// Gets the given key's corresponding entry in the map for in-place manipulation.
+    pub fn entry(&mut self, key: K) -> Entry<'_, A, K, V, S> {
+        let hash = make_hash(&self.hash_builder, &key);
+
+        match self.table.find(hash, |x| x.0 == key) {
+            Some(index) => Entry::Occupied(OccupiedEntry { index, map: self }),
+            None => Entry::Vacant(VacantEntry {
+                hash,
+                key,
+                map: self,
+            }),
+        }
+    }
+}
+
+/// A view into a single entry in a map, which may either be vacant or occupied.
+///
+/// This `enum` is constructed from the `entry` method on `HashMap`.
+pub enum Entry<'a, A, K, V, S>
+where
+    A: Alloc,
+{
+    /// An occupied entry.
+    Occupied(OccupiedEntry<'a, A, K, V, S>),
+    /// A vacant entry.
+    Vacant(VacantEntry<'a, A, K, V, S>),
+}
+
+/// A view into an occupied entry in a `HashMap`. It is part of the `Entry` enum.
+pub struct OccupiedEntry<'a, A, K, V, S>
+where
+    A: Alloc,
+{
+    index: usize,
+    map: &'a mut HashMap<A, K, V, S>,
+}
+
+/// A view into a vacant entry in a `HashMap`. It is part of the `Entry` enum.
+pub struct VacantEntry<'a, A, K, V, S>
+where
+    A: Alloc,
+{
+    hash: u64,
+    key: K,
+    map: &'a mut HashMap<A, K, V, S>,
+}
+
+impl<'a, A, K, V, S> Entry<'a, A, K, V, S>
+where
+    A: Alloc,
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    /// Ensures a value is in the entry by inserting the default if empty, and returns a mutable
+    /// reference to the value in the entry.
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default),
+        }
+    }
+
+    /// Ensures a value is in the entry by inserting the result of the default function if empty,
+    /// and returns a mutable reference to the value in the entry.
+    pub fn or_insert_with<F>(self, default: F) -> &'a mut V
+    where
+        F: FnOnce() -> V,
+    {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    /// Ensures a value is in the entry by inserting the default value if empty, and returns a
+    /// mutable reference to the value in the entry.
+    pub fn or_default(self) -> &'a mut V
+    where
+        V: Default,
+    {
+        self.or_insert_with(V::default)
+    }
+
+    /// Returns a reference to this entry's key.
+    pub fn key(&self) -> &K {
+        match self {
+            Entry::Occupied(entry) => entry.key(),
+            Entry::Vacant(entry) => entry.key(),
+        }
+    }
+
+    /// Provides in-place mutable access to an occupied entry before any potential inserts into
+    /// the map.
+    pub fn and_modify<F>(self, f: F) -> Self
+    where
+        F: FnOnce(&mut V),
+    {
+        match self {
+            Entry::Occupied(mut entry) => {
+                f(entry.get_mut());
+                Entry::Occupied(entry)
+            }
+            Entry::Vacant(entry) => Entry::Vacant(entry),
+        }
+    }
+}
+
+impl<'a, A, K, V, S> OccupiedEntry<'a, A, K, V, S>
+where
+    A: Alloc,
+{
+    fn elem(&self) -> *mut (K, V) {
+        unsafe { self.map.table.bucket(self.index) }
+    }
+
+    /// Gets a reference to the key in the entry.
+    pub fn key(&self) -> &K {
+        unsafe { &(*self.elem()).0 }
+    }
+
+    /// Gets a reference to the value in the entry.
+    pub fn get(&self) -> &V {
+        unsafe { &(*self.elem()).1 }
+    }
+
+    /// Gets a mutable reference to the value in the entry.
+    pub fn get_mut(&mut self) -> &mut V {
+        unsafe { &mut (*self.elem()).1 }
+    }
+
+    /// Converts the entry into a mutable reference to its value.
+    pub fn into_mut(self) -> &'a mut V {
+        unsafe { &mut (*self.elem()).1 }
+    }
+
+    /// Sets the value of the entry, and returns the entry's old value.
+    pub fn insert(&mut self, value: V) -> V {
+        mem::replace(self.get_mut(), value)
+    }
+
+    /// Takes the value out of the entry, and returns it.
+    pub fn remove(self) -> V {
+        self.remove_entry().1
+    }
+
+    /// Take the ownership of the key and value from the map.
+    pub fn remove_entry(self) -> (K, V) {
+        unsafe { self.map.table.remove(self.index) }
+    }
+}
+
+impl<'a, A, K, V, S> VacantEntry<'a, A, K, V, S>
+where
+    A: Alloc,
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    /// Gets a reference to the key that would be used when inserting a value through the
+    /// `VacantEntry`.
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Take ownership of the key.
+    pub fn into_key(self) -> K {
+        self.key
+    }
+
+    /// Sets the value of the entry with the `VacantEntry`'s key, and returns a mutable reference
+    /// to it.
+    pub fn insert(self, value: V) -> &'a mut V {
+        let map = self.map;
+        map.reserve(1);
+
+        unsafe {
+            let index = map.table.insert_no_grow(self.hash, (self.key, value));
+            &mut (*map.table.bucket(index)).1
+        }
+    }
+}
+
+/// An iterator over the entries of a `HashMap`.
+///
+/// This `struct` is created by the `iter` method on `HashMap`.
+pub struct Iter<'a, K, V> {
+    inner: RawIter<(K, V)>,
+    _marker: PhantomData<&'a (K, V)>,
+}
+
+unsafe impl<'a, K, V> Send for Iter<'a, K, V>
+where
+    K: Sync,
+    V: Sync,
+{
+}
+
+unsafe impl<'a, K, V> Sync for Iter<'a, K, V>
+where
+    K: Sync,
+    V: Sync,
+{
+}
+
+impl<'a, K, V> Clone for Iter<'a, K, V> {
+    fn clone(&self) -> Self {
+        Iter {
+            inner: self.inner.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<(&'a K, &'a V)> {
+        self.inner
+            .next()
+            .map(|elem| unsafe { (&(*elem).0, &(*elem).1) })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for Iter<'a, K, V> {}
+
+/// A mutable iterator over the entries of a `HashMap`.
+///
+/// This `struct` is created by the `iter_mut` method on `HashMap`.
+pub struct IterMut<'a, K, V> {
+    inner: RawIter<(K, V)>,
+    _marker: PhantomData<&'a mut (K, V)>,
+}
+
+unsafe impl<'a, K, V> Send for IterMut<'a, K, V>
+where
+    K: Send,
+    V: Send,
+{
+}
+
+unsafe impl<'a, K, V> Sync for IterMut<'a, K, V>
+where
+    K: Sync,
+    V: Sync,
+{
+}
+
+impl<'a, K, V> Iterator for IterMut<'a, K, V> {
+    type Item = (&'a K, &'a mut V);
+
+    fn next(&mut self) -> Option<(&'a K, &'a mut V)> {
+        self.inner
+            .next()
+            .map(|elem| unsafe { (&(*elem).0, &mut (*elem).1) })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for IterMut<'a, K, V> {}
+
+/// An iterator over the keys of a `HashMap`.
+///
+/// This `struct` is created by the `keys` method on `HashMap`.
+pub struct Keys<'a, K, V> {
+    inner: Iter<'a, K, V>,
+}
+
+impl<'a, K, V> Clone for Keys<'a, K, V> {
+    fn clone(&self) -> Self {
+        Keys {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<'a, K, V> Iterator for Keys<'a, K, V> {
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<&'a K> {
+        self.inner.next().map(|(k, _)| k)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for Keys<'a, K, V> {}
+
+/// An iterator over the values of a `HashMap`.
+///
+/// This `struct` is created by the `values` method on `HashMap`.
+pub struct Values<'a, K, V> {
+    inner: Iter<'a, K, V>,
+}
+
+impl<'a, K, V> Iterator for Values<'a, K, V> {
+    type Item = &'a V;
+
+    fn next(&mut self) -> Option<&'a V> {
+        self.inner.next().map(|(_, v)| v)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for Values<'a, K, V> {}
+
+/// A mutable iterator over the values of a `HashMap`.
+///
+/// This `struct` is created by the `values_mut` method on `HashMap`.
+pub struct ValuesMut<'a, K, V> {
+    inner: IterMut<'a, K, V>,
+}
+
+impl<'a, K, V> Iterator for ValuesMut<'a, K, V> {
+    type Item = &'a mut V;
+
+    fn next(&mut self) -> Option<&'a mut V> {
+        self.inner.next().map(|(_, v)| v)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for ValuesMut<'a, K, V> {}
+
+/// A draining iterator over the entries of a `HashMap`.
+///
+/// This `struct` is created by the `drain` method on `HashMap`.
+pub struct Drain<'a, A, K, V>
+where
+    A: Alloc,
+{
+    inner: RawIter<(K, V)>,
+    table: ManuallyDrop<RawTable<A, (K, V)>>,
+    orig: &'a mut RawTable<A, (K, V)>,
+}
+
+unsafe impl<'a, A, K, V> Send for Drain<'a, A, K, V>
+where
+    A: Alloc,
+    K: Send,
+    V: Send,
+{
+}
+
+unsafe impl<'a, A, K, V> Sync for Drain<'a, A, K, V>
+where
+    A: Alloc,
+    K: Sync,
+    V: Sync,
+{
+}
+
+impl<'a, A, K, V> Iterator for Drain<'a, A, K, V>
+where
+    A: Alloc,
+{
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<(K, V)> {
+        self.inner.next().map(|elem| unsafe { elem.read() })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, A, K, V> ExactSizeIterator for Drain<'a, A, K, V> where A: Alloc {}
+
+impl<'a, A, K, V> Drop for Drain<'a, A, K, V>
+where
+    A: Alloc,
+{
+    fn drop(&mut self) {
+        self.for_each(drop);
+
+        unsafe {
+            let mut table = ptr::read(&*self.table);
+            table.clear_no_drop();
+            // NOTE `orig` is an empty table that owns no memory
+            ptr::write(self.orig, table);
+        }
+    }
+}
+
+/// An owning iterator over the entries of a `HashMap`.
+///
+/// This `struct` is created by the `into_iter` method on `HashMap`.
+pub struct IntoIter<A, K, V>
+where
+    A: Alloc,
+{
+    inner: RawIter<(K, V)>,
+    table: ManuallyDrop<RawTable<A, (K, V)>>,
+}
+
+unsafe impl<A, K, V> Send for IntoIter<A, K, V>
+where
+    A: Alloc,
+    K: Send,
+    V: Send,
+{
+}
+
+unsafe impl<A, K, V> Sync for IntoIter<A, K, V>
+where
+    A: Alloc,
+    K: Sync,
+    V: Sync,
+{
+}
+
+impl<A, K, V> Iterator for IntoIter<A, K, V>
+where
+    A: Alloc,
+{
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<(K, V)> {
+        self.inner.next().map(|elem| unsafe { elem.read() })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<A, K, V> ExactSizeIterator for IntoIter<A, K, V> where A: Alloc {}
+
+impl<A, K, V> Drop for IntoIter<A, K, V>
+where
+    A: Alloc,
+{
+    fn drop(&mut self) {
+        self.for_each(drop);
+
+        unsafe { self.table.free_buckets() }
+    }
+}
+
+impl<A, K, V, S> IntoIterator for HashMap<A, K, V, S>
+where
+    A: Alloc,
+{
+    type Item = (K, V);
+    type IntoIter = IntoIter<A, K, V>;
+
+    fn into_iter(self) -> IntoIter<A, K, V> {
+        let table = ManuallyDrop::new(self.table);
+
+        IntoIter {
+            inner: table.iter(),
+            table,
+        }
+    }
+}
+
+impl<'a, A, K, V, S> IntoIterator for &'a HashMap<A, K, V, S>
+where
+    A: Alloc,
+{
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Iter<'a, K, V> {
+        self.iter()
+    }
+}
+
+impl<'a, A, K, V, S> IntoIterator for &'a mut HashMap<A, K, V, S>
+where
+    A: Alloc,
+{
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = IterMut<'a, K, V>;
+
+    fn into_iter(self) -> IterMut<'a, K, V> {
+        self.iter_mut()
+    }
+}
+
+impl<A, K, V, S> Extend<(K, V)> for HashMap<A, K, V, S>
+where
+    A: Alloc,
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+
+        for (k, v) in iter {
+            self.insert(k, v);
+        }
+    }
+}
+
+impl<'a, A, K, V, S> Extend<(&'a K, &'a V)> for HashMap<A, K, V, S>
+where
+    A: Alloc,
+    K: Eq + Hash + Copy,
+    V: Copy,
+    S: BuildHasher,
+{
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = (&'a K, &'a V)>,
+    {
+        self.extend(iter.into_iter().map(|(&k, &v)| (k, v)))
+    }
+}
+
+impl<A, K, V, S> FromIterator<(K, V)> for HashMap<A, K, V, S>
+where
+    A: Alloc,
+    K: Eq + Hash,
+    S: BuildHasher + Default,
+{
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        let mut map = HashMap::with_hasher(S::default());
+        map.extend(iter);
+        map
+    }
+}
+
+impl<A, K, V, S> Clone for HashMap<A, K, V, S>
+where
+    A: Alloc,
+    K: Clone + Eq + Hash,
+    V: Clone,
+    S: BuildHasher + Clone,
+{
+    fn clone(&self) -> Self {
+        let mut map = HashMap::with_capacity_and_hasher(self.len(), self.hash_builder.clone());
+        map.extend(self.iter().map(|(k, v)| (k.clone(), v.clone())));
+        map
+    }
+}
+
+impl<A, K, V, S> Default for HashMap<A, K, V, S>
+where
+    A: Alloc,
+    S: Default,
+{
+    fn default() -> Self {
+        HashMap::with_hasher(S::default())
+    }
+}
+
+impl<A, K, V, S> fmt::Debug for HashMap<A, K, V, S>
+where
+    A: Alloc,
+    K: fmt::Debug,
+    V: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<A, K, Q, V, S> ops::Index<&Q> for HashMap<A, K, V, S>
+where
+    A: Alloc,
+    K: Eq + Hash + Borrow<Q>,
+    Q: ?Sized + Eq + Hash,
+    S: BuildHasher,
+{
+    type Output = V;
+
+    fn index(&self, key: &Q) -> &V {
+        self.get(key).expect("no entry found for key")
+    }
+}
+
+impl<A, K, V, S> Eq for HashMap<A, K, V, S>
+where
+    A: Alloc,
+    K: Eq + Hash,
+    V: Eq,
+    S: BuildHasher,
+{
+}
+
+impl<A, B, K, V, S, T> PartialEq<HashMap<B, K, V, T>> for HashMap<A, K, V, S>
+where
+    A: Alloc,
+    B: Alloc,
+    K: Eq + Hash,
+    V: PartialEq,
+    T: BuildHasher,
+{
+    fn eq(&self, other: &HashMap<B, K, V, T>) -> bool {
+        self.len() == other.len()
+            && self
+                .iter()
+                .all(|(k, v)| other.get(k).map(|w| *v == *w).unwrap_or(false))
+    }
+}
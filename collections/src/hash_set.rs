@@ -0,0 +1,392 @@
+//! A hash set implemented as a `HashMap` where the value is `()`.
+
+use core::{
+    borrow::Borrow,
+    fmt,
+    hash::{BuildHasher, Hash},
+    iter::FromIterator,
+};
+
+use alloc_many::Alloc;
+
+use crate::{
+    hash_map::{self, FixedState, HashMap},
+    TryReserveError,
+};
+
+/// A hash set implemented as a `HashMap` where the value is `()`.
+///
+/// The table is allocated on the allocator `A`.
+pub struct HashSet<A, T, S = FixedState>
+where
+    A: Alloc,
+{
+    map: HashMap<A, T, (), S>,
+}
+
+impl<A, T> HashSet<A, T, FixedState>
+where
+    A: Alloc,
+{
+    /// Creates an empty `HashSet`.
+    ///
+    /// Does not allocate anything on its own.
+    pub fn new() -> Self {
+        HashSet {
+            map: HashMap::new(),
+        }
+    }
+
+    /// Creates an empty `HashSet` with the specified capacity.
+    pub fn with_capacity(capacity: usize) -> Self {
+        HashSet {
+            map: HashMap::with_capacity(capacity),
+        }
+    }
+}
+
+impl<A, T, S> HashSet<A, T, S>
+where
+    A: Alloc,
+{
+    /// Creates a new empty hash set which will use the given hasher to hash keys.
+    pub fn with_hasher(hasher: S) -> Self {
+        HashSet {
+            map: HashMap::with_hasher(hasher),
+        }
+    }
+
+    /// Creates an empty `HashSet` with the specified capacity, using `hasher` to hash the keys.
+    pub fn with_capacity_and_hasher(capacity: usize, hasher: S) -> Self {
+        HashSet {
+            map: HashMap::with_capacity_and_hasher(capacity, hasher),
+        }
+    }
+
+    /// Returns a reference to the set's `BuildHasher`.
+    pub fn hasher(&self) -> &S {
+        self.map.hasher()
+    }
+
+    /// Returns the number of elements the set can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.map.capacity()
+    }
+
+    /// Returns the number of elements in the set.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns `true` if the set contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Clears the set, removing all values. Keeps the allocated memory for reuse.
+    pub fn clear(&mut self) {
+        self.map.clear()
+    }
+
+    /// An iterator visiting all elements in arbitrary order.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            inner: self.map.keys(),
+        }
+    }
+
+    /// Clears the set, returning all elements in an iterator. Keeps the allocated memory for
+    /// reuse.
+    pub fn drain(&mut self) -> Drain<'_, A, T> {
+        Drain {
+            inner: self.map.drain(),
+        }
+    }
+
+    /// Retains only the elements specified by the predicate.
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.map.retain(|k, _| f(k))
+    }
+}
+
+impl<A, T, S> HashSet<A, T, S>
+where
+    A: Alloc,
+    T: Eq + Hash,
+    S: BuildHasher,
+{
+    /// Reserves capacity for at least `additional` more elements to be inserted in the
+    /// `HashSet`.
+    pub fn reserve(&mut self, additional: usize) {
+        self.map.reserve(additional)
+    }
+
+    /// Tries to reserve capacity for at least `additional` more elements to be inserted in the
+    /// `HashSet`.
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.map.try_reserve(additional)
+    }
+
+    /// Shrinks the capacity of the set as much as possible.
+    pub fn shrink_to_fit(&mut self) {
+        self.map.shrink_to_fit()
+    }
+
+    /// Returns `true` if the set contains a value.
+    pub fn contains<Q>(&self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.map.contains_key(value)
+    }
+
+    /// Returns a reference to the value in the set, if any, that is equal to the given value.
+    pub fn get<Q>(&self, value: &Q) -> Option<&T>
+    where
+        T: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.map.get_key_value(value).map(|(k, _)| k)
+    }
+
+    /// Adds a value to the set.
+    ///
+    /// If the set did not have this value present, `true` is returned.
+    pub fn insert(&mut self, value: T) -> bool {
+        match self.map.entry(value) {
+            hash_map::Entry::Occupied(_) => false,
+            hash_map::Entry::Vacant(entry) => {
+                entry.insert(());
+                true
+            }
+        }
+    }
+
+    /// Removes a value from the set. Returns whether the value was present in the set.
+    pub fn remove<Q>(&mut self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.map.remove(value).is_some()
+    }
+
+    /// Removes and returns the value in the set, if any, that is equal to the given one.
+    pub fn take<Q>(&mut self, value: &Q) -> Option<T>
+    where
+        T: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.map.remove_entry(value).map(|(k, _)| k)
+    }
+}
+
+/// An iterator over the items of a `HashSet`.
+///
+/// This `struct` is created by the `iter` method on `HashSet`.
+pub struct Iter<'a, T> {
+    inner: hash_map::Keys<'a, T, ()>,
+}
+
+impl<'a, T> Clone for Iter<'a, T> {
+    fn clone(&self) -> Self {
+        Iter {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {}
+
+/// A draining iterator over the items of a `HashSet`.
+///
+/// This `struct` is created by the `drain` method on `HashSet`.
+pub struct Drain<'a, A, T>
+where
+    A: Alloc,
+{
+    inner: hash_map::Drain<'a, A, T, ()>,
+}
+
+impl<'a, A, T> Iterator for Drain<'a, A, T>
+where
+    A: Alloc,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.inner.next().map(|(k, _)| k)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, A, T> ExactSizeIterator for Drain<'a, A, T> where A: Alloc {}
+
+/// An owning iterator over the items of a `HashSet`.
+///
+/// This `struct` is created by the `into_iter` method on `HashSet`.
+pub struct IntoIter<A, T>
+where
+    A: Alloc,
+{
+    inner: hash_map::IntoIter<A, T, ()>,
+}
+
+impl<A, T> Iterator for IntoIter<A, T>
+where
+    A: Alloc,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.inner.next().map(|(k, _)| k)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<A, T> ExactSizeIterator for IntoIter<A, T> where A: Alloc {}
+
+impl<A, T, S> IntoIterator for HashSet<A, T, S>
+where
+    A: Alloc,
+{
+    type Item = T;
+    type IntoIter = IntoIter<A, T>;
+
+    fn into_iter(self) -> IntoIter<A, T> {
+        IntoIter {
+            inner: self.map.into_iter(),
+        }
+    }
+}
+
+impl<'a, A, T, S> IntoIterator for &'a HashSet<A, T, S>
+where
+    A: Alloc,
+{
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<A, T, S> Extend<T> for HashSet<A, T, S>
+where
+    A: Alloc,
+    T: Eq + Hash,
+    S: BuildHasher,
+{
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        self.map.extend(iter.into_iter().map(|k| (k, ())))
+    }
+}
+
+impl<'a, A, T, S> Extend<&'a T> for HashSet<A, T, S>
+where
+    A: Alloc,
+    T: 'a + Eq + Hash + Copy,
+    S: BuildHasher,
+{
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = &'a T>,
+    {
+        self.extend(iter.into_iter().cloned())
+    }
+}
+
+impl<A, T, S> FromIterator<T> for HashSet<A, T, S>
+where
+    A: Alloc,
+    T: Eq + Hash,
+    S: BuildHasher + Default,
+{
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let mut set = HashSet::with_hasher(S::default());
+        set.extend(iter);
+        set
+    }
+}
+
+impl<A, T, S> Clone for HashSet<A, T, S>
+where
+    A: Alloc,
+    T: Clone + Eq + Hash,
+    S: BuildHasher + Clone,
+{
+    fn clone(&self) -> Self {
+        HashSet {
+            map: self.map.clone(),
+        }
+    }
+}
+
+impl<A, T, S> Default for HashSet<A, T, S>
+where
+    A: Alloc,
+    S: Default,
+{
+    fn default() -> Self {
+        HashSet::with_hasher(S::default())
+    }
+}
+
+impl<A, T, S> fmt::Debug for HashSet<A, T, S>
+where
+    A: Alloc,
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<A, T, S> Eq for HashSet<A, T, S>
+where
+    A: Alloc,
+    T: Eq + Hash,
+    S: BuildHasher,
+{
+}
+
+impl<A, B, T, S, U> PartialEq<HashSet<B, T, U>> for HashSet<A, T, S>
+where
+    A: Alloc,
+    B: Alloc,
+    T: Eq + Hash,
+    U: BuildHasher,
+{
+    fn eq(&self, other: &HashSet<B, T, U>) -> bool {
+        self.map == other.map
+    }
+}
@@ -5,24 +5,112 @@
 //!
 //! # Minimum Supported Rust Version (MSRV)
 //!
-//! This crate is guaranteed to compile on stable Rust 1.33 and up. It might compile on older
+//! This crate is guaranteed to compile on stable Rust 1.36 and up. It might compile on older
 //! versions but that may change in any new patch release.
+//!
+//! # Cargo features
+//!
+//! - `nightly`: implements `CoerceUnsized` for `Box` so that e.g. `Box<A, T>` implicitly coerces
+//!   into `Box<A, dyn Trait>`. Requires a nightly compiler. On stable use the `unsize_box!` macro
+//!   instead.
 
 #![deny(missing_docs)]
 #![deny(rust_2018_compatibility)]
 #![deny(rust_2018_idioms)]
 #![deny(warnings)]
 #![no_std]
+#![cfg_attr(
+    feature = "nightly",
+    feature(coerce_unsized, fn_traits, tuple_trait, unboxed_closures, unsize)
+)]
+
+#[cfg(test)]
+extern crate std;
+
+use core::{alloc::Layout, any::TypeId, fmt};
 
-use core::alloc::Layout;
+use alloc_many::Alloc;
 
+pub mod binary_heap;
 pub mod boxed;
+pub mod btree_map;
+pub mod btree_set;
+pub mod hash_map;
+pub mod hash_set;
+pub mod linked_list;
+pub mod rc;
+pub mod string;
+#[cfg(not(armv6m))]
+pub mod sync;
 #[cfg(test)]
 mod tests;
 mod unique;
 pub mod vec;
+pub mod vec_deque;
+
+/// The error type for fallible allocation methods like `Box::try_new` and `Vec::try_reserve`
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TryReserveError {
+    /// The computed capacity exceeded the collection's maximum (usually `isize::MAX` bytes)
+    CapacityOverflow,
+
+    /// The allocator returned an error
+    AllocError {
+        /// The layout of the allocation request that failed
+        layout: Layout,
+    },
+}
+
+impl fmt::Display for TryReserveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TryReserveError::CapacityOverflow => f.write_str("capacity overflow"),
+            TryReserveError::AllocError { layout } => write!(
+                f,
+                "memory allocation of {} bytes (align = {}) failed",
+                layout.size(),
+                layout.align()
+            ),
+        }
+    }
+}
+
+// the infallible API diverges here
+fn handle_reserve_error<A>(err: TryReserveError) -> !
+where
+    A: Alloc,
+{
+    match err {
+        TryReserveError::CapacityOverflow => panic!("capacity overflow"),
+        TryReserveError::AllocError { layout } => A::oom(layout),
+    }
+}
+
+// whether `A` and `B` are the same allocator; used to skip reallocations when moving data between
+// allocators
+fn same_alloc<A, B>() -> bool
+where
+    A: 'static,
+    B: 'static,
+{
+    TypeId::of::<A>() == TypeId::of::<B>()
+}
+
+// reference counts are not allowed to grow past this value; `mem::forget`-ing clones in a loop
+// could otherwise wrap them around and free the value while it's still in use
+const MAX_REFCOUNT: usize = isize::max_value() as usize;
+
+// `core` has no stable `abort` so we panic while unwinding from another panic, which aborts
+#[cold]
+fn abort() -> ! {
+    struct Abort;
+
+    impl Drop for Abort {
+        fn drop(&mut self) {
+            panic!("reference count overflow");
+        }
+    }
 
-#[allow(improper_ctypes)]
-extern "Rust" {
-    fn alloc_many_oom(layout: Layout) -> !;
+    let _abort = Abort;
+    panic!("reference count overflow")
 }
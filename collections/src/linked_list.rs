@@ -0,0 +1,835 @@
+//! A doubly-linked list with owned nodes.
+
+use core::{
+    alloc::Layout,
+    cmp, fmt,
+    hash::{Hash, Hasher},
+    iter::FromIterator,
+    marker::PhantomData,
+    mem,
+    ptr::{self, NonNull},
+};
+
+use alloc_many::Alloc;
+
+use crate::TryReserveError;
+
+struct Node<T> {
+    next: Option<NonNull<Node<T>>>,
+    prev: Option<NonNull<Node<T>>>,
+    element: T,
+}
+
+/// A doubly-linked list with owned nodes.
+///
+/// Each node is a separate allocation on the allocator `A`.
+pub struct LinkedList<A, T>
+where
+    A: Alloc,
+{
+    head: Option<NonNull<Node<T>>>,
+    tail: Option<NonNull<Node<T>>>,
+    len: usize,
+    _allocator: PhantomData<A>,
+    _marker: PhantomData<Node<T>>,
+}
+
+unsafe impl<A, T> Send for LinkedList<A, T>
+where
+    A: Alloc,
+    T: Send,
+{
+}
+
+unsafe impl<A, T> Sync for LinkedList<A, T>
+where
+    A: Alloc,
+    T: Sync,
+{
+}
+
+fn alloc_node<A, T>(element: T) -> Result<NonNull<Node<T>>, TryReserveError>
+where
+    A: Alloc,
+{
+    let layout = Layout::new::<Node<T>>();
+
+    unsafe {
+        let node = NonNull::new(A::alloc(layout) as *mut Node<T>)
+            .ok_or(TryReserveError::AllocError { layout })?;
+
+        node.as_ptr().write(Node {
+            next: None,
+            prev: None,
+            element,
+        });
+
+        Ok(node)
+    }
+}
+
+// NOTE `node` must be unlinked
+unsafe fn free_node<A, T>(node: NonNull<Node<T>>) -> T
+where
+    A: Alloc,
+{
+    let element = ptr::read(&(*node.as_ptr()).element);
+    A::dealloc(node.as_ptr() as *mut u8, Layout::new::<Node<T>>());
+    element
+}
+
+impl<A, T> LinkedList<A, T>
+where
+    A: Alloc,
+{
+    /// Creates an empty `LinkedList`.
+    pub fn new() -> Self {
+        LinkedList {
+            head: None,
+            tail: None,
+            len: 0,
+            _allocator: PhantomData,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the length of the `LinkedList`.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the `LinkedList` is empty.
+    pub fn is_empty(&self) -> bool {
+        self.head.is_none()
+    }
+
+    /// Removes all elements from the `LinkedList`.
+    pub fn clear(&mut self) {
+        *self = LinkedList::new();
+    }
+
+    /// Returns `true` if the `LinkedList` contains an element equal to the given value.
+    pub fn contains(&self, x: &T) -> bool
+    where
+        T: PartialEq,
+    {
+        self.iter().any(|e| e == x)
+    }
+
+    /// Provides a reference to the front element, or `None` if the list is empty.
+    pub fn front(&self) -> Option<&T> {
+        self.head.map(|node| unsafe { &(*node.as_ptr()).element })
+    }
+
+    /// Provides a mutable reference to the front element, or `None` if the list is empty.
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        self.head
+            .map(|node| unsafe { &mut (*node.as_ptr()).element })
+    }
+
+    /// Provides a reference to the back element, or `None` if the list is empty.
+    pub fn back(&self) -> Option<&T> {
+        self.tail.map(|node| unsafe { &(*node.as_ptr()).element })
+    }
+
+    /// Provides a mutable reference to the back element, or `None` if the list is empty.
+    pub fn back_mut(&mut self) -> Option<&mut T> {
+        self.tail
+            .map(|node| unsafe { &mut (*node.as_ptr()).element })
+    }
+
+    /// Adds an element first in the list.
+    pub fn push_front(&mut self, elt: T) {
+        self.try_push_front(elt)
+            .unwrap_or_else(|e| crate::handle_reserve_error::<A>(e))
+    }
+
+    /// Adds an element first in the list, returning an error if the allocation of the node fails
+    pub fn try_push_front(&mut self, elt: T) -> Result<(), TryReserveError> {
+        let node = alloc_node::<A, T>(elt)?;
+        unsafe { self.link(None, self.head, node) }
+        Ok(())
+    }
+
+    /// Appends an element to the back of the list.
+    pub fn push_back(&mut self, elt: T) {
+        self.try_push_back(elt)
+            .unwrap_or_else(|e| crate::handle_reserve_error::<A>(e))
+    }
+
+    /// Appends an element to the back of the list, returning an error if the allocation of the
+    /// node fails
+    pub fn try_push_back(&mut self, elt: T) -> Result<(), TryReserveError> {
+        let node = alloc_node::<A, T>(elt)?;
+        unsafe { self.link(self.tail, None, node) }
+        Ok(())
+    }
+
+    /// Removes the first element and returns it, or `None` if the list is empty.
+    pub fn pop_front(&mut self) -> Option<T> {
+        self.head.map(|node| unsafe {
+            self.unlink(node);
+            free_node::<A, T>(node)
+        })
+    }
+
+    /// Removes the last element from the list and returns it, or `None` if it is empty.
+    pub fn pop_back(&mut self) -> Option<T> {
+        self.tail.map(|node| unsafe {
+            self.unlink(node);
+            free_node::<A, T>(node)
+        })
+    }
+
+    /// Moves all elements from `other` to the end of the list.
+    ///
+    /// This reuses all the nodes from `other` and moves them into `self`. After this operation,
+    /// `other` becomes empty. This operation computes in O(1) time.
+    pub fn append(&mut self, other: &mut Self) {
+        match self.tail {
+            None => mem::swap(self, other),
+            Some(tail) => {
+                if let Some(other_head) = other.head.take() {
+                    unsafe {
+                        (*tail.as_ptr()).next = Some(other_head);
+                        (*other_head.as_ptr()).prev = Some(tail);
+                    }
+
+                    self.tail = other.tail.take();
+                    self.len += mem::replace(&mut other.len, 0);
+                }
+            }
+        }
+    }
+
+    /// Splits the list into two at the given index. Returns everything after the given index,
+    /// including the index.
+    ///
+    /// This operation computes in O(n) time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at > len`.
+    pub fn split_off(&mut self, at: usize) -> Self {
+        let len = self.len();
+        assert!(at <= len, "Cannot split off at a nonexistent index");
+
+        let mut second = LinkedList::new();
+        if at == 0 {
+            mem::swap(self, &mut second);
+            return second;
+        } else if at == len {
+            return second;
+        }
+
+        unsafe {
+            // the last node that stays in `self`
+            let split = self.node_at(at - 1);
+            let second_head = (*split.as_ptr()).next.take();
+
+            if let Some(head) = second_head {
+                (*head.as_ptr()).prev = None;
+            }
+
+            second.head = second_head;
+            second.tail = self.tail;
+            second.len = len - at;
+
+            self.tail = Some(split);
+            self.len = at;
+
+            second
+        }
+    }
+
+    /// Provides a forward iterator.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            head: self.head,
+            tail: self.tail,
+            len: self.len,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Provides a forward iterator with mutable references.
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            head: self.head,
+            tail: self.tail,
+            len: self.len,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Provides a cursor with editing operations at the front element.
+    ///
+    /// The cursor is pointing to the "ghost" non-element if the list is empty.
+    pub fn cursor_front_mut(&mut self) -> CursorMut<'_, A, T> {
+        CursorMut {
+            index: 0,
+            current: self.head,
+            list: self,
+        }
+    }
+
+    /// Provides a cursor with editing operations at the back element.
+    ///
+    /// The cursor is pointing to the "ghost" non-element if the list is empty.
+    pub fn cursor_back_mut(&mut self) -> CursorMut<'_, A, T> {
+        CursorMut {
+            index: self.len.saturating_sub(1),
+            current: self.tail,
+            list: self,
+        }
+    }
+
+    // NOTE `index` must be in bounds
+    unsafe fn node_at(&self, index: usize) -> NonNull<Node<T>> {
+        // walk from the closer end
+        if index < self.len / 2 {
+            let mut node = self.head.unwrap();
+            for _ in 0..index {
+                node = (*node.as_ptr()).next.unwrap();
+            }
+            node
+        } else {
+            let mut node = self.tail.unwrap();
+            for _ in index + 1..self.len {
+                node = (*node.as_ptr()).prev.unwrap();
+            }
+            node
+        }
+    }
+
+    // links a detached `node` between `prev` and `next`, which must be adjacent
+    unsafe fn link(
+        &mut self,
+        prev: Option<NonNull<Node<T>>>,
+        next: Option<NonNull<Node<T>>>,
+        node: NonNull<Node<T>>,
+    ) {
+        (*node.as_ptr()).prev = prev;
+        (*node.as_ptr()).next = next;
+
+        match prev {
+            Some(prev) => (*prev.as_ptr()).next = Some(node),
+            None => self.head = Some(node),
+        }
+
+        match next {
+            Some(next) => (*next.as_ptr()).prev = Some(node),
+            None => self.tail = Some(node),
+        }
+
+        self.len += 1;
+    }
+
+    // detaches `node` from the list without freeing it
+    unsafe fn unlink(&mut self, node: NonNull<Node<T>>) {
+        let node = &mut *node.as_ptr();
+
+        match node.prev {
+            Some(prev) => (*prev.as_ptr()).next = node.next,
+            None => self.head = node.next,
+        }
+
+        match node.next {
+            Some(next) => (*next.as_ptr()).prev = node.prev,
+            None => self.tail = node.prev,
+        }
+
+        node.prev = None;
+        node.next = None;
+        self.len -= 1;
+    }
+}
+
+impl<A, T> Drop for LinkedList<A, T>
+where
+    A: Alloc,
+{
+    fn drop(&mut self) {
+        while self.pop_front().is_some() {}
+    }
+}
+
+/// A cursor over a `LinkedList` with editing operations.
+///
+/// A cursor points to an element or to a "ghost" non-element that sits between the tail and the
+/// head of the list.
+///
+/// This `struct` is created by the `cursor_front_mut` and `cursor_back_mut` methods on
+/// `LinkedList`.
+pub struct CursorMut<'a, A, T>
+where
+    A: Alloc,
+{
+    // NOTE equal to `list.len` when pointing to the "ghost" non-element
+    index: usize,
+    current: Option<NonNull<Node<T>>>,
+    list: &'a mut LinkedList<A, T>,
+}
+
+impl<'a, A, T> CursorMut<'a, A, T>
+where
+    A: Alloc,
+{
+    /// Returns the cursor position index within the `LinkedList`.
+    ///
+    /// This returns `None` if the cursor is currently pointing to the "ghost" non-element.
+    pub fn index(&self) -> Option<usize> {
+        self.current.map(|_| self.index)
+    }
+
+    /// Moves the cursor to the next element of the `LinkedList`.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this will move it to the first
+    /// element of the `LinkedList`. If it is pointing to the last element of the `LinkedList`
+    /// then this will move it to the "ghost" non-element.
+    pub fn move_next(&mut self) {
+        match self.current {
+            None => {
+                self.current = self.list.head;
+                self.index = 0;
+            }
+            Some(current) => unsafe {
+                self.current = (*current.as_ptr()).next;
+                self.index += 1;
+            },
+        }
+    }
+
+    /// Moves the cursor to the previous element of the `LinkedList`.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this will move it to the last
+    /// element of the `LinkedList`. If it is pointing to the first element of the `LinkedList`
+    /// then this will move it to the "ghost" non-element.
+    pub fn move_prev(&mut self) {
+        match self.current {
+            None => {
+                self.current = self.list.tail;
+                self.index = self.list.len.saturating_sub(1);
+            }
+            Some(current) => unsafe {
+                self.current = (*current.as_ptr()).prev;
+                self.index = self.index.checked_sub(1).unwrap_or(self.list.len);
+            },
+        }
+    }
+
+    /// Returns a reference to the element that the cursor is currently pointing to.
+    ///
+    /// This returns `None` if the cursor is currently pointing to the "ghost" non-element.
+    pub fn current(&mut self) -> Option<&mut T> {
+        self.current
+            .map(|current| unsafe { &mut (*current.as_ptr()).element })
+    }
+
+    /// Returns a reference to the next element.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this returns the first element
+    /// of the `LinkedList`. If it is pointing to the last element of the `LinkedList` then this
+    /// returns `None`.
+    pub fn peek_next(&mut self) -> Option<&mut T> {
+        unsafe {
+            let next = match self.current {
+                None => self.list.head,
+                Some(current) => (*current.as_ptr()).next,
+            };
+
+            next.map(|next| &mut (*next.as_ptr()).element)
+        }
+    }
+
+    /// Returns a reference to the previous element.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this returns the last element of
+    /// the `LinkedList`. If it is pointing to the first element of the `LinkedList` then this
+    /// returns `None`.
+    pub fn peek_prev(&mut self) -> Option<&mut T> {
+        unsafe {
+            let prev = match self.current {
+                None => self.list.tail,
+                Some(current) => (*current.as_ptr()).prev,
+            };
+
+            prev.map(|prev| &mut (*prev.as_ptr()).element)
+        }
+    }
+
+    /// Inserts a new element into the `LinkedList` after the current one.
+    ///
+    /// If the cursor is pointing at the "ghost" non-element then the new element is inserted at
+    /// the front of the `LinkedList`.
+    pub fn insert_after(&mut self, item: T) {
+        self.try_insert_after(item)
+            .unwrap_or_else(|e| crate::handle_reserve_error::<A>(e))
+    }
+
+    /// Inserts a new element into the `LinkedList` after the current one, returning an error if
+    /// the allocation of the node fails
+    pub fn try_insert_after(&mut self, item: T) -> Result<(), TryReserveError> {
+        let node = alloc_node::<A, T>(item)?;
+
+        unsafe {
+            match self.current {
+                None => {
+                    self.list.link(None, self.list.head, node);
+                    self.index = self.list.len;
+                }
+                Some(current) => self
+                    .list
+                    .link(Some(current), (*current.as_ptr()).next, node),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Inserts a new element into the `LinkedList` before the current one.
+    ///
+    /// If the cursor is pointing at the "ghost" non-element then the new element is inserted at
+    /// the end of the `LinkedList`.
+    pub fn insert_before(&mut self, item: T) {
+        self.try_insert_before(item)
+            .unwrap_or_else(|e| crate::handle_reserve_error::<A>(e))
+    }
+
+    /// Inserts a new element into the `LinkedList` before the current one, returning an error if
+    /// the allocation of the node fails
+    pub fn try_insert_before(&mut self, item: T) -> Result<(), TryReserveError> {
+        let node = alloc_node::<A, T>(item)?;
+
+        unsafe {
+            match self.current {
+                None => self.list.link(self.list.tail, None, node),
+                Some(current) => self
+                    .list
+                    .link((*current.as_ptr()).prev, Some(current), node),
+            }
+        }
+        self.index += 1;
+
+        Ok(())
+    }
+
+    /// Removes the current element from the `LinkedList`.
+    ///
+    /// The element that was removed is returned, and the cursor is moved to point to the next
+    /// element in the `LinkedList`.
+    ///
+    /// If the cursor is currently pointing to the "ghost" non-element then no element is removed
+    /// and `None` is returned.
+    pub fn remove_current(&mut self) -> Option<T> {
+        self.current.map(|current| unsafe {
+            self.current = (*current.as_ptr()).next;
+            self.list.unlink(current);
+            free_node::<A, T>(current)
+        })
+    }
+}
+
+/// An iterator over the elements of a `LinkedList`.
+///
+/// This `struct` is created by the `iter` method on `LinkedList`.
+pub struct Iter<'a, T> {
+    head: Option<NonNull<Node<T>>>,
+    tail: Option<NonNull<Node<T>>>,
+    len: usize,
+    _marker: PhantomData<&'a Node<T>>,
+}
+
+impl<'a, T> Clone for Iter<'a, T> {
+    fn clone(&self) -> Self {
+        Iter {
+            head: self.head,
+            tail: self.tail,
+            len: self.len,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        if self.len == 0 {
+            None
+        } else {
+            self.head.map(|node| unsafe {
+                let node = &*node.as_ptr();
+                self.len -= 1;
+                self.head = node.next;
+                &node.element
+            })
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<&'a T> {
+        if self.len == 0 {
+            None
+        } else {
+            self.tail.map(|node| unsafe {
+                let node = &*node.as_ptr();
+                self.len -= 1;
+                self.tail = node.prev;
+                &node.element
+            })
+        }
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {}
+
+/// A mutable iterator over the elements of a `LinkedList`.
+///
+/// This `struct` is created by the `iter_mut` method on `LinkedList`.
+pub struct IterMut<'a, T> {
+    head: Option<NonNull<Node<T>>>,
+    tail: Option<NonNull<Node<T>>>,
+    len: usize,
+    _marker: PhantomData<&'a mut Node<T>>,
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<&'a mut T> {
+        if self.len == 0 {
+            None
+        } else {
+            self.head.map(|node| unsafe {
+                let node = &mut *node.as_ptr();
+                self.len -= 1;
+                self.head = node.next;
+                &mut node.element
+            })
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
+    fn next_back(&mut self) -> Option<&'a mut T> {
+        if self.len == 0 {
+            None
+        } else {
+            self.tail.map(|node| unsafe {
+                let node = &mut *node.as_ptr();
+                self.len -= 1;
+                self.tail = node.prev;
+                &mut node.element
+            })
+        }
+    }
+}
+
+impl<'a, T> ExactSizeIterator for IterMut<'a, T> {}
+
+/// An owning iterator over the elements of a `LinkedList`.
+///
+/// This `struct` is created by the `into_iter` method on `LinkedList`.
+pub struct IntoIter<A, T>
+where
+    A: Alloc,
+{
+    list: LinkedList<A, T>,
+}
+
+impl<A, T> Iterator for IntoIter<A, T>
+where
+    A: Alloc,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.list.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.list.len, Some(self.list.len))
+    }
+}
+
+impl<A, T> DoubleEndedIterator for IntoIter<A, T>
+where
+    A: Alloc,
+{
+    fn next_back(&mut self) -> Option<T> {
+        self.list.pop_back()
+    }
+}
+
+impl<A, T> ExactSizeIterator for IntoIter<A, T> where A: Alloc {}
+
+impl<A, T> IntoIterator for LinkedList<A, T>
+where
+    A: Alloc,
+{
+    type Item = T;
+    type IntoIter = IntoIter<A, T>;
+
+    fn into_iter(self) -> IntoIter<A, T> {
+        IntoIter { list: self }
+    }
+}
+
+impl<'a, A, T> IntoIterator for &'a LinkedList<A, T>
+where
+    A: Alloc,
+{
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, A, T> IntoIterator for &'a mut LinkedList<A, T>
+where
+    A: Alloc,
+{
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+impl<A, T> Extend<T> for LinkedList<A, T>
+where
+    A: Alloc,
+{
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        for elt in iter {
+            self.push_back(elt);
+        }
+    }
+}
+
+impl<'a, A, T> Extend<&'a T> for LinkedList<A, T>
+where
+    A: Alloc,
+    T: 'a + Copy,
+{
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = &'a T>,
+    {
+        self.extend(iter.into_iter().cloned())
+    }
+}
+
+impl<A, T> FromIterator<T> for LinkedList<A, T>
+where
+    A: Alloc,
+{
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let mut list = LinkedList::new();
+        list.extend(iter);
+        list
+    }
+}
+
+impl<A, T> Clone for LinkedList<A, T>
+where
+    A: Alloc,
+    T: Clone,
+{
+    fn clone(&self) -> Self {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<A, T> Default for LinkedList<A, T>
+where
+    A: Alloc,
+{
+    fn default() -> Self {
+        LinkedList::new()
+    }
+}
+
+impl<A, T> fmt::Debug for LinkedList<A, T>
+where
+    A: Alloc,
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<A, T> Hash for LinkedList<A, T>
+where
+    A: Alloc,
+    T: Hash,
+{
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: Hasher,
+    {
+        self.len.hash(state);
+        for elt in self {
+            elt.hash(state);
+        }
+    }
+}
+
+impl<A, T> Eq for LinkedList<A, T>
+where
+    A: Alloc,
+    T: Eq,
+{
+}
+
+impl<A, B, T> PartialEq<LinkedList<B, T>> for LinkedList<A, T>
+where
+    A: Alloc,
+    B: Alloc,
+    T: PartialEq,
+{
+    fn eq(&self, other: &LinkedList<B, T>) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl<A, T> Ord for LinkedList<A, T>
+where
+    A: Alloc,
+    T: Ord,
+{
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<A, B, T> PartialOrd<LinkedList<B, T>> for LinkedList<A, T>
+where
+    A: Alloc,
+    B: Alloc,
+    T: PartialOrd,
+{
+    fn partial_cmp(&self, other: &LinkedList<B, T>) -> Option<cmp::Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
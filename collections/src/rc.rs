@@ -0,0 +1,448 @@
+//! Single-threaded reference-counting pointers.
+
+use core::{
+    alloc::Layout,
+    borrow::Borrow,
+    cell::Cell,
+    cmp, fmt,
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+    mem, ops,
+    ptr::{self, NonNull},
+};
+
+use alloc_many::Alloc;
+
+use crate::TryReserveError;
+
+struct RcBox<T>
+where
+    T: ?Sized,
+{
+    strong: Cell<usize>,
+    // NOTE all the strong pointers collectively hold one weak reference
+    weak: Cell<usize>,
+    value: T,
+}
+
+impl<T> RcBox<T>
+where
+    T: ?Sized,
+{
+    fn inc_strong(&self) {
+        let strong = self.strong.get();
+        if strong >= crate::MAX_REFCOUNT {
+            crate::abort()
+        }
+        self.strong.set(strong + 1);
+    }
+
+    fn inc_weak(&self) {
+        let weak = self.weak.get();
+        if weak >= crate::MAX_REFCOUNT {
+            crate::abort()
+        }
+        self.weak.set(weak + 1);
+    }
+}
+
+/// A single-threaded reference-counting pointer.
+pub struct Rc<A, T>
+where
+    A: Alloc,
+    T: ?Sized,
+{
+    _allocator: PhantomData<A>,
+    ptr: NonNull<RcBox<T>>,
+    _marker: PhantomData<RcBox<T>>,
+}
+
+/// `Weak` is a version of `Rc` that holds a non-owning reference to the managed value.
+pub struct Weak<A, T>
+where
+    A: Alloc,
+    T: ?Sized,
+{
+    _allocator: PhantomData<A>,
+    ptr: NonNull<RcBox<T>>,
+}
+
+impl<A, T> Rc<A, T>
+where
+    A: Alloc,
+{
+    /// Constructs a new `Rc<T>` on the allocator `A`.
+    pub fn new(value: T) -> Self {
+        Self::try_new(value).unwrap_or_else(|e| crate::handle_reserve_error::<A>(e))
+    }
+
+    /// Constructs a new `Rc<T>` on the allocator `A`, returning an error if the allocation fails
+    pub fn try_new(value: T) -> Result<Self, TryReserveError> {
+        unsafe {
+            let ptr = Self::allocate()?;
+            ptr.as_ptr().write(RcBox {
+                strong: Cell::new(1),
+                weak: Cell::new(1),
+                value,
+            });
+
+            Ok(Self::from_inner(ptr))
+        }
+    }
+
+    /// Returns the contained value, if the `Rc` has exactly one strong reference.
+    ///
+    /// Otherwise, an `Err` is returned with the same `Rc` that was passed in.
+    pub fn try_unwrap(this: Self) -> Result<T, Self> {
+        if Rc::strong_count(&this) != 1 {
+            return Err(this);
+        }
+
+        unsafe {
+            let value = ptr::read(&this.inner().value);
+
+            // the remaining `Weak`s can no longer be upgraded
+            this.inner().strong.set(0);
+            // release the implicit weak reference held by the strong pointers
+            drop(Weak::<A, T> {
+                _allocator: PhantomData,
+                ptr: this.ptr,
+            });
+            mem::forget(this);
+
+            Ok(value)
+        }
+    }
+
+    /// Makes a mutable reference into the given `Rc`.
+    ///
+    /// If there are other `Rc` pointers to the same allocation, then `make_mut` will clone the
+    /// inner value into a new allocation to ensure unique ownership. If there are no other `Rc`
+    /// pointers but there are `Weak` pointers then the value is moved into a new allocation and
+    /// the `Weak` pointers are disassociated from it.
+    pub fn make_mut(this: &mut Self) -> &mut T
+    where
+        T: Clone,
+    {
+        if Rc::strong_count(this) != 1 {
+            *this = Rc::new((**this).clone());
+        } else if Rc::weak_count(this) != 0 {
+            unsafe {
+                let fresh =
+                    Self::allocate().unwrap_or_else(|e| crate::handle_reserve_error::<A>(e));
+
+                let inner = this.inner();
+                fresh.as_ptr().write(RcBox {
+                    strong: Cell::new(1),
+                    weak: Cell::new(1),
+                    value: ptr::read(&inner.value),
+                });
+
+                // the old allocation is now only owned by its `Weak` pointers
+                inner.strong.set(0);
+                inner.weak.set(inner.weak.get() - 1);
+
+                ptr::write(this, Self::from_inner(fresh));
+            }
+        }
+
+        unsafe { &mut this.ptr.as_mut().value }
+    }
+
+    unsafe fn allocate() -> Result<NonNull<RcBox<T>>, TryReserveError> {
+        let layout = Layout::new::<RcBox<T>>();
+        NonNull::new(A::alloc(layout) as *mut RcBox<T>)
+            .ok_or(TryReserveError::AllocError { layout })
+    }
+}
+
+impl<A, T> Rc<A, T>
+where
+    A: Alloc,
+    T: ?Sized,
+{
+    fn from_inner(ptr: NonNull<RcBox<T>>) -> Self {
+        Rc {
+            _allocator: PhantomData,
+            ptr,
+            _marker: PhantomData,
+        }
+    }
+
+    fn inner(&self) -> &RcBox<T> {
+        unsafe { self.ptr.as_ref() }
+    }
+
+    /// Creates a new `Weak` pointer to this allocation.
+    pub fn downgrade(this: &Self) -> Weak<A, T> {
+        this.inner().inc_weak();
+
+        Weak {
+            _allocator: PhantomData,
+            ptr: this.ptr,
+        }
+    }
+
+    /// Gets the number of `Weak` pointers to this allocation.
+    pub fn weak_count(this: &Self) -> usize {
+        this.inner().weak.get() - 1
+    }
+
+    /// Gets the number of strong (`Rc`) pointers to this allocation.
+    pub fn strong_count(this: &Self) -> usize {
+        this.inner().strong.get()
+    }
+
+    /// Returns a mutable reference into the given `Rc`, if there are no other `Rc` or `Weak`
+    /// pointers to the same allocation.
+    pub fn get_mut(this: &mut Self) -> Option<&mut T> {
+        if Rc::strong_count(this) == 1 && Rc::weak_count(this) == 0 {
+            unsafe { Some(&mut this.ptr.as_mut().value) }
+        } else {
+            None
+        }
+    }
+
+    /// Returns `true` if the two `Rc`s point to the same allocation.
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        this.ptr.as_ptr() as *const u8 == other.ptr.as_ptr() as *const u8
+    }
+}
+
+impl<A, T> Clone for Rc<A, T>
+where
+    A: Alloc,
+    T: ?Sized,
+{
+    fn clone(&self) -> Self {
+        self.inner().inc_strong();
+
+        Self::from_inner(self.ptr)
+    }
+}
+
+impl<A, T> ops::Deref for Rc<A, T>
+where
+    A: Alloc,
+    T: ?Sized,
+{
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.inner().value
+    }
+}
+
+impl<A, T> Drop for Rc<A, T>
+where
+    A: Alloc,
+    T: ?Sized,
+{
+    fn drop(&mut self) {
+        unsafe {
+            let strong = self.inner().strong.get() - 1;
+            self.inner().strong.set(strong);
+
+            if strong == 0 {
+                ptr::drop_in_place(&mut self.ptr.as_mut().value);
+
+                // release the implicit weak reference held by the strong pointers
+                drop(Weak::<A, T> {
+                    _allocator: PhantomData,
+                    ptr: self.ptr,
+                });
+            }
+        }
+    }
+}
+
+impl<A, T> Weak<A, T>
+where
+    A: Alloc,
+    T: ?Sized,
+{
+    fn inner(&self) -> &RcBox<T> {
+        unsafe { self.ptr.as_ref() }
+    }
+
+    /// Attempts to upgrade the `Weak` pointer to an `Rc`.
+    ///
+    /// Returns `None` if the inner value has since been dropped.
+    pub fn upgrade(&self) -> Option<Rc<A, T>> {
+        let inner = self.inner();
+
+        if inner.strong.get() == 0 {
+            None
+        } else {
+            inner.inc_strong();
+            Some(Rc::from_inner(self.ptr))
+        }
+    }
+
+    /// Gets the number of strong (`Rc`) pointers pointing to this allocation.
+    pub fn strong_count(&self) -> usize {
+        self.inner().strong.get()
+    }
+
+    /// Gets the number of `Weak` pointers pointing to this allocation.
+    ///
+    /// If no strong pointers remain, this will return zero.
+    pub fn weak_count(&self) -> usize {
+        let inner = self.inner();
+        if inner.strong.get() == 0 {
+            0
+        } else {
+            inner.weak.get() - 1
+        }
+    }
+
+    /// Returns `true` if the two `Weak`s point to the same allocation.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        self.ptr.as_ptr() as *const u8 == other.ptr.as_ptr() as *const u8
+    }
+}
+
+impl<A, T> Clone for Weak<A, T>
+where
+    A: Alloc,
+    T: ?Sized,
+{
+    fn clone(&self) -> Self {
+        self.inner().inc_weak();
+
+        Weak {
+            _allocator: PhantomData,
+            ptr: self.ptr,
+        }
+    }
+}
+
+impl<A, T> Drop for Weak<A, T>
+where
+    A: Alloc,
+    T: ?Sized,
+{
+    fn drop(&mut self) {
+        unsafe {
+            let weak = self.inner().weak.get() - 1;
+            self.inner().weak.set(weak);
+
+            if weak == 0 {
+                let layout = Layout::for_value(self.ptr.as_ref());
+                A::dealloc(self.ptr.as_ptr() as *mut u8, layout)
+            }
+        }
+    }
+}
+
+impl<A, T> fmt::Debug for Weak<A, T>
+where
+    A: Alloc,
+    T: ?Sized,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("(Weak)")
+    }
+}
+
+impl<A, T> fmt::Debug for Rc<A, T>
+where
+    A: Alloc,
+    T: ?Sized + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        <T as fmt::Debug>::fmt(self, f)
+    }
+}
+
+impl<A, T> fmt::Display for Rc<A, T>
+where
+    A: Alloc,
+    T: ?Sized + fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        <T as fmt::Display>::fmt(self, f)
+    }
+}
+
+impl<A, T> Default for Rc<A, T>
+where
+    A: Alloc,
+    T: Default,
+{
+    fn default() -> Self {
+        Rc::new(T::default())
+    }
+}
+
+impl<A, T> Hash for Rc<A, T>
+where
+    A: Alloc,
+    T: ?Sized + Hash,
+{
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: Hasher,
+    {
+        <T as Hash>::hash(self, state)
+    }
+}
+
+impl<A, T> AsRef<T> for Rc<A, T>
+where
+    A: Alloc,
+    T: ?Sized,
+{
+    fn as_ref(&self) -> &T {
+        self
+    }
+}
+
+impl<A, T> Borrow<T> for Rc<A, T>
+where
+    A: Alloc,
+    T: ?Sized,
+{
+    fn borrow(&self) -> &T {
+        self
+    }
+}
+
+impl<A, T> Eq for Rc<A, T>
+where
+    A: Alloc,
+    T: ?Sized + Eq,
+{
+}
+
+impl<A, B, T> PartialEq<Rc<B, T>> for Rc<A, T>
+where
+    A: Alloc,
+    B: Alloc,
+    T: ?Sized + PartialEq,
+{
+    fn eq(&self, other: &Rc<B, T>) -> bool {
+        <T as PartialEq>::eq(self, other)
+    }
+}
+
+impl<A, T> Ord for Rc<A, T>
+where
+    A: Alloc,
+    T: ?Sized + Ord,
+{
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        <T as Ord>::cmp(self, other)
+    }
+}
+
+impl<A, B, T> PartialOrd<Rc<B, T>> for Rc<A, T>
+where
+    A: Alloc,
+    B: Alloc,
+    T: ?Sized + PartialOrd,
+{
+    fn partial_cmp(&self, other: &Rc<B, T>) -> Option<cmp::Ordering> {
+        <T as PartialOrd>::partial_cmp(self, other)
+    }
+}
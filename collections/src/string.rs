@@ -0,0 +1,585 @@
+//! A UTF-8 encoded, growable string.
+
+use core::{
+    borrow::Borrow,
+    cmp, fmt,
+    hash::{Hash, Hasher},
+    iter::FromIterator,
+    ops, ptr,
+    str::{self, Utf8Error},
+};
+
+use alloc_many::Alloc;
+
+use crate::{boxed::Box, vec::Vec, TryReserveError};
+
+/// A UTF-8 encoded, growable string.
+pub struct String<A>
+where
+    A: Alloc,
+{
+    vec: Vec<A, u8>,
+}
+
+impl<A> String<A>
+where
+    A: Alloc,
+{
+    /// Creates a new empty `String`.
+    pub fn new() -> Self {
+        String { vec: Vec::new() }
+    }
+
+    /// Creates a new empty `String` with a particular capacity.
+    pub fn with_capacity(capacity: usize) -> Self {
+        String {
+            vec: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Creates a new empty `String` with a particular capacity, returning an error if the
+    /// allocation fails
+    pub fn try_with_capacity(capacity: usize) -> Result<Self, TryReserveError> {
+        Ok(String {
+            vec: Vec::try_with_capacity(capacity)?,
+        })
+    }
+
+    /// Converts a vector of bytes to a `String`.
+    ///
+    /// Returns the vector back, as part of the error, if the bytes are not valid UTF-8.
+    pub fn from_utf8(vec: Vec<A, u8>) -> Result<Self, FromUtf8Error<A>> {
+        match str::from_utf8(&vec) {
+            Ok(_) => Ok(String { vec }),
+            Err(error) => Err(FromUtf8Error { bytes: vec, error }),
+        }
+    }
+
+    /// Converts a vector of bytes to a `String` without checking that the string contains valid
+    /// UTF-8.
+    ///
+    /// # Safety
+    ///
+    /// `bytes` must be valid UTF-8.
+    pub unsafe fn from_utf8_unchecked(bytes: Vec<A, u8>) -> Self {
+        String { vec: bytes }
+    }
+
+    /// Converts a `String` into a byte vector.
+    pub fn into_bytes(self) -> Vec<A, u8> {
+        self.vec
+    }
+
+    /// Converts this `String` into a `Box<A, str>`, dropping any excess capacity.
+    pub fn into_boxed_str(self) -> Box<A, str> {
+        let bytes = self.vec.into_boxed_slice();
+        unsafe { Box::from_raw(Box::into_raw(bytes) as *mut str) }
+    }
+
+    /// Moves the contents of this `String` into the allocator `B`.
+    ///
+    /// If `B` is `A` the buffer is reused.
+    pub fn into_alloc<B>(self) -> String<B>
+    where
+        A: 'static,
+        B: Alloc + 'static,
+    {
+        String {
+            vec: self.vec.into_alloc(),
+        }
+    }
+
+    /// Moves the contents of this `String` into the allocator `B`, returning an error if the
+    /// allocation fails
+    ///
+    /// On error the original string is returned along with the error.
+    pub fn try_into_alloc<B>(self) -> Result<String<B>, (Self, TryReserveError)>
+    where
+        A: 'static,
+        B: Alloc + 'static,
+    {
+        match self.vec.try_into_alloc() {
+            Ok(vec) => Ok(String { vec }),
+            Err((vec, e)) => Err((String { vec }, e)),
+        }
+    }
+
+    /// Extracts a string slice containing the entire `String`.
+    pub fn as_str(&self) -> &str {
+        self
+    }
+
+    /// Converts a `String` into a mutable string slice.
+    pub fn as_mut_str(&mut self) -> &mut str {
+        self
+    }
+
+    /// Returns a byte slice of this `String`'s contents.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.vec
+    }
+
+    /// Returns a mutable reference to the contents of this `String`.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that the contents are valid UTF-8 when the reference is dropped.
+    pub unsafe fn as_mut_vec(&mut self) -> &mut Vec<A, u8> {
+        &mut self.vec
+    }
+
+    /// Returns the length of this `String`, in bytes.
+    pub fn len(&self) -> usize {
+        self.vec.len()
+    }
+
+    /// Returns `true` if this `String` has a length of zero.
+    pub fn is_empty(&self) -> bool {
+        self.vec.is_empty()
+    }
+
+    /// Returns this `String`'s capacity, in bytes.
+    pub fn capacity(&self) -> usize {
+        self.vec.capacity()
+    }
+
+    /// Ensures that this `String`'s capacity is at least `additional` bytes larger than its
+    /// length.
+    pub fn reserve(&mut self, additional: usize) {
+        self.vec.reserve(additional)
+    }
+
+    /// Tries to reserve capacity for at least `additional` more bytes
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.vec.try_reserve(additional)
+    }
+
+    /// Shrinks the capacity of this `String` to match its length.
+    pub fn shrink_to_fit(&mut self) {
+        self.vec.shrink_to_fit()
+    }
+
+    /// Appends a given string slice onto the end of this `String`.
+    pub fn push_str(&mut self, string: &str) {
+        self.vec.extend_from_slice(string.as_bytes())
+    }
+
+    /// Appends a given string slice onto the end of this `String`, returning an error if the
+    /// allocation fails
+    pub fn try_push_str(&mut self, string: &str) -> Result<(), TryReserveError> {
+        self.vec.try_reserve(string.len())?;
+        self.vec.extend_from_slice(string.as_bytes());
+        Ok(())
+    }
+
+    /// Appends the given `char` to the end of this `String`.
+    pub fn push(&mut self, ch: char) {
+        match ch.len_utf8() {
+            1 => self.vec.push(ch as u8),
+            _ => self.push_str(ch.encode_utf8(&mut [0; 4])),
+        }
+    }
+
+    /// Removes the last character from the string buffer and returns it.
+    pub fn pop(&mut self) -> Option<char> {
+        let ch = self.chars().next_back()?;
+        let new_len = self.len() - ch.len_utf8();
+        unsafe { self.vec.set_len(new_len) }
+        Some(ch)
+    }
+
+    /// Inserts a character into this `String` at a byte position.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is larger than the `String`'s length, or if it does not lie on a `char`
+    /// boundary.
+    pub fn insert(&mut self, idx: usize, ch: char) {
+        self.insert_str(idx, ch.encode_utf8(&mut [0; 4]))
+    }
+
+    /// Inserts a string slice into this `String` at a byte position.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is larger than the `String`'s length, or if it does not lie on a `char`
+    /// boundary.
+    pub fn insert_str(&mut self, idx: usize, string: &str) {
+        assert!(self.is_char_boundary(idx));
+
+        let len = self.len();
+        let amt = string.len();
+        self.vec.reserve(amt);
+
+        unsafe {
+            let p = self.vec.as_mut_ptr().add(idx);
+            ptr::copy(p, p.add(amt), len - idx);
+            ptr::copy_nonoverlapping(string.as_ptr(), p, amt);
+            self.vec.set_len(len + amt);
+        }
+    }
+
+    /// Shortens this `String` to the specified length.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `new_len` does not lie on a `char` boundary.
+    pub fn truncate(&mut self, new_len: usize) {
+        if new_len <= self.len() {
+            assert!(self.is_char_boundary(new_len));
+            self.vec.truncate(new_len)
+        }
+    }
+
+    /// Truncates this `String`, removing all contents.
+    pub fn clear(&mut self) {
+        self.vec.clear()
+    }
+}
+
+/// A possible error value when converting a `String` from a UTF-8 byte vector.
+pub struct FromUtf8Error<A>
+where
+    A: Alloc,
+{
+    bytes: Vec<A, u8>,
+    error: Utf8Error,
+}
+
+impl<A> FromUtf8Error<A>
+where
+    A: Alloc,
+{
+    /// Returns a slice of the bytes that were attempted to convert to a `String`.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Returns the bytes that were attempted to convert to a `String`.
+    pub fn into_bytes(self) -> Vec<A, u8> {
+        self.bytes
+    }
+
+    /// Fetch a `Utf8Error` to get more details about the conversion failure.
+    pub fn utf8_error(&self) -> Utf8Error {
+        self.error
+    }
+}
+
+impl<A> fmt::Debug for FromUtf8Error<A>
+where
+    A: Alloc,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FromUtf8Error")
+            .field("bytes", &self.bytes)
+            .field("error", &self.error)
+            .finish()
+    }
+}
+
+impl<A> fmt::Display for FromUtf8Error<A>
+where
+    A: Alloc,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.error, f)
+    }
+}
+
+impl<A> ops::Deref for String<A>
+where
+    A: Alloc,
+{
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        unsafe { str::from_utf8_unchecked(&self.vec) }
+    }
+}
+
+impl<A> ops::DerefMut for String<A>
+where
+    A: Alloc,
+{
+    fn deref_mut(&mut self) -> &mut str {
+        unsafe { str::from_utf8_unchecked_mut(&mut self.vec) }
+    }
+}
+
+impl<A> fmt::Write for String<A>
+where
+    A: Alloc,
+{
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.push_str(s);
+        Ok(())
+    }
+
+    fn write_char(&mut self, c: char) -> fmt::Result {
+        self.push(c);
+        Ok(())
+    }
+}
+
+impl<A> fmt::Debug for String<A>
+where
+    A: Alloc,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        <str as fmt::Debug>::fmt(self, f)
+    }
+}
+
+impl<A> fmt::Display for String<A>
+where
+    A: Alloc,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        <str as fmt::Display>::fmt(self, f)
+    }
+}
+
+impl<A> Clone for String<A>
+where
+    A: Alloc,
+{
+    fn clone(&self) -> Self {
+        String {
+            vec: self.vec.clone(),
+        }
+    }
+}
+
+impl<A> Default for String<A>
+where
+    A: Alloc,
+{
+    fn default() -> Self {
+        String::new()
+    }
+}
+
+impl<'a, A> From<&'a str> for String<A>
+where
+    A: Alloc,
+{
+    fn from(s: &'a str) -> Self {
+        let mut string = String::with_capacity(s.len());
+        string.push_str(s);
+        string
+    }
+}
+
+impl<A> From<String<A>> for Vec<A, u8>
+where
+    A: Alloc,
+{
+    fn from(s: String<A>) -> Self {
+        s.into_bytes()
+    }
+}
+
+impl<A> Hash for String<A>
+where
+    A: Alloc,
+{
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: Hasher,
+    {
+        <str as Hash>::hash(self, state)
+    }
+}
+
+impl<A> AsRef<str> for String<A>
+where
+    A: Alloc,
+{
+    fn as_ref(&self) -> &str {
+        self
+    }
+}
+
+impl<A> AsRef<[u8]> for String<A>
+where
+    A: Alloc,
+{
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl<A> Borrow<str> for String<A>
+where
+    A: Alloc,
+{
+    fn borrow(&self) -> &str {
+        self
+    }
+}
+
+impl<A> Extend<char> for String<A>
+where
+    A: Alloc,
+{
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = char>,
+    {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+
+        for ch in iter {
+            self.push(ch)
+        }
+    }
+}
+
+impl<'a, A> Extend<&'a str> for String<A>
+where
+    A: Alloc,
+{
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        for s in iter {
+            self.push_str(s)
+        }
+    }
+}
+
+impl<A> FromIterator<char> for String<A>
+where
+    A: Alloc,
+{
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = char>,
+    {
+        let mut s = String::new();
+        s.extend(iter);
+        s
+    }
+}
+
+impl<'a, A> FromIterator<&'a str> for String<A>
+where
+    A: Alloc,
+{
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        let mut s = String::new();
+        s.extend(iter);
+        s
+    }
+}
+
+impl<A> ops::Add<&str> for String<A>
+where
+    A: Alloc,
+{
+    type Output = String<A>;
+
+    fn add(mut self, other: &str) -> String<A> {
+        self.push_str(other);
+        self
+    }
+}
+
+impl<A> ops::AddAssign<&str> for String<A>
+where
+    A: Alloc,
+{
+    fn add_assign(&mut self, other: &str) {
+        self.push_str(other)
+    }
+}
+
+impl<A> Eq for String<A> where A: Alloc {}
+
+impl<A, B> PartialEq<String<B>> for String<A>
+where
+    A: Alloc,
+    B: Alloc,
+{
+    fn eq(&self, other: &String<B>) -> bool {
+        <str as PartialEq>::eq(self, other)
+    }
+}
+
+impl<A> PartialEq<str> for String<A>
+where
+    A: Alloc,
+{
+    fn eq(&self, other: &str) -> bool {
+        <str as PartialEq>::eq(self, other)
+    }
+}
+
+impl<'a, A> PartialEq<&'a str> for String<A>
+where
+    A: Alloc,
+{
+    fn eq(&self, other: &&'a str) -> bool {
+        <str as PartialEq>::eq(self, other)
+    }
+}
+
+impl<A> PartialEq<String<A>> for str
+where
+    A: Alloc,
+{
+    fn eq(&self, other: &String<A>) -> bool {
+        <str as PartialEq>::eq(self, other)
+    }
+}
+
+impl<A> PartialEq<String<A>> for &str
+where
+    A: Alloc,
+{
+    fn eq(&self, other: &String<A>) -> bool {
+        <str as PartialEq>::eq(self, other)
+    }
+}
+
+impl<A> Ord for String<A>
+where
+    A: Alloc,
+{
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        <str as Ord>::cmp(self, other)
+    }
+}
+
+impl<A, B> PartialOrd<String<B>> for String<A>
+where
+    A: Alloc,
+    B: Alloc,
+{
+    fn partial_cmp(&self, other: &String<B>) -> Option<cmp::Ordering> {
+        <str as PartialOrd>::partial_cmp(self, other)
+    }
+}
+
+/// Creates a `String<A>` using interpolation of runtime expressions
+///
+/// The first argument is the allocator the string will be allocated on; the rest of the
+/// arguments are the same as the ones taken by `core::format_args!`.
+///
+/// ``` ignore
+/// let s = format_in!(A, "x = {}", 42);
+/// ```
+#[macro_export]
+macro_rules! format_in {
+    ($A:ty, $($arg:tt)*) => {{
+        let mut s = $crate::string::String::<$A>::new();
+        ::core::fmt::Write::write_fmt(&mut s, format_args!($($arg)*))
+            .expect("a formatting trait implementation returned an error");
+        s
+    }};
+}
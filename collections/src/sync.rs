@@ -0,0 +1,541 @@
+//! Thread-safe reference-counting pointers.
+
+use core::{
+    alloc::Layout,
+    borrow::Borrow,
+    cmp, fmt,
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+    mem, ops,
+    ptr::{self, NonNull},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use alloc_many::Alloc;
+
+use crate::TryReserveError;
+
+struct ArcInner<T>
+where
+    T: ?Sized,
+{
+    strong: AtomicUsize,
+    // NOTE all the strong pointers collectively hold one weak reference
+    weak: AtomicUsize,
+    value: T,
+}
+
+// NOTE we use an `Acquire` load instead of an `Acquire` fence because TSAN doesn't understand
+// fences
+macro_rules! acquire {
+    ($x:expr) => {
+        $x.load(Ordering::Acquire)
+    };
+}
+
+/// A thread-safe reference-counting pointer. 'Arc' stands for 'Atomically Reference Counted'.
+pub struct Arc<A, T>
+where
+    A: Alloc,
+    T: ?Sized,
+{
+    _allocator: PhantomData<A>,
+    ptr: NonNull<ArcInner<T>>,
+    _marker: PhantomData<ArcInner<T>>,
+}
+
+unsafe impl<A, T> Send for Arc<A, T>
+where
+    A: Alloc,
+    T: ?Sized + Send + Sync,
+{
+}
+
+unsafe impl<A, T> Sync for Arc<A, T>
+where
+    A: Alloc,
+    T: ?Sized + Send + Sync,
+{
+}
+
+/// `Weak` is a version of `Arc` that holds a non-owning reference to the managed value.
+pub struct Weak<A, T>
+where
+    A: Alloc,
+    T: ?Sized,
+{
+    _allocator: PhantomData<A>,
+    ptr: NonNull<ArcInner<T>>,
+}
+
+unsafe impl<A, T> Send for Weak<A, T>
+where
+    A: Alloc,
+    T: ?Sized + Send + Sync,
+{
+}
+
+unsafe impl<A, T> Sync for Weak<A, T>
+where
+    A: Alloc,
+    T: ?Sized + Send + Sync,
+{
+}
+
+impl<A, T> Arc<A, T>
+where
+    A: Alloc,
+{
+    /// Constructs a new `Arc<T>` on the allocator `A`.
+    pub fn new(value: T) -> Self {
+        Self::try_new(value).unwrap_or_else(|e| crate::handle_reserve_error::<A>(e))
+    }
+
+    /// Constructs a new `Arc<T>` on the allocator `A`, returning an error if the allocation fails
+    pub fn try_new(value: T) -> Result<Self, TryReserveError> {
+        unsafe {
+            let ptr = Self::allocate()?;
+            ptr.as_ptr().write(ArcInner {
+                strong: AtomicUsize::new(1),
+                weak: AtomicUsize::new(1),
+                value,
+            });
+
+            Ok(Self::from_inner(ptr))
+        }
+    }
+
+    /// Returns the contained value, if the `Arc` has exactly one strong reference.
+    ///
+    /// Otherwise, an `Err` is returned with the same `Arc` that was passed in.
+    pub fn try_unwrap(this: Self) -> Result<T, Self> {
+        if this
+            .inner()
+            .strong
+            .compare_exchange(1, 0, Ordering::Relaxed, Ordering::Relaxed)
+            .is_err()
+        {
+            return Err(this);
+        }
+
+        acquire!(this.inner().strong);
+
+        unsafe {
+            let value = ptr::read(&this.inner().value);
+
+            // release the implicit weak reference held by the strong pointers
+            drop(Weak::<A, T> {
+                _allocator: PhantomData,
+                ptr: this.ptr,
+            });
+            mem::forget(this);
+
+            Ok(value)
+        }
+    }
+
+    /// Makes a mutable reference into the given `Arc`.
+    ///
+    /// If there are other `Arc` pointers to the same allocation, then `make_mut` will clone the
+    /// inner value into a new allocation to ensure unique ownership. If there are no other `Arc`
+    /// pointers but there are `Weak` pointers then the value is moved into a new allocation and
+    /// the `Weak` pointers are disassociated from it.
+    pub fn make_mut(this: &mut Self) -> &mut T
+    where
+        T: Clone,
+    {
+        if this.is_unique() {
+            return unsafe { &mut this.ptr.as_mut().value };
+        }
+
+        unsafe {
+            // NOTE allocate before touching the counts: the OOM handler may unwind and `this` must
+            // still be a valid strong pointer when it's dropped
+            let fresh = Self::allocate().unwrap_or_else(|e| crate::handle_reserve_error::<A>(e));
+
+            // `Acquire` synchronizes with the `Release` decrement of other strong pointers
+            if this
+                .inner()
+                .strong
+                .compare_exchange(1, 0, Ordering::Acquire, Ordering::Relaxed)
+                .is_err()
+            {
+                A::dealloc(fresh.as_ptr() as *mut u8, Layout::new::<ArcInner<T>>());
+                *this = Arc::new((**this).clone());
+            } else {
+                // we were the only strong pointer but `Weak` pointers remain; the strong count is
+                // now zero so they can't be upgraded
+                fresh.as_ptr().write(ArcInner {
+                    strong: AtomicUsize::new(1),
+                    weak: AtomicUsize::new(1),
+                    value: ptr::read(&this.inner().value),
+                });
+
+                // release the implicit weak reference held by the strong pointers
+                drop(Weak::<A, T> {
+                    _allocator: PhantomData,
+                    ptr: this.ptr,
+                });
+
+                ptr::write(this, Self::from_inner(fresh));
+            }
+
+            &mut this.ptr.as_mut().value
+        }
+    }
+
+    unsafe fn allocate() -> Result<NonNull<ArcInner<T>>, TryReserveError> {
+        let layout = Layout::new::<ArcInner<T>>();
+        NonNull::new(A::alloc(layout) as *mut ArcInner<T>)
+            .ok_or(TryReserveError::AllocError { layout })
+    }
+}
+
+impl<A, T> Arc<A, T>
+where
+    A: Alloc,
+    T: ?Sized,
+{
+    fn from_inner(ptr: NonNull<ArcInner<T>>) -> Self {
+        Arc {
+            _allocator: PhantomData,
+            ptr,
+            _marker: PhantomData,
+        }
+    }
+
+    fn inner(&self) -> &ArcInner<T> {
+        unsafe { self.ptr.as_ref() }
+    }
+
+    /// Creates a new `Weak` pointer to this allocation.
+    pub fn downgrade(this: &Self) -> Weak<A, T> {
+        let weak = &this.inner().weak;
+        let mut cur = weak.load(Ordering::Relaxed);
+
+        loop {
+            // the weak count is "locked" (see `is_unique`); spin
+            if cur == usize::max_value() {
+                cur = weak.load(Ordering::Relaxed);
+                continue;
+            }
+
+            if cur > crate::MAX_REFCOUNT {
+                crate::abort()
+            }
+
+            match weak.compare_exchange_weak(cur, cur + 1, Ordering::Acquire, Ordering::Relaxed) {
+                Ok(_) => {
+                    break Weak {
+                        _allocator: PhantomData,
+                        ptr: this.ptr,
+                    }
+                }
+                Err(old) => cur = old,
+            }
+        }
+    }
+
+    /// Gets the number of `Weak` pointers to this allocation.
+    pub fn weak_count(this: &Self) -> usize {
+        let cnt = this.inner().weak.load(Ordering::SeqCst);
+        if cnt == usize::max_value() {
+            0
+        } else {
+            cnt - 1
+        }
+    }
+
+    /// Gets the number of strong (`Arc`) pointers to this allocation.
+    pub fn strong_count(this: &Self) -> usize {
+        this.inner().strong.load(Ordering::SeqCst)
+    }
+
+    /// Returns a mutable reference into the given `Arc`, if there are no other `Arc` or `Weak`
+    /// pointers to the same allocation.
+    pub fn get_mut(this: &mut Self) -> Option<&mut T> {
+        if this.is_unique() {
+            unsafe { Some(&mut this.ptr.as_mut().value) }
+        } else {
+            None
+        }
+    }
+
+    fn is_unique(&mut self) -> bool {
+        // "lock" the weak count so that no new `Weak` pointer can be created while we inspect the
+        // strong count
+        if self
+            .inner()
+            .weak
+            .compare_exchange(1, usize::max_value(), Ordering::Acquire, Ordering::Relaxed)
+            .is_ok()
+        {
+            let unique = self.inner().strong.load(Ordering::Acquire) == 1;
+            self.inner().weak.store(1, Ordering::Release);
+            unique
+        } else {
+            false
+        }
+    }
+
+    /// Returns `true` if the two `Arc`s point to the same allocation.
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        this.ptr.as_ptr() as *const u8 == other.ptr.as_ptr() as *const u8
+    }
+}
+
+impl<A, T> Clone for Arc<A, T>
+where
+    A: Alloc,
+    T: ?Sized,
+{
+    fn clone(&self) -> Self {
+        // NOTE other threads may increment the count concurrently so we check after the fact and
+        // abort well before it can wrap around
+        if self.inner().strong.fetch_add(1, Ordering::Relaxed) > crate::MAX_REFCOUNT {
+            crate::abort()
+        }
+
+        Self::from_inner(self.ptr)
+    }
+}
+
+impl<A, T> ops::Deref for Arc<A, T>
+where
+    A: Alloc,
+    T: ?Sized,
+{
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.inner().value
+    }
+}
+
+impl<A, T> Drop for Arc<A, T>
+where
+    A: Alloc,
+    T: ?Sized,
+{
+    fn drop(&mut self) {
+        if self.inner().strong.fetch_sub(1, Ordering::Release) != 1 {
+            return;
+        }
+
+        acquire!(self.inner().strong);
+
+        unsafe {
+            ptr::drop_in_place(&mut self.ptr.as_mut().value);
+
+            // release the implicit weak reference held by the strong pointers
+            drop(Weak::<A, T> {
+                _allocator: PhantomData,
+                ptr: self.ptr,
+            });
+        }
+    }
+}
+
+impl<A, T> Weak<A, T>
+where
+    A: Alloc,
+    T: ?Sized,
+{
+    fn inner(&self) -> &ArcInner<T> {
+        unsafe { self.ptr.as_ref() }
+    }
+
+    /// Attempts to upgrade the `Weak` pointer to an `Arc`.
+    ///
+    /// Returns `None` if the inner value has since been dropped.
+    pub fn upgrade(&self) -> Option<Arc<A, T>> {
+        let strong = &self.inner().strong;
+        let mut cur = strong.load(Ordering::Relaxed);
+
+        loop {
+            if cur == 0 {
+                return None;
+            }
+
+            if cur > crate::MAX_REFCOUNT {
+                crate::abort()
+            }
+
+            match strong.compare_exchange_weak(cur, cur + 1, Ordering::Acquire, Ordering::Relaxed) {
+                Ok(_) => return Some(Arc::from_inner(self.ptr)),
+                Err(old) => cur = old,
+            }
+        }
+    }
+
+    /// Gets the number of strong (`Arc`) pointers pointing to this allocation.
+    pub fn strong_count(&self) -> usize {
+        self.inner().strong.load(Ordering::SeqCst)
+    }
+
+    /// Gets an approximation of the number of `Weak` pointers pointing to this allocation.
+    ///
+    /// If no strong pointers remain, this will return zero.
+    pub fn weak_count(&self) -> usize {
+        let weak = self.inner().weak.load(Ordering::SeqCst);
+        let strong = self.inner().strong.load(Ordering::SeqCst);
+        if strong == 0 {
+            0
+        } else {
+            weak - 1
+        }
+    }
+
+    /// Returns `true` if the two `Weak`s point to the same allocation.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        self.ptr.as_ptr() as *const u8 == other.ptr.as_ptr() as *const u8
+    }
+}
+
+impl<A, T> Clone for Weak<A, T>
+where
+    A: Alloc,
+    T: ?Sized,
+{
+    fn clone(&self) -> Self {
+        if self.inner().weak.fetch_add(1, Ordering::Relaxed) > crate::MAX_REFCOUNT {
+            crate::abort()
+        }
+
+        Weak {
+            _allocator: PhantomData,
+            ptr: self.ptr,
+        }
+    }
+}
+
+impl<A, T> Drop for Weak<A, T>
+where
+    A: Alloc,
+    T: ?Sized,
+{
+    fn drop(&mut self) {
+        if self.inner().weak.fetch_sub(1, Ordering::Release) != 1 {
+            return;
+        }
+
+        acquire!(self.inner().weak);
+
+        unsafe {
+            let layout = Layout::for_value(self.ptr.as_ref());
+            A::dealloc(self.ptr.as_ptr() as *mut u8, layout)
+        }
+    }
+}
+
+impl<A, T> fmt::Debug for Weak<A, T>
+where
+    A: Alloc,
+    T: ?Sized,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("(Weak)")
+    }
+}
+
+impl<A, T> fmt::Debug for Arc<A, T>
+where
+    A: Alloc,
+    T: ?Sized + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        <T as fmt::Debug>::fmt(self, f)
+    }
+}
+
+impl<A, T> fmt::Display for Arc<A, T>
+where
+    A: Alloc,
+    T: ?Sized + fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        <T as fmt::Display>::fmt(self, f)
+    }
+}
+
+impl<A, T> Default for Arc<A, T>
+where
+    A: Alloc,
+    T: Default,
+{
+    fn default() -> Self {
+        Arc::new(T::default())
+    }
+}
+
+impl<A, T> Hash for Arc<A, T>
+where
+    A: Alloc,
+    T: ?Sized + Hash,
+{
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: Hasher,
+    {
+        <T as Hash>::hash(self, state)
+    }
+}
+
+impl<A, T> AsRef<T> for Arc<A, T>
+where
+    A: Alloc,
+    T: ?Sized,
+{
+    fn as_ref(&self) -> &T {
+        self
+    }
+}
+
+impl<A, T> Borrow<T> for Arc<A, T>
+where
+    A: Alloc,
+    T: ?Sized,
+{
+    fn borrow(&self) -> &T {
+        self
+    }
+}
+
+impl<A, T> Eq for Arc<A, T>
+where
+    A: Alloc,
+    T: ?Sized + Eq,
+{
+}
+
+impl<A, B, T> PartialEq<Arc<B, T>> for Arc<A, T>
+where
+    A: Alloc,
+    B: Alloc,
+    T: ?Sized + PartialEq,
+{
+    fn eq(&self, other: &Arc<B, T>) -> bool {
+        <T as PartialEq>::eq(self, other)
+    }
+}
+
+impl<A, T> Ord for Arc<A, T>
+where
+    A: Alloc,
+    T: ?Sized + Ord,
+{
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        <T as Ord>::cmp(self, other)
+    }
+}
+
+impl<A, B, T> PartialOrd<Arc<B, T>> for Arc<A, T>
+where
+    A: Alloc,
+    B: Alloc,
+    T: ?Sized + PartialOrd,
+{
+    fn partial_cmp(&self, other: &Arc<B, T>) -> Option<cmp::Ordering> {
+        <T as PartialOrd>::partial_cmp(self, other)
+    }
+}
@@ -0,0 +1,238 @@
+use core::{
+    alloc::{GlobalAlloc, Layout},
+    ptr,
+};
+use std::panic::{self, UnwindSafe};
+
+use alloc_many::{allocator, combinators::Fallback, Alloc, AllocInfo};
+use alloc_many_bump::{consts, BumpAlloc};
+
+use super::Counting;
+use crate::{
+    binary_heap::BinaryHeap, boxed::Box, btree_map::BTreeMap, hash_map::HashMap,
+    linked_list::LinkedList, rc::Rc, string::String, sync::Arc, vec::Vec, vec_deque::VecDeque,
+};
+
+// an allocator that is always out of memory
+struct Empty;
+
+unsafe impl GlobalAlloc for Empty {
+    unsafe fn alloc(&self, _: Layout) -> *mut u8 {
+        ptr::null_mut()
+    }
+
+    unsafe fn dealloc(&self, _: *mut u8, _: Layout) {}
+}
+
+// asserts that `f` calls the `full` OOM handler
+fn assert_full<F>(f: F)
+where
+    F: FnOnce() + UnwindSafe,
+{
+    let payload = panic::catch_unwind(f).unwrap_err();
+    assert_eq!(payload.downcast_ref::<&str>(), Some(&"allocator is full"));
+}
+
+fn full(_: Layout) -> ! {
+    panic!("allocator is full")
+}
+
+#[test]
+#[should_panic(expected = "allocator is full")]
+fn oom() {
+    #[allocator(oom = full)]
+    static A: BumpAlloc<consts::U16> = BumpAlloc::new();
+
+    let _x: Box<A, [u8; 32]> = Box::new([0; 32]);
+}
+
+#[test]
+#[should_panic(expected = r#"full: AllocInfo { name: "A", capacity: Some(16), used: Some(0) }"#)]
+fn oom_info() {
+    fn full(_: Layout, info: &AllocInfo) -> ! {
+        panic!("full: {:?}", info)
+    }
+
+    #[allocator(oom = full)]
+    static A: BumpAlloc<consts::U16> = BumpAlloc::new();
+
+    let _x: Box<A, [u8; 32]> = Box::new([0; 32]);
+}
+
+#[test]
+#[should_panic(expected = r#"AllocInfo { name: "A", capacity: Some(16), used: Some(0) }"#)]
+fn default_oom() {
+    // NOTE calls the `#[oom]` handler defined in `tests/mod.rs`
+    #[allocator]
+    static A: BumpAlloc<consts::U16> = BumpAlloc::new();
+
+    let _x: Box<A, [u8; 32]> = Box::new([0; 32]);
+}
+
+#[test]
+fn stats() {
+    #[allocator(stats)]
+    static A: Counting = Counting::new();
+
+    let v: Vec<A, u8> = Vec::with_capacity(8);
+    assert_eq!(A.snapshot().live, 8);
+    assert_eq!(A.inner().live(), 1);
+
+    drop(v);
+    assert_eq!(A.snapshot().live, 0);
+    assert_eq!(A.snapshot().peak, 8);
+}
+
+#[test]
+fn link_section() {
+    #[allocator(link_section = ".data.alloc_many")]
+    static A: BumpAlloc<consts::U16> = BumpAlloc::new();
+
+    let x: Box<A, u32> = Box::new(42);
+    assert_eq!(*x, 42);
+}
+
+#[test]
+#[should_panic(expected = "allocator is full")]
+fn all() {
+    #[allocator(stats, oom = full, link_section = ".data.alloc_many")]
+    static A: BumpAlloc<consts::U16> = BumpAlloc::new();
+
+    let mut v: Vec<A, u8> = Vec::with_capacity(16);
+    v.extend_from_slice(&[0; 16]);
+    assert_eq!(A.snapshot().live, 16);
+
+    v.push(0);
+}
+
+#[test]
+fn manual_oom() {
+    // `Alloc` implemented without `#[allocator]`
+    struct A;
+
+    unsafe impl Alloc for A {
+        unsafe fn alloc(_: Layout) -> *mut u8 {
+            ptr::null_mut()
+        }
+
+        unsafe fn dealloc(_: *mut u8, _: Layout) {}
+
+        unsafe fn alloc_zeroed(_: Layout) -> *mut u8 {
+            ptr::null_mut()
+        }
+
+        unsafe fn realloc(_: *mut u8, _: Layout, _: usize) -> *mut u8 {
+            ptr::null_mut()
+        }
+
+        fn oom(layout: Layout) -> ! {
+            full(layout)
+        }
+    }
+
+    assert_full(|| drop(Box::<A, _>::new(0)));
+}
+
+#[test]
+fn collections_oom() {
+    #[allocator(oom = full)]
+    static A: Empty = Empty;
+
+    // every infallible API calls the handler of the allocator that failed
+    assert_full(|| drop(Box::<A, _>::new(0)));
+    assert_full(|| drop(Box::<A, [u8]>::from_slice(&[0])));
+    assert_full(|| drop(Vec::<A, u8>::with_capacity(1)));
+    assert_full(|| Vec::<A, u8>::new().push(0));
+    assert_full(|| String::<A>::new().push_str("a"));
+    assert_full(|| drop(VecDeque::<A, u8>::with_capacity(1)));
+    assert_full(|| LinkedList::<A, u8>::new().push_back(0));
+    assert_full(|| BinaryHeap::<A, u8>::new().push(0));
+    assert_full(|| {
+        BTreeMap::<A, u8, u8>::new().insert(0, 0);
+    });
+    assert_full(|| {
+        HashMap::<A, u8, u8>::new().insert(0, 0);
+    });
+    assert_full(|| drop(Rc::<A, _>::new(0)));
+    assert_full(|| drop(Arc::<A, _>::new(0)));
+
+    // fallible APIs don't call the handler
+    assert!(Box::<A, _>::try_new(0).is_err());
+    assert!(Vec::<A, u8>::new().try_reserve(1).is_err());
+}
+
+#[test]
+fn move_oom() {
+    #[allocator]
+    static A: Counting = Counting::new();
+
+    #[allocator(oom = full)]
+    static B: Empty = Empty;
+
+    // moving or cloning into another allocator calls the handler of the target allocator
+    let x: Box<A, u8> = Box::new(0);
+    assert_full(|| drop(x.clone_in::<B>()));
+    assert_full(|| drop(Box::<A, u8>::new(0).move_to::<B>()));
+
+    let v: Vec<A, u8> = (0..1).collect();
+    assert_full(|| drop(v.clone_in::<B>()));
+    assert_full(|| drop(v.into_alloc::<B>()));
+
+    let mut s: String<A> = String::new();
+    s.push_str("a");
+    assert_full(|| drop(s.into_alloc::<B>()));
+}
+
+#[test]
+#[should_panic(expected = "capacity overflow")]
+fn capacity_overflow() {
+    // capacity overflows are not OOM errors
+    #[allocator(oom = full)]
+    static A: Empty = Empty;
+
+    drop(Vec::<A, u32>::with_capacity(usize::max_value()));
+}
+
+#[test]
+fn info() {
+    #[allocator]
+    static A: BumpAlloc<consts::U16> = BumpAlloc::new();
+
+    #[allocator(stats)]
+    static B: BumpAlloc<consts::U16> = BumpAlloc::new();
+
+    #[allocator]
+    static C: Counting = Counting::new();
+
+    #[allocator]
+    static D: Fallback<BumpAlloc<consts::U16>, BumpAlloc<consts::U32>> =
+        Fallback::new(BumpAlloc::new(), BumpAlloc::new());
+
+    let _a: Box<A, u32> = Box::new(0);
+    assert_eq!(
+        A::info(),
+        AllocInfo {
+            name: "A",
+            capacity: Some(16),
+            used: Some(4),
+        }
+    );
+
+    let _b: Box<B, u64> = Box::new(0);
+    assert_eq!(B::info().capacity, Some(16));
+    assert_eq!(B::info().used, Some(8));
+
+    // `Counting` doesn't implement `Usage`
+    assert_eq!(
+        C::info(),
+        AllocInfo {
+            name: "C",
+            capacity: None,
+            used: None,
+        }
+    );
+
+    let _d: Box<D, [u8; 24]> = Box::new([0; 24]);
+    assert_eq!(D::info().capacity, Some(48));
+    assert_eq!(D::info().used, Some(24));
+}
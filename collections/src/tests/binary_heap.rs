@@ -0,0 +1,190 @@
+use core::{alloc::Layout, cell::Cell};
+use std::format;
+
+use alloc_many::{allocator, Alloc};
+use alloc_many_bump::{consts, BumpAlloc};
+
+use crate::{
+    binary_heap::{BinaryHeap, PeekMut},
+    vec::Vec,
+    TryReserveError,
+};
+
+alloc_tests!(push_pop, peek_mut, heapify, drain_retain, append, traits);
+
+/// Increments a counter when dropped
+#[derive(Eq, Ord, PartialEq, PartialOrd)]
+struct D<'a>(i32, &'a Cell<usize>);
+
+impl Drop for D<'_> {
+    fn drop(&mut self) {
+        self.1.set(self.1.get() + 1);
+    }
+}
+
+// NOTE a permutation of `0..n`
+fn shuffled(n: i32) -> impl Iterator<Item = i32> {
+    (0..n).map(move |i| (i * 37) % n)
+}
+
+fn push_pop<A>()
+where
+    A: Alloc,
+{
+    let mut h: BinaryHeap<A, i32> = BinaryHeap::new();
+    assert!(h.is_empty());
+    assert_eq!(h.peek(), None);
+    assert_eq!(h.pop(), None);
+
+    for i in shuffled(50) {
+        h.push(i);
+        assert!(*h.peek().unwrap() >= i);
+    }
+    assert_eq!(h.len(), 50);
+    assert_eq!(h.peek(), Some(&49));
+
+    for i in (0..50).rev() {
+        assert_eq!(h.pop(), Some(i));
+    }
+    assert_eq!(h.pop(), None);
+
+    // duplicates
+    h.extend(&[3, 1, 3, 2, 1]);
+    assert_eq!(*h.into_sorted_vec(), [1, 1, 2, 3, 3]);
+}
+
+fn peek_mut<A>()
+where
+    A: Alloc,
+{
+    let mut h: BinaryHeap<A, i32> = shuffled(10).collect();
+
+    // decreasing the top sifts it down
+    *h.peek_mut().unwrap() = -1;
+    assert_eq!(h.peek(), Some(&8));
+
+    {
+        let top = h.peek_mut().unwrap();
+        assert_eq!(*top, 8);
+        assert_eq!(PeekMut::pop(top), 8);
+    }
+    assert_eq!(h.len(), 9);
+
+    // increasing the top keeps it on top
+    *h.peek_mut().unwrap() += 100;
+    assert_eq!(h.pop(), Some(107));
+    assert_eq!(*h.into_sorted_vec(), [-1, 0, 1, 2, 3, 4, 5, 6]);
+
+    let mut e: BinaryHeap<A, i32> = BinaryHeap::new();
+    assert!(e.peek_mut().is_none());
+}
+
+fn heapify<A>()
+where
+    A: Alloc,
+{
+    let v: Vec<A, i32> = shuffled(40).collect();
+    let h = BinaryHeap::from(v);
+    assert_eq!(h.len(), 40);
+    assert_eq!(h.peek(), Some(&39));
+
+    let sorted = h.into_sorted_vec();
+    assert_eq!(*sorted, *(0..40).collect::<Vec<A, _>>());
+
+    let h: BinaryHeap<A, i32> = BinaryHeap::from(Vec::new());
+    assert!(h.is_empty());
+    let v: Vec<A, i32> = BinaryHeap::<A, i32>::from(sorted).into();
+    assert_eq!(v.len(), 40);
+}
+
+fn drain_retain<A>()
+where
+    A: Alloc,
+{
+    let mut h: BinaryHeap<A, i32> = shuffled(30).collect();
+    h.retain(|x| x % 3 == 0);
+    assert_eq!(h.len(), 10);
+    assert_eq!(h.peek(), Some(&27));
+    for i in (0..30).rev().filter(|x| x % 3 == 0) {
+        assert_eq!(h.pop(), Some(i));
+    }
+
+    let mut h: BinaryHeap<A, i32> = shuffled(10).collect();
+    let cap = h.capacity();
+    assert_eq!(h.drain().sum::<i32>(), 45);
+    assert!(h.is_empty());
+    assert_eq!(h.capacity(), cap);
+}
+
+fn append<A>()
+where
+    A: Alloc,
+{
+    let mut a: BinaryHeap<A, i32> = (0..5).collect();
+    let mut b: BinaryHeap<A, i32> = (5..20).collect();
+    a.append(&mut b);
+    assert!(b.is_empty());
+    assert_eq!(a.len(), 20);
+    assert_eq!(*a.into_sorted_vec(), *(0..20).collect::<Vec<A, _>>());
+}
+
+fn traits<A>()
+where
+    A: Alloc,
+{
+    let h: BinaryHeap<A, i32> = [1].iter().cloned().collect();
+    let g = h.clone();
+    assert_eq!(format!("{:?}", g), "[1]");
+    assert_eq!(h.iter().count(), 1);
+    assert_eq!(h.into_iter().next(), Some(1));
+    assert!(BinaryHeap::<A, i32>::default().is_empty());
+
+    // values are dropped exactly once
+    let dropped = Cell::new(0);
+    let mut h: BinaryHeap<A, D<'_>> = BinaryHeap::new();
+    for i in shuffled(10) {
+        h.push(D(i, &dropped));
+    }
+    drop(h.pop());
+    assert_eq!(dropped.get(), 1);
+    drop(h);
+    assert_eq!(dropped.get(), 10);
+}
+
+#[test]
+fn oom() {
+    #[allocator]
+    static A: BumpAlloc<consts::U48> = BumpAlloc::new();
+
+    let mut h: BinaryHeap<A, u32> = BinaryHeap::with_capacity(8);
+    for i in 0..8 {
+        h.try_push(i).unwrap();
+    }
+
+    // no space left to grow the heap
+    assert_eq!(
+        h.try_push(8),
+        Err(TryReserveError::AllocError {
+            layout: Layout::from_size_align(64, 4).unwrap()
+        })
+    );
+    // the heap is left untouched
+    assert_eq!(h.len(), 8);
+    assert_eq!(h.peek(), Some(&7));
+    assert_eq!(
+        h.try_reserve(usize::max_value()),
+        Err(TryReserveError::CapacityOverflow)
+    );
+}
+
+#[test]
+#[should_panic]
+fn oom_push() {
+    #[allocator]
+    static A: BumpAlloc<consts::U64> = BumpAlloc::new();
+
+    let mut h: BinaryHeap<A, u32> = BinaryHeap::new();
+    for i in 0..32 {
+        h.push(i);
+    }
+}
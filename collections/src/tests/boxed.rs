@@ -0,0 +1,463 @@
+use core::{
+    alloc::Layout,
+    borrow::Borrow,
+    cell::Cell,
+    fmt::Debug,
+    future::Future,
+    hash::{Hash, Hasher},
+    mem::MaybeUninit,
+    pin::Pin,
+    ptr,
+    task::{Context, Poll, RawWaker, RawWakerVTable, Waker},
+};
+use std::format;
+
+use alloc_many::{allocator, Alloc};
+use alloc_many_bump::{consts, BumpAlloc};
+
+use crate::{
+    boxed::Box, hash_map::DefaultHasher, string::String, tests::Counting, unsize_box, vec::Vec,
+    TryReserveError,
+};
+
+alloc_tests!(
+    slice,
+    uninit_slice,
+    str_,
+    into_iter,
+    zero_sized,
+    unsize,
+    raw,
+    uninit,
+    pin_future,
+    traits,
+    iterator
+);
+
+/// Increments a counter when dropped
+#[derive(Debug)]
+struct D<'a>(&'a Cell<usize>);
+
+impl Drop for D<'_> {
+    fn drop(&mut self) {
+        self.0.set(self.0.get() + 1);
+    }
+}
+
+fn slice<A>()
+where
+    A: Alloc,
+{
+    let b: Box<A, [i32]> = Box::from_slice(&[1, 2, 3]);
+    assert_eq!(b.len(), 3);
+    assert_eq!(*b, [1, 2, 3]);
+
+    let mut c = b.clone();
+    c[0] = 0;
+    assert_eq!(*c, [0, 2, 3]);
+    assert_ne!(b, c);
+    assert_eq!(format!("{:?}", b), "[1, 2, 3]");
+
+    // excess capacity is dropped
+    let mut v: Vec<A, i32> = Vec::with_capacity(10);
+    v.extend_from_slice(&[4, 5]);
+    let d = v.into_boxed_slice();
+    assert_eq!(*d, [4, 5]);
+
+    let v = d.into_vec();
+    assert_eq!(v.capacity(), 2);
+    let d: Box<A, [i32]> = v.into();
+    assert_eq!(*d, [4, 5]);
+
+    let e: Box<A, [i32]> = Vec::new().into_boxed_slice();
+    assert!(e.is_empty());
+    let f: Box<A, [i32]> = Vec::with_capacity(4).into_boxed_slice();
+    assert!(f.is_empty());
+}
+
+fn uninit_slice<A>()
+where
+    A: Alloc,
+{
+    let mut b = Box::<A, [u32]>::new_uninit_slice(4);
+    assert_eq!(b.len(), 4);
+    for (i, x) in b.iter_mut().enumerate() {
+        *x = MaybeUninit::new(i as u32 * 2);
+    }
+    let b = unsafe { b.assume_init() };
+    assert_eq!(*b, [0, 2, 4, 6]);
+
+    let e = Box::<A, [u32]>::new_uninit_slice(0);
+    assert!(unsafe { e.assume_init() }.is_empty());
+}
+
+fn str_<A>()
+where
+    A: Alloc,
+{
+    let s: Box<A, str> = Box::from_str("hello");
+    assert_eq!(&*s, "hello");
+    assert_eq!(format!("{}", s), "hello");
+    assert_eq!(s.clone(), s);
+
+    let mut t: String<A> = String::with_capacity(16);
+    t.push_str("héllo");
+    let t = t.into_boxed_str();
+    assert_eq!(&*t, "héllo");
+    assert_eq!(t.len(), 6);
+
+    let e: Box<A, str> = Box::from_str("");
+    assert!(e.is_empty());
+}
+
+fn into_iter<A>()
+where
+    A: Alloc,
+{
+    let mut b: Box<A, [i32]> = Box::from_slice(&[1, 2, 3]);
+    for x in &mut b {
+        *x *= 10;
+    }
+    assert_eq!((&b).into_iter().sum::<i32>(), 60);
+
+    let mut it = b.into_iter();
+    assert_eq!(it.next_back(), Some(30));
+    assert_eq!(it.collect::<Vec<A, _>>()[..], [10, 20]);
+
+    // values are dropped exactly once
+    let dropped = Cell::new(0);
+    let mut v: Vec<A, D<'_>> = Vec::new();
+    for _ in 0..4 {
+        v.push(D(&dropped));
+    }
+    let b = v.into_boxed_slice();
+    let mut it = b.into_iter();
+    drop(it.next());
+    assert_eq!(dropped.get(), 1);
+    drop(it);
+    assert_eq!(dropped.get(), 4);
+
+    let mut v: Vec<A, D<'_>> = Vec::new();
+    v.push(D(&dropped));
+    drop(v.into_boxed_slice());
+    assert_eq!(dropped.get(), 5);
+}
+
+fn zero_sized<A>()
+where
+    A: Alloc,
+{
+    let a: Box<A, ()> = Box::new(());
+    assert_eq!(*a, ());
+
+    let b: Box<A, [()]> = Box::from_slice(&[(), ()]);
+    assert_eq!(b.len(), 2);
+    assert_eq!(b.into_iter().count(), 2);
+
+    let mut v: Vec<A, [u8; 0]> = Vec::new();
+    v.push([]);
+    v.push([]);
+    assert_eq!(v.into_boxed_slice().len(), 2);
+}
+
+fn unsize<A>()
+where
+    A: Alloc,
+{
+    let counter = Cell::new(0);
+    let f: Box<A, _> = Box::new(|| counter.set(counter.get() + 1));
+    let f: Box<A, dyn Fn() + '_> = unsize_box!(f as dyn Fn() + '_);
+    (*f)();
+    (*f)();
+    assert_eq!(counter.get(), 2);
+
+    let mut g: Box<A, dyn FnMut() -> u32> = {
+        let mut x = 0u32;
+        let g: Box<A, _> = Box::new(move || {
+            x += 1;
+            x
+        });
+        unsize_box!(g as dyn FnMut() -> u32)
+    };
+    assert_eq!((*g)(), 1);
+    assert_eq!((*g)(), 2);
+
+    let d: Box<A, _> = Box::new([1, 2]);
+    let d: Box<A, dyn Debug> = unsize_box!(d as dyn Debug);
+    assert_eq!(format!("{:?}", d), "[1, 2]");
+
+    // the values behind the trait objects are dropped and freed
+    let dropped = Cell::new(0);
+    let x: Box<A, _> = Box::new(D(&dropped));
+    let y: Box<A, _> = Box::new((D(&dropped), 0u64));
+    let xs: [Box<A, dyn Debug + '_>; 2] = [
+        unsize_box!(x as dyn Debug + '_),
+        unsize_box!(y as dyn Debug + '_),
+    ];
+    drop(xs);
+    assert_eq!(dropped.get(), 2);
+
+    let z: Box<A, ()> = Box::new(());
+    let z: Box<A, dyn Debug> = unsize_box!(z as dyn Debug);
+    assert_eq!(format!("{:?}", z), "()");
+}
+
+fn raw<A>()
+where
+    A: Alloc,
+{
+    let dropped = Cell::new(0);
+    let b: Box<A, _> = Box::new(D(&dropped));
+    let p = Box::into_raw(b);
+    assert_eq!(dropped.get(), 0);
+    drop(unsafe { Box::<A, _>::from_raw(p) });
+    assert_eq!(dropped.get(), 1);
+
+    let s: Box<A, [u8]> = Box::from_slice(&[1, 2]);
+    let p = Box::into_raw(s);
+    let s = unsafe { Box::<A, [u8]>::from_raw(p) };
+    assert_eq!(*s, [1, 2]);
+
+    let x: &'static mut u32 = Box::leak(Box::<A, _>::new(41));
+    *x += 1;
+    assert_eq!(*x, 42);
+    // NOTE free the leaked value so the counting allocator doesn't report it
+    drop(unsafe { Box::<A, u32>::from_raw(x) });
+}
+
+fn uninit<A>()
+where
+    A: Alloc,
+{
+    let mut b = Box::<A, [u64; 16]>::new_uninit();
+    unsafe {
+        b.as_mut_ptr().write([7; 16]);
+        assert_eq!(*b.assume_init(), [7; 16]);
+    }
+
+    let z = Box::<A, [u64; 16]>::new_zeroed();
+    assert_eq!(unsafe { *z.assume_init() }, [0; 16]);
+
+    let u = Box::<A, ()>::new_zeroed();
+    assert_eq!(*unsafe { u.assume_init() }, ());
+}
+
+fn pin_future<A>()
+where
+    A: Alloc,
+{
+    struct Countdown(u8);
+
+    impl Future for Countdown {
+        type Output = &'static str;
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<&'static str> {
+            if self.0 == 0 {
+                Poll::Ready("done")
+            } else {
+                self.0 -= 1;
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+        }
+    }
+
+    fn noop(_: *const ()) {}
+    fn clone(_: *const ()) -> RawWaker {
+        RawWaker::new(ptr::null(), &VTABLE)
+    }
+    static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, noop, noop, noop);
+
+    let waker = unsafe { Waker::from_raw(clone(ptr::null())) };
+    let mut cx = Context::from_waker(&waker);
+
+    let mut f: Box<A, _> = Box::new(Countdown(1));
+    assert_eq!(Pin::new(&mut f).poll(&mut cx), Poll::Pending);
+    assert_eq!(Pin::new(&mut f).poll(&mut cx), Poll::Ready("done"));
+
+    let mut p = Box::<A, _>::pin(Countdown(0));
+    assert_eq!(p.as_mut().poll(&mut cx), Poll::Ready("done"));
+
+    let b: Box<A, _> = Box::new(Countdown(0));
+    let mut d: Box<A, dyn Future<Output = &'static str> + Unpin> =
+        unsize_box!(b as dyn Future<Output = &'static str> + Unpin);
+    assert_eq!(Pin::new(&mut d).poll(&mut cx), Poll::Ready("done"));
+}
+
+fn traits<A>()
+where
+    A: Alloc,
+{
+    let a: Box<A, i32> = Box::new(1);
+    let b = a.clone();
+    assert_eq!(a, b);
+    assert!(a < Box::<A, _>::new(2));
+    assert_eq!(a.cmp(&b), core::cmp::Ordering::Equal);
+
+    let hash = |x: &dyn Fn(&mut DefaultHasher)| {
+        let mut h = DefaultHasher::default();
+        x(&mut h);
+        h.finish()
+    };
+    assert_eq!(hash(&|h| a.hash(h)), hash(&|h| 1i32.hash(h)));
+
+    assert_eq!(*Box::<A, u8>::default(), 0);
+    assert!(Box::<A, [u8]>::default().is_empty());
+    assert_eq!(&*Box::<A, str>::default(), "");
+
+    let mut c: Box<A, i32> = 3.into();
+    *c.as_mut() += 1;
+    assert_eq!(c.as_ref(), &4);
+    let r: &i32 = c.borrow();
+    assert_eq!(*r, 4);
+    assert_eq!(format!("{:p}", c), format!("{:p}", r));
+}
+
+fn iterator<A>()
+where
+    A: Alloc,
+{
+    let mut it: Box<A, _> = Box::new(0..5);
+    assert_eq!(it.next(), Some(0));
+    assert_eq!(it.next_back(), Some(4));
+    assert_eq!(it.len(), 3);
+    assert_eq!(it.sum::<i32>(), 6);
+
+    let it: Box<A, _> = Box::new((0..4).map(|x| x * 2));
+    let mut it: Box<A, dyn Iterator<Item = i32>> = unsize_box!(it as dyn Iterator<Item = i32>);
+    assert_eq!(it.nth(1), Some(2));
+    assert_eq!(it.collect::<Vec<A, _>>()[..], [4, 6]);
+}
+
+#[cfg(feature = "nightly")]
+#[test]
+fn coerce_unsized() {
+    #[allocator]
+    static A: Counting = Counting::new();
+
+    let f: Box<A, dyn Fn() -> i32> = Box::new(|| 1);
+    assert_eq!(f(), 1);
+
+    let mut x = 0;
+    let mut g: Box<A, dyn FnMut(i32) + '_> = Box::new(|y| x += y);
+    g(2);
+    g(3);
+    drop(g);
+    assert_eq!(x, 5);
+
+    let s: Box<A, [u8]> = Box::new([1, 2, 3]);
+    assert_eq!(*s, [1, 2, 3]);
+
+    let d: Box<A, dyn Debug> = Box::new(0u16);
+    assert_eq!(format!("{:?}", d), "0");
+
+    drop((f, s, d));
+    assert_eq!(A.live(), 0);
+}
+
+#[test]
+fn exact_size() {
+    #[allocator]
+    static A: Counting = Counting::new();
+
+    // one allocation per non-empty boxed slice; none for empty or zero-sized ones
+    let a: Box<A, [u64]> = Box::from_slice(&[1, 2, 3]);
+    assert_eq!(A.live(), 1);
+    let b: Box<A, [u64]> = Box::from_slice(&[]);
+    let c: Box<A, [()]> = Box::from_slice(&[(); 8]);
+    let d: Box<A, ()> = Box::new(());
+    assert_eq!(A.live(), 1);
+
+    drop((a, b, c, d));
+    assert_eq!(A.live(), 0);
+}
+
+#[test]
+fn oom() {
+    #[allocator]
+    static A: BumpAlloc<consts::U64> = BumpAlloc::new();
+
+    let a = Box::<A, [u8]>::try_from_slice(&[0; 32]).unwrap();
+    assert_eq!(a.len(), 32);
+    assert_eq!(
+        Box::<A, [u8]>::try_from_slice(&[0; 64]),
+        Err(TryReserveError::AllocError {
+            layout: Layout::new::<[u8; 64]>()
+        })
+    );
+    assert_eq!(
+        Box::<A, str>::try_from_str("too long to fit in the remaining space of this bump"),
+        Err(TryReserveError::AllocError {
+            layout: Layout::from_size_align(51, 1).unwrap()
+        })
+    );
+    match Box::<A, [u32]>::try_new_uninit_slice(usize::max_value()) {
+        Err(TryReserveError::CapacityOverflow) => {}
+        res => panic!("{:?}", res.map(|b| b.len())),
+    }
+}
+
+#[test]
+fn move_between_allocs() {
+    #[allocator]
+    static A: BumpAlloc<consts::U128> = BumpAlloc::new();
+
+    #[allocator]
+    static B: Counting = Counting::new();
+
+    let a: Box<A, [u64; 4]> = Box::new([1, 2, 3, 4]);
+    let b: Box<B, [u64; 4]> = a.clone_in();
+    assert_eq!(a, b);
+    assert_eq!(B.live(), 1);
+
+    let c: Box<B, _> = a.move_to();
+    assert_eq!(*c, [1, 2, 3, 4]);
+    assert_eq!(B.live(), 2);
+
+    // moving into the same allocator reuses the allocation
+    let ptr = &*c as *const [u64; 4];
+    let d: Box<B, _> = c.move_to();
+    assert_eq!(&*d as *const _, ptr);
+    assert_eq!(B.live(), 2);
+
+    // the value is moved, not dropped
+    let dropped = Cell::new(0);
+    let e: Box<B, _> = Box::new(D(&dropped));
+    let f: Box<A, _> = e.try_move_to().unwrap();
+    assert_eq!(dropped.get(), 0);
+    assert_eq!(B.live(), 2);
+    drop(f);
+    assert_eq!(dropped.get(), 1);
+
+    drop((b, d));
+    assert_eq!(B.live(), 0);
+
+    let big: Box<B, [u8; 200]> = Box::new([0; 200]);
+    assert_eq!(
+        big.try_clone_in::<A>(),
+        Err(TryReserveError::AllocError {
+            layout: Layout::new::<[u8; 200]>()
+        })
+    );
+
+    // on error the box is handed back untouched
+    let ptr = &*big as *const [u8; 200];
+    let (big, e) = big.try_move_to::<A>().unwrap_err();
+    assert_eq!(
+        e,
+        TryReserveError::AllocError {
+            layout: Layout::new::<[u8; 200]>()
+        }
+    );
+    assert_eq!(&*big as *const _, ptr);
+    assert_eq!(B.live(), 1);
+    drop(big);
+    assert_eq!(B.live(), 0);
+
+    let dropped = Cell::new(0);
+    let big: Box<B, (D<'_>, [u8; 200])> = Box::new((D(&dropped), [0; 200]));
+    let (big, _) = big.try_move_to::<A>().unwrap_err();
+    assert_eq!(dropped.get(), 0);
+    drop(big);
+    assert_eq!(dropped.get(), 1);
+}
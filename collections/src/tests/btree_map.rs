@@ -0,0 +1,461 @@
+use core::{
+    alloc::{GlobalAlloc, Layout},
+    cell::Cell,
+    cmp,
+    sync::atomic::{AtomicBool, Ordering},
+};
+use std::{
+    alloc::System,
+    format,
+    panic::{self, AssertUnwindSafe},
+};
+
+use alloc_many::{allocator, Alloc};
+
+use crate::{
+    btree_map::{BTreeMap, Entry},
+    btree_set::BTreeSet,
+    tests::Counting,
+    vec::Vec,
+};
+
+alloc_tests!(
+    insert_get,
+    remove,
+    iter,
+    range,
+    entry,
+    split_off_append,
+    into_iter,
+    set,
+    traits
+);
+
+/// Increments a counter when dropped
+struct D<'a>(&'a Cell<usize>);
+
+impl Drop for D<'_> {
+    fn drop(&mut self) {
+        self.0.set(self.0.get() + 1);
+    }
+}
+
+// NOTE spreads the keys over the whole tree instead of only appending to the last leaf
+fn shuffled(n: i32) -> impl Iterator<Item = i32> {
+    (0..n).map(move |i| (i * 37) % n)
+}
+
+/// An allocator that fails every allocation while `fail` is set
+struct Failing {
+    fail: AtomicBool,
+}
+
+unsafe impl GlobalAlloc for Failing {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if self.fail.load(Ordering::Relaxed) {
+            core::ptr::null_mut()
+        } else {
+            System.alloc(layout)
+        }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+fn keys<A>(map: &BTreeMap<A, i32, i32>) -> Vec<A, i32>
+where
+    A: Alloc,
+{
+    map.keys().cloned().collect()
+}
+
+fn insert_get<A>()
+where
+    A: Alloc,
+{
+    let mut m: BTreeMap<A, i32, i32> = BTreeMap::new();
+    assert!(m.is_empty());
+    assert_eq!(m.get(&0), None);
+
+    // enough keys to grow the tree to three levels
+    for k in shuffled(100) {
+        assert_eq!(m.insert(k, k * 10), None);
+    }
+    assert_eq!(m.len(), 100);
+    assert_eq!(m.insert(42, 0), Some(420));
+    assert_eq!(m.len(), 100);
+
+    for k in 0..100 {
+        assert!(m.contains_key(&k));
+    }
+    assert_eq!(m.get(&42), Some(&0));
+    assert_eq!(m.get_key_value(&7), Some((&7, &70)));
+    assert_eq!(m.get(&100), None);
+
+    *m.get_mut(&1).unwrap() = -1;
+    assert_eq!(m[&1], -1);
+
+    assert_eq!(m.first_key_value(), Some((&0, &0)));
+    assert_eq!(m.last_key_value(), Some((&99, &990)));
+}
+
+fn remove<A>()
+where
+    A: Alloc,
+{
+    let mut m: BTreeMap<A, i32, i32> = shuffled(100).map(|k| (k, k)).collect();
+
+    // remove every other key; exercises stealing and merging at every level
+    for k in shuffled(100).filter(|k| k % 2 == 0) {
+        assert_eq!(m.remove(&k), Some(k));
+    }
+    assert_eq!(m.remove(&0), None);
+    assert_eq!(m.len(), 50);
+    assert_eq!(
+        *keys(&m),
+        *(0..100).filter(|k| k % 2 == 1).collect::<Vec<A, _>>()
+    );
+
+    assert_eq!(m.pop_first(), Some((1, 1)));
+    assert_eq!(m.pop_last(), Some((99, 99)));
+    assert_eq!(m.remove_entry(&51), Some((51, 51)));
+
+    while let Some((k, _)) = m.pop_first() {
+        assert!(!m.contains_key(&k));
+    }
+    assert!(m.is_empty());
+    assert_eq!(m.pop_last(), None);
+
+    // the map is still usable after becoming empty
+    m.insert(1, 1);
+    assert_eq!(m.len(), 1);
+}
+
+fn iter<A>()
+where
+    A: Alloc,
+{
+    let mut m: BTreeMap<A, i32, i32> = shuffled(50).map(|k| (k, k)).collect();
+
+    assert_eq!(m.iter().len(), 50);
+    assert_eq!(*keys(&m), *(0..50).collect::<Vec<A, _>>());
+    assert_eq!(
+        *m.values().rev().cloned().collect::<Vec<A, _>>(),
+        *(0..50).rev().collect::<Vec<A, _>>()
+    );
+
+    // meet in the middle
+    let mut it = m.iter();
+    for i in 0..25 {
+        assert_eq!(it.next(), Some((&i, &i)));
+        assert_eq!(it.next_back(), Some((&(49 - i), &(49 - i))));
+    }
+    assert_eq!(it.next(), None);
+    assert_eq!(it.next_back(), None);
+
+    for v in m.values_mut() {
+        *v *= 2;
+    }
+    for (k, v) in &mut m {
+        *v += k;
+    }
+    assert!(m.iter().all(|(k, v)| *v == 3 * k));
+
+    let e: BTreeMap<A, i32, i32> = BTreeMap::new();
+    assert_eq!(e.iter().next(), None);
+    assert_eq!(e.range(..).next_back(), None);
+}
+
+fn range<A>()
+where
+    A: Alloc,
+{
+    let m: BTreeMap<A, i32, i32> = shuffled(60).map(|k| (k * 2, k)).collect();
+
+    let r =
+        |start: i32, end: i32| -> Vec<A, i32> { m.range(start..end).map(|(k, _)| *k).collect() };
+    assert_eq!(*r(10, 20), [10, 12, 14, 16, 18]);
+    assert_eq!(*r(11, 19), [12, 14, 16, 18]);
+    assert_eq!(*r(-5, 3), [0, 2]);
+    assert_eq!(*r(115, 500), [116, 118]);
+    assert_eq!(*r(7, 7), []);
+    assert_eq!(*r(200, 300), []);
+
+    assert_eq!(m.range(..=4).count(), 3);
+    assert_eq!(m.range(114..).count(), 3);
+    assert_eq!(m.range(..).count(), 60);
+    assert_eq!(m.range(40..=60).next_back(), Some((&60, &30)));
+    assert_eq!(m.range(40..=60).rev().count(), 11);
+
+    // mixed directions
+    let mut it = m.range(10..20);
+    assert_eq!(it.next(), Some((&10, &5)));
+    assert_eq!(it.next_back(), Some((&18, &9)));
+    assert_eq!(it.count(), 3);
+
+    let mut m = m;
+    for (_, v) in m.range_mut(0..10) {
+        *v = -1;
+    }
+    assert_eq!(m.values().filter(|v| **v == -1).count(), 5);
+}
+
+fn entry<A>()
+where
+    A: Alloc,
+{
+    let mut m: BTreeMap<A, i32, i32> = BTreeMap::new();
+
+    for k in shuffled(40) {
+        *m.entry(k % 10).or_insert(0) += 1;
+    }
+    assert_eq!(m.len(), 10);
+    assert!(m.values().all(|v| *v == 4));
+
+    match m.entry(3) {
+        Entry::Occupied(mut e) => {
+            assert_eq!(e.key(), &3);
+            assert_eq!(e.insert(7), 4);
+            assert_eq!(e.remove_entry(), (3, 7));
+        }
+        Entry::Vacant(_) => panic!(),
+    }
+    match m.entry(3) {
+        Entry::Vacant(e) => {
+            assert_eq!(e.key(), &3);
+            assert_eq!(*e.insert(9), 9);
+        }
+        Entry::Occupied(_) => panic!(),
+    }
+
+    m.entry(4).and_modify(|v| *v = 0).or_default();
+    m.entry(40).and_modify(|v| *v = 0).or_default();
+    assert_eq!(m[&4], 0);
+    assert_eq!(m[&40], 0);
+    assert_eq!(*m.entry(41).or_insert_with(|| 5), 5);
+    assert_eq!(m.len(), 12);
+}
+
+fn split_off_append<A>()
+where
+    A: Alloc,
+{
+    let mut a: BTreeMap<A, i32, i32> = shuffled(40).map(|k| (k, k)).collect();
+
+    let mut b = a.split_off(&25);
+    assert_eq!(*keys(&a), *(0..25).collect::<Vec<A, _>>());
+    assert_eq!(*keys(&b), *(25..40).collect::<Vec<A, _>>());
+
+    assert!(a.split_off(&100).is_empty());
+    assert_eq!(a.len(), 25);
+
+    b.insert(0, -1);
+    a.append(&mut b);
+    assert!(b.is_empty());
+    assert_eq!(a.len(), 40);
+    // the values of `b` win
+    assert_eq!(a[&0], -1);
+    assert_eq!(*keys(&a), *(0..40).collect::<Vec<A, _>>());
+}
+
+fn into_iter<A>()
+where
+    A: Alloc,
+{
+    let m: BTreeMap<A, i32, i32> = shuffled(50).map(|k| (k, -k)).collect();
+    let mut it = m.into_iter();
+    assert_eq!(it.len(), 50);
+    assert_eq!(it.next(), Some((0, 0)));
+    assert_eq!(it.next_back(), Some((49, -49)));
+    assert_eq!(
+        *it.map(|(k, _)| k).collect::<Vec<A, _>>(),
+        *(1..49).collect::<Vec<A, _>>()
+    );
+
+    // partially consumed iterators drop the remaining entries and free all the nodes
+    let dropped = Cell::new(0);
+    let mut m: BTreeMap<A, i32, D<'_>> = BTreeMap::new();
+    for k in shuffled(50) {
+        m.insert(k, D(&dropped));
+    }
+    m.insert(0, D(&dropped));
+    assert_eq!(dropped.get(), 1);
+
+    let mut it = m.into_iter();
+    drop(it.next());
+    drop(it.next_back());
+    assert_eq!(dropped.get(), 3);
+    drop(it);
+    assert_eq!(dropped.get(), 51);
+
+    let mut m: BTreeMap<A, i32, D<'_>> = BTreeMap::new();
+    m.insert(0, D(&dropped));
+    m.insert(1, D(&dropped));
+    m.clear();
+    assert_eq!(dropped.get(), 53);
+    assert!(m.is_empty());
+}
+
+fn set<A>()
+where
+    A: Alloc,
+{
+    let mut s: BTreeSet<A, i32> = BTreeSet::new();
+    for k in shuffled(30) {
+        assert!(s.insert(k));
+    }
+    assert!(!s.insert(3));
+    assert_eq!(s.len(), 30);
+    assert!(s.contains(&29));
+    assert_eq!(s.get(&4), Some(&4));
+    assert_eq!(s.first(), Some(&0));
+    assert_eq!(s.last(), Some(&29));
+
+    assert!(s.remove(&4));
+    assert!(!s.remove(&4));
+    assert_eq!(s.take(&5), Some(5));
+    assert_eq!(*s.range(2..8).cloned().collect::<Vec<A, _>>(), [2, 3, 6, 7]);
+
+    let mut t = s.split_off(&20);
+    assert_eq!(t.len(), 10);
+    assert_eq!(t.pop_first(), Some(20));
+    assert_eq!(t.pop_last(), Some(29));
+    s.append(&mut t);
+    assert_eq!(s.len(), 26);
+    assert_eq!(
+        *s.iter().rev().take(2).cloned().collect::<Vec<A, _>>(),
+        [28, 27]
+    );
+
+    let v: Vec<A, i32> = s.into_iter().collect();
+    assert_eq!(v.len(), 26);
+    assert!(v.windows(2).all(|w| w[0] < w[1]));
+}
+
+fn traits<A>()
+where
+    A: Alloc,
+{
+    let m: BTreeMap<A, i32, char> = [(2, 'b'), (1, 'a')].iter().cloned().collect();
+    let mut n = m.clone();
+    assert_eq!(m, n);
+    assert_eq!(format!("{:?}", m), "{1: 'a', 2: 'b'}");
+
+    n.insert(0, 'z');
+    assert!(n < m);
+    assert_ne!(m, n);
+    assert_eq!(BTreeMap::<A, i32, char>::default().len(), 0);
+
+    let s: BTreeSet<A, i32> = [3, 1, 2].iter().cloned().collect();
+    assert_eq!(format!("{:?}", s), "{1, 2, 3}");
+    assert_eq!(s.clone(), s);
+}
+
+#[test]
+fn against_std() {
+    use std::collections::BTreeMap as StdBTreeMap;
+
+    #[allocator]
+    static A: Counting = Counting::new();
+
+    let mut m: BTreeMap<A, u32, u32> = BTreeMap::new();
+    let mut s = StdBTreeMap::new();
+
+    // xorshift
+    let mut state = 0x1234_5678u32;
+    let mut next = || {
+        state ^= state << 13;
+        state ^= state >> 17;
+        state ^= state << 5;
+        state
+    };
+
+    for i in 0..20_000 {
+        let k = next() % 1_000;
+        if next() % 3 == 0 {
+            assert_eq!(m.remove(&k), s.remove(&k));
+        } else {
+            assert_eq!(m.insert(k, i), s.insert(k, i));
+        }
+
+        if i % 1_000 == 0 {
+            assert_eq!(m.len(), s.len());
+            assert!(m.iter().eq(s.iter()));
+            assert!(m.iter().rev().eq(s.iter().rev()));
+            assert!(m.range(250..750).eq(s.range(250..750)));
+        }
+    }
+
+    assert!(m.iter().eq(s.iter()));
+    drop(m);
+    assert_eq!(A.live(), 0);
+}
+
+#[test]
+fn split_off_append_against_std() {
+    use std::collections::BTreeMap as StdBTreeMap;
+
+    #[allocator]
+    static A: Counting = Counting::new();
+
+    for &n in &[0, 1, 10, 11, 12, 100, 1_000] {
+        for at in (0..=n + 1).step_by(cmp::max(n as usize / 20, 1)) {
+            let mut l: BTreeMap<A, i32, i32> = shuffled(n).map(|k| (k, k)).collect();
+            let mut s: StdBTreeMap<i32, i32> = (0..n).map(|k| (k, k)).collect();
+
+            let mut r = l.split_off(&at);
+            let mut t = s.split_off(&at);
+            assert_eq!((l.len(), r.len()), (s.len(), t.len()));
+            assert!(l.iter().eq(s.iter()));
+            assert!(r.iter().eq(t.iter()));
+
+            // both halves are valid trees
+            for k in (0..n + 2).step_by(3) {
+                assert_eq!(l.remove(&k), s.remove(&k));
+                assert_eq!(r.remove(&k), t.remove(&k));
+            }
+            for k in (0..n + 2).step_by(2) {
+                assert_eq!(l.insert(k, -k), s.insert(k, -k));
+                assert_eq!(r.insert(k, k), t.insert(k, k));
+            }
+            assert!(l.iter().eq(s.iter()));
+            assert!(r.iter().rev().eq(t.iter().rev()));
+
+            l.append(&mut r);
+            s.append(&mut t);
+            assert!(r.is_empty());
+            assert_eq!(l.len(), s.len());
+            assert!(l.iter().eq(s.iter()));
+
+            // and so is the merged tree
+            for k in (0..n + 2).step_by(5) {
+                assert_eq!(l.remove(&k), s.remove(&k));
+            }
+            assert!(l.iter().rev().eq(s.iter().rev()));
+        }
+    }
+
+    assert_eq!(A.live(), 0);
+}
+
+#[test]
+fn split_off_append_oom() {
+    #[allocator]
+    static A: Failing = Failing {
+        fail: AtomicBool::new(false),
+    };
+
+    let mut a: BTreeMap<A, i32, i32> = shuffled(100).map(|k| (k, k)).collect();
+    let mut b: BTreeMap<A, i32, i32> = (100..200).map(|k| (k, k)).collect();
+
+    A.fail.store(true, Ordering::Relaxed);
+    assert!(panic::catch_unwind(AssertUnwindSafe(|| a.split_off(&50))).is_err());
+    assert!(panic::catch_unwind(AssertUnwindSafe(|| a.append(&mut b))).is_err());
+    A.fail.store(false, Ordering::Relaxed);
+
+    // both maps are left untouched
+    assert_eq!(*keys(&a), *(0..100).collect::<Vec<A, _>>());
+    assert_eq!(*keys(&b), *(100..200).collect::<Vec<A, _>>());
+}
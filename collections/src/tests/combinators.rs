@@ -0,0 +1,156 @@
+use core::alloc::Layout;
+
+use alloc_many::{
+    allocator,
+    combinators::{Fallback, Segregator},
+    Alloc, Owns,
+};
+use alloc_many_bump::{consts, BumpAlloc};
+
+use super::Counting;
+use crate::{boxed::Box, vec::Vec};
+
+#[test]
+fn owns() {
+    #[allocator]
+    static A: BumpAlloc<consts::U64> = BumpAlloc::new();
+
+    #[allocator]
+    static B: Counting = Counting::new();
+
+    let layout = Layout::new::<u32>();
+    let a: Box<A, u32> = Box::new(0);
+    let b: Box<B, u32> = Box::new(0);
+    let pa = &*a as *const u32 as *mut u8;
+    let pb = &*b as *const u32 as *mut u8;
+
+    // NOTE the query is available on the singleton through `Deref`
+    assert!(A.owns(pa, layout));
+    assert!(!A.owns(pb, layout));
+    assert!(!A.owns(pa, Layout::new::<[u8; 128]>()));
+}
+
+#[test]
+fn fallback() {
+    #[allocator]
+    static A: Fallback<BumpAlloc<consts::U64>, Counting> =
+        Fallback::new(BumpAlloc::new(), Counting::new());
+
+    let layout = Layout::new::<[u8; 32]>();
+    let x: Box<A, [u8; 32]> = Box::new([0; 32]);
+    let y: Box<A, [u8; 32]> = Box::new([1; 32]);
+    assert!(A.primary().owns(&*x as *const _ as *mut u8, layout));
+    assert!(A.primary().owns(&*y as *const _ as *mut u8, layout));
+    assert_eq!(A.secondary().live(), 0);
+
+    // the primary allocator is full
+    let z: Box<A, [u8; 32]> = Box::new([2; 32]);
+    assert!(!A.primary().owns(&*z as *const _ as *mut u8, layout));
+    assert_eq!(A.secondary().live(), 1);
+    assert_eq!(*z, [2; 32]);
+
+    // `dealloc` is routed to the owner
+    drop(z);
+    assert_eq!(A.secondary().live(), 0);
+
+    unsafe {
+        let p = A::alloc_zeroed(Layout::new::<u64>());
+        assert!(!p.is_null());
+        assert_eq!(*(p as *mut u64), 0);
+        assert_eq!(A.secondary().live(), 1);
+        A::dealloc(p, Layout::new::<u64>());
+        assert_eq!(A.secondary().live(), 0);
+    }
+
+    drop((x, y));
+}
+
+#[test]
+fn fallback_realloc() {
+    #[allocator]
+    static A: Fallback<BumpAlloc<consts::U64>, Counting> =
+        Fallback::new(BumpAlloc::new(), Counting::new());
+
+    // the buffer starts on the primary allocator and moves to the secondary one when it grows
+    // past the primary's capacity
+    let mut v: Vec<A, u8> = Vec::new();
+    for i in 0..16 {
+        v.push(i);
+    }
+    assert!(A
+        .primary()
+        .owns(v.as_ptr() as *mut u8, Layout::new::<[u8; 16]>()));
+    assert_eq!(A.secondary().live(), 0);
+
+    for i in 16..100 {
+        v.push(i);
+    }
+    assert_eq!(A.secondary().live(), 1);
+    assert!(v.iter().cloned().eq(0..100));
+
+    drop(v);
+    assert_eq!(A.secondary().live(), 0);
+}
+
+#[test]
+fn segregator() {
+    #[allocator]
+    static A: Segregator<Counting, Counting> =
+        Segregator::new(16, Counting::new(), Counting::new());
+
+    let x: Box<A, u64> = Box::new(1);
+    assert_eq!((A.small().live(), A.large().live()), (1, 0));
+    let y: Box<A, [u64; 4]> = Box::new([2; 4]);
+    assert_eq!((A.small().live(), A.large().live()), (1, 1));
+    drop((x, y));
+    assert_eq!((A.small().live(), A.large().live()), (0, 0));
+
+    // reallocations move the buffer across the threshold, in both directions
+    let mut v: Vec<A, u32> = Vec::with_capacity(4);
+    v.extend_from_slice(&[0, 1, 2]);
+    assert_eq!((A.small().live(), A.large().live()), (1, 0));
+    v.extend_from_slice(&[3, 4, 5]);
+    assert_eq!((A.small().live(), A.large().live()), (0, 1));
+    v.truncate(2);
+    v.shrink_to_fit();
+    assert_eq!((A.small().live(), A.large().live()), (1, 0));
+    assert_eq!(*v, [0, 1]);
+
+    drop(v);
+    assert_eq!((A.small().live(), A.large().live()), (0, 0));
+}
+
+#[test]
+fn nested() {
+    type Small = Fallback<BumpAlloc<consts::U32>, Counting>;
+
+    #[allocator]
+    static A: Segregator<Small, Counting> = Segregator::new(
+        8,
+        Fallback::new(BumpAlloc::new(), Counting::new()),
+        Counting::new(),
+    );
+
+    let mut xs: Vec<A, Box<A, u64>> = Vec::with_capacity(8);
+    xs.extend((0..8).map(Box::new));
+    assert!(xs.iter().map(|x| **x).eq(0..8));
+    // the vector's buffer
+    assert_eq!(A.large().live(), 1);
+
+    // the first boxes fit in the bump allocator
+    let layout = Layout::new::<u64>();
+    let on_bump = xs
+        .iter()
+        .filter(|x| {
+            A.small()
+                .primary()
+                .owns(&***x as *const u64 as *mut u8, layout)
+        })
+        .count();
+    assert!(on_bump >= 3);
+    assert_eq!(A.small().secondary().live(), 8 - on_bump);
+
+    drop(xs);
+    assert_eq!(A.small().secondary().live(), 0);
+    assert_eq!(A.large().live(), 0);
+}
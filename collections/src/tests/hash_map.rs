@@ -0,0 +1,498 @@
+use core::{
+    cell::Cell,
+    hash::{BuildHasherDefault, Hash, Hasher},
+};
+use std::{
+    format,
+    panic::{self, AssertUnwindSafe},
+    string::String as StdString,
+};
+
+use alloc_many::{allocator, Alloc};
+use alloc_many_bump::{consts, BumpAlloc};
+
+use crate::{
+    hash_map::{DefaultHasher, Entry, HashMap},
+    hash_set::HashSet,
+    tests::Counting,
+    vec::Vec,
+    TryReserveError,
+};
+
+alloc_tests!(
+    insert_get,
+    remove,
+    entry,
+    iter,
+    retain_drain,
+    capacity,
+    set,
+    traits,
+    drop_elements
+);
+
+/// Increments a counter when dropped
+struct D<'a>(&'a Cell<usize>);
+
+impl Drop for D<'_> {
+    fn drop(&mut self) {
+        self.0.set(self.0.get() + 1);
+    }
+}
+
+fn sorted_keys<A>(map: &HashMap<A, i32, i32>) -> Vec<A, i32>
+where
+    A: Alloc,
+{
+    let mut keys: Vec<A, i32> = map.keys().cloned().collect();
+    keys.sort();
+    keys
+}
+
+fn insert_get<A>()
+where
+    A: Alloc,
+{
+    let mut m: HashMap<A, i32, i32> = HashMap::new();
+    assert!(m.is_empty());
+    assert_eq!(m.capacity(), 0);
+    assert_eq!(m.get(&0), None);
+
+    for k in 0..50 {
+        assert_eq!(m.insert(k, k * 10), None);
+    }
+    assert_eq!(m.len(), 50);
+    assert_eq!(m.insert(42, 0), Some(420));
+    assert_eq!(m.len(), 50);
+
+    for k in 0..50 {
+        assert!(m.contains_key(&k));
+    }
+    assert!(!m.contains_key(&50));
+    assert_eq!(m.get(&42), Some(&0));
+    assert_eq!(m.get_key_value(&7), Some((&7, &70)));
+
+    *m.get_mut(&1).unwrap() = -1;
+    assert_eq!(m[&1], -1);
+
+    // `Borrow` lookups
+    let mut s: HashMap<A, StdString, i32> = HashMap::new();
+    s.insert("one".into(), 1);
+    assert_eq!(s.get("one"), Some(&1));
+    assert_eq!(s.remove("one"), Some(1));
+    assert!(s.is_empty());
+}
+
+fn remove<A>()
+where
+    A: Alloc,
+{
+    let mut m: HashMap<A, i32, i32> = (0..40).map(|k| (k, k)).collect();
+
+    for k in (0..40).filter(|k| k % 2 == 0) {
+        assert_eq!(m.remove(&k), Some(k));
+    }
+    assert_eq!(m.remove(&0), None);
+    assert_eq!(m.len(), 20);
+    assert_eq!(
+        *sorted_keys(&m),
+        *(0..40).filter(|k| k % 2 == 1).collect::<Vec<A, _>>()
+    );
+    assert_eq!(m.remove_entry(&1), Some((1, 1)));
+
+    // reuse the freed buckets
+    let cap = m.capacity();
+    for round in 0..10 {
+        for k in 100..110 {
+            m.insert(k, round);
+        }
+        for k in 100..110 {
+            assert_eq!(m.remove(&k), Some(round));
+        }
+    }
+    assert_eq!(m.capacity(), cap);
+    assert_eq!(m.len(), 19);
+}
+
+fn entry<A>()
+where
+    A: Alloc,
+{
+    let mut m: HashMap<A, i32, i32> = HashMap::new();
+
+    for k in 0..40 {
+        *m.entry(k % 10).or_insert(0) += 1;
+    }
+    assert_eq!(m.len(), 10);
+    assert!(m.values().all(|v| *v == 4));
+
+    match m.entry(3) {
+        Entry::Occupied(mut e) => {
+            assert_eq!(e.key(), &3);
+            assert_eq!(e.insert(7), 4);
+            assert_eq!(e.remove_entry(), (3, 7));
+        }
+        Entry::Vacant(_) => panic!(),
+    }
+    match m.entry(3) {
+        Entry::Vacant(e) => {
+            assert_eq!(e.key(), &3);
+            assert_eq!(*e.insert(9), 9);
+        }
+        Entry::Occupied(_) => panic!(),
+    }
+
+    m.entry(4).and_modify(|v| *v = 0).or_default();
+    m.entry(40).and_modify(|v| *v = 0).or_default();
+    assert_eq!(m[&4], 0);
+    assert_eq!(m[&40], 0);
+    assert_eq!(*m.entry(41).or_insert_with(|| 5), 5);
+    assert_eq!(m.len(), 12);
+}
+
+fn iter<A>()
+where
+    A: Alloc,
+{
+    let mut m: HashMap<A, i32, i32> = (0..30).map(|k| (k, k)).collect();
+    assert_eq!(m.iter().len(), 30);
+    assert_eq!(m.values().sum::<i32>(), (0..30).sum());
+
+    for v in m.values_mut() {
+        *v *= 2;
+    }
+    for (k, v) in &mut m {
+        *v += k;
+    }
+    assert!(m.iter().all(|(k, v)| *v == 3 * k));
+
+    let mut it = m.into_iter();
+    assert_eq!(it.len(), 30);
+    it.next();
+    assert_eq!(it.len(), 29);
+    let mut rest: Vec<A, (i32, i32)> = it.collect();
+    rest.sort();
+    assert_eq!(rest.len(), 29);
+}
+
+fn retain_drain<A>()
+where
+    A: Alloc,
+{
+    let mut m: HashMap<A, i32, i32> = (0..30).map(|k| (k, k)).collect();
+    m.retain(|k, v| {
+        *v += 1;
+        k % 3 == 0
+    });
+    assert_eq!(m.len(), 10);
+    assert!(m.iter().all(|(k, v)| k % 3 == 0 && *v == k + 1));
+
+    let cap = m.capacity();
+    let mut drained: Vec<A, (i32, i32)> = m.drain().collect();
+    drained.sort();
+    assert_eq!(drained.len(), 10);
+    assert_eq!(drained[1], (3, 4));
+    assert!(m.is_empty());
+    assert_eq!(m.capacity(), cap);
+    assert_eq!(m.get(&3), None);
+
+    // partially consumed `Drain`s still empty the map
+    m.extend((0..10).map(|k| (k, k)));
+    assert_eq!(m.drain().take(2).count(), 2);
+    assert!(m.is_empty());
+    m.insert(1, 1);
+    assert_eq!(m[&1], 1);
+}
+
+fn capacity<A>()
+where
+    A: Alloc,
+{
+    let mut m: HashMap<A, i32, i32> = HashMap::with_capacity(10);
+    assert!(m.capacity() >= 10);
+    let cap = m.capacity();
+    for k in 0..10 {
+        m.insert(k, k);
+    }
+    assert_eq!(m.capacity(), cap);
+
+    m.reserve(20);
+    assert!(m.capacity() >= 30);
+    assert_eq!(m.try_reserve(0), Ok(()));
+    assert_eq!(
+        m.try_reserve(usize::max_value()),
+        Err(TryReserveError::CapacityOverflow)
+    );
+
+    for k in 2..10 {
+        m.remove(&k);
+    }
+    m.shrink_to_fit();
+    assert!(m.capacity() >= 2 && m.capacity() < 10);
+    assert_eq!(*sorted_keys(&m), [0, 1]);
+
+    m.clear();
+    m.shrink_to_fit();
+    assert_eq!(m.capacity(), 0);
+}
+
+fn set<A>()
+where
+    A: Alloc,
+{
+    let mut s: HashSet<A, i32> = HashSet::new();
+    for k in 0..30 {
+        assert!(s.insert(k));
+    }
+    assert!(!s.insert(3));
+    assert_eq!(s.len(), 30);
+    assert!(s.contains(&29));
+    assert_eq!(s.get(&4), Some(&4));
+    assert!(s.remove(&4));
+    assert!(!s.remove(&4));
+    assert_eq!(s.take(&5), Some(5));
+
+    s.retain(|k| k % 2 == 0);
+    assert_eq!(s.len(), 14);
+    assert_eq!(
+        s.iter().cloned().sum::<i32>(),
+        (0..30).filter(|k| k % 2 == 0).sum::<i32>() - 4
+    );
+
+    let t: HashSet<A, i32> = s.iter().cloned().collect();
+    assert_eq!(s, t);
+
+    assert_eq!(s.drain().count(), 14);
+    assert!(s.is_empty());
+    assert_eq!(t.into_iter().count(), 14);
+}
+
+fn traits<A>()
+where
+    A: Alloc,
+{
+    let m: HashMap<A, i32, char> = [(1, 'a')].iter().cloned().collect();
+    let mut n = m.clone();
+    assert_eq!(m, n);
+    assert_eq!(format!("{:?}", m), "{1: 'a'}");
+
+    n.insert(0, 'z');
+    assert_ne!(m, n);
+    assert_eq!(HashMap::<A, i32, char>::default().len(), 0);
+
+    let s: HashSet<A, i32> = [3].iter().cloned().collect();
+    assert_eq!(format!("{:?}", s), "{3}");
+
+    // custom `BuildHasher`
+    let mut c: HashMap<A, i32, i32, BuildHasherDefault<DefaultHasher>> = HashMap::default();
+    c.insert(1, 1);
+    assert_eq!(c.get(&1), Some(&1));
+}
+
+fn drop_elements<A>()
+where
+    A: Alloc,
+{
+    let dropped = Cell::new(0);
+    let mut m: HashMap<A, i32, D<'_>> = HashMap::new();
+    for k in 0..20 {
+        m.insert(k, D(&dropped));
+    }
+    m.insert(0, D(&dropped));
+    assert_eq!(dropped.get(), 1);
+
+    drop(m.remove(&1));
+    assert_eq!(dropped.get(), 2);
+    m.retain(|k, _| *k < 10);
+    assert_eq!(dropped.get(), 12);
+
+    let mut it = m.into_iter();
+    drop(it.next());
+    drop(it);
+    assert_eq!(dropped.get(), 21);
+}
+
+#[test]
+fn panicking_hash() {
+    #[allocator]
+    static A: Counting = Counting::new();
+
+    /// A key whose `Hash` implementation panics once `budget` hashes have been computed
+    struct K<'a> {
+        id: i32,
+        budget: &'a Cell<usize>,
+        _d: D<'a>,
+    }
+
+    impl Hash for K<'_> {
+        fn hash<H>(&self, state: &mut H)
+        where
+            H: Hasher,
+        {
+            let budget = self.budget.get();
+            if budget == 0 {
+                panic!("hash")
+            }
+            self.budget.set(budget - 1);
+
+            self.id.hash(state)
+        }
+    }
+
+    impl PartialEq for K<'_> {
+        fn eq(&self, other: &Self) -> bool {
+            self.id == other.id
+        }
+    }
+
+    impl Eq for K<'_> {}
+
+    let dropped = Cell::new(0);
+    let budget = Cell::new(usize::max_value());
+    let mut m: HashMap<A, K<'_>, i32> = HashMap::new();
+    for id in 0..8 {
+        let k = K {
+            id,
+            budget: &budget,
+            _d: D(&dropped),
+        };
+        m.insert(k, id);
+    }
+
+    // the hasher panics halfway through growing the table
+    budget.set(4);
+    assert!(panic::catch_unwind(AssertUnwindSafe(|| m.reserve(100))).is_err());
+
+    // the elements that were moved to the new table have been dropped exactly once
+    assert_eq!(dropped.get(), 4);
+    assert_eq!(m.len(), 4);
+    assert_eq!(m.iter().count(), 4);
+
+    drop(m);
+    assert_eq!(dropped.get(), 8);
+    assert_eq!(A.live(), 0);
+}
+
+#[test]
+fn panicking_clear() {
+    #[allocator]
+    static A: Counting = Counting::new();
+
+    /// Panics when dropped if `panic` is set
+    struct P<'a> {
+        panic: bool,
+        _d: D<'a>,
+    }
+
+    impl Drop for P<'_> {
+        fn drop(&mut self) {
+            if self.panic {
+                panic!("drop")
+            }
+        }
+    }
+
+    let dropped = Cell::new(0);
+    let mut m: HashMap<A, i32, P<'_>> = HashMap::new();
+    for k in 0..8 {
+        let v = P {
+            panic: k == 4,
+            _d: D(&dropped),
+        };
+        m.insert(k, v);
+    }
+
+    assert!(panic::catch_unwind(AssertUnwindSafe(|| m.clear())).is_err());
+
+    // the map is empty and no element is dropped twice
+    assert!(m.is_empty());
+    drop(m);
+    assert!(dropped.get() <= 8);
+    assert_eq!(A.live(), 0);
+}
+
+#[test]
+fn alloc_zeroed() {
+    // the table is the only allocation; it gets its control bytes zeroed by the allocator
+    #[allocator]
+    static A: BumpAlloc<consts::U256> = BumpAlloc::new();
+
+    let mut m: HashMap<A, u8, u8> = HashMap::with_capacity(7);
+    for k in 0..7 {
+        m.insert(k, k);
+    }
+    assert_eq!(m.len(), 7);
+
+    // no space left to grow the table
+    match m.try_reserve(100) {
+        Err(TryReserveError::AllocError { .. }) => {}
+        res => panic!("{:?}", res),
+    }
+    assert_eq!(m.len(), 7);
+}
+
+#[test]
+fn against_std() {
+    use std::collections::HashMap as StdHashMap;
+
+    #[allocator]
+    static A: Counting = Counting::new();
+
+    let mut m: HashMap<A, u32, u32> = HashMap::new();
+    let mut s = StdHashMap::new();
+
+    // xorshift
+    let mut state = 0x1234_5678u32;
+    let mut next = || {
+        state ^= state << 13;
+        state ^= state >> 17;
+        state ^= state << 5;
+        state
+    };
+
+    for i in 0..20_000 {
+        let k = next() % 1_000;
+        if next() % 3 == 0 {
+            assert_eq!(m.remove(&k), s.remove(&k));
+        } else {
+            assert_eq!(m.insert(k, i), s.insert(k, i));
+        }
+
+        if i % 1_000 == 0 {
+            assert_eq!(m.len(), s.len());
+            assert!(s.iter().all(|(k, v)| m.get(k) == Some(v)));
+        }
+    }
+
+    assert_eq!(m.iter().count(), s.len());
+    drop(m);
+    assert_eq!(A.live(), 0);
+}
+
+#[test]
+fn iter_send_sync() {
+    fn assert_send_sync<T>(_: &T)
+    where
+        T: Send + Sync,
+    {
+    }
+
+    #[allocator]
+    static A: Counting = Counting::new();
+
+    let mut map: HashMap<A, i32, i32> = HashMap::new();
+    map.insert(1, 2);
+    assert_send_sync(&map.iter());
+    assert_send_sync(&map.iter_mut());
+    assert_send_sync(&map.keys());
+    assert_send_sync(&map.values());
+    assert_send_sync(&map.values_mut());
+    assert_send_sync(&map.drain());
+    assert_send_sync(&map.into_iter());
+
+    let mut set: HashSet<A, i32> = HashSet::new();
+    set.insert(1);
+    assert_send_sync(&set.iter());
+    assert_send_sync(&set.drain());
+    assert_send_sync(&set.into_iter());
+}
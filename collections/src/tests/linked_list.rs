@@ -0,0 +1,319 @@
+use core::{alloc::Layout, cell::Cell};
+use std::format;
+
+use alloc_many::{allocator, Alloc};
+use alloc_many_bump::{consts, BumpAlloc};
+
+use crate::{linked_list::LinkedList, tests::Counting, vec::Vec, TryReserveError};
+
+alloc_tests!(
+    push_pop,
+    append_split_off,
+    iter,
+    cursor,
+    traits,
+    drop_elements
+);
+
+/// Increments a counter when dropped
+struct D<'a>(&'a Cell<usize>);
+
+impl Drop for D<'_> {
+    fn drop(&mut self) {
+        self.0.set(self.0.get() + 1);
+    }
+}
+
+fn to_vec<A>(list: &LinkedList<A, i32>) -> Vec<A, i32>
+where
+    A: Alloc,
+{
+    list.iter().cloned().collect()
+}
+
+fn push_pop<A>()
+where
+    A: Alloc,
+{
+    let mut l: LinkedList<A, i32> = LinkedList::new();
+    assert!(l.is_empty());
+    assert_eq!(l.front(), None);
+    assert_eq!(l.back(), None);
+    assert_eq!(l.pop_front(), None);
+    assert_eq!(l.pop_back(), None);
+
+    l.push_back(2);
+    l.push_front(1);
+    l.push_back(3);
+    assert_eq!(l.len(), 3);
+    assert_eq!(l.front(), Some(&1));
+    assert_eq!(l.back(), Some(&3));
+
+    *l.front_mut().unwrap() = 0;
+    *l.back_mut().unwrap() = 4;
+    assert_eq!(*to_vec(&l), [0, 2, 4]);
+    assert!(l.contains(&2));
+    assert!(!l.contains(&3));
+
+    assert_eq!(l.pop_front(), Some(0));
+    assert_eq!(l.pop_back(), Some(4));
+    assert_eq!(l.pop_back(), Some(2));
+    assert_eq!(l.pop_front(), None);
+    assert!(l.is_empty());
+
+    // the list is still usable after being emptied
+    l.push_front(5);
+    assert_eq!(l.back(), Some(&5));
+    l.clear();
+    assert!(l.is_empty());
+}
+
+fn append_split_off<A>()
+where
+    A: Alloc,
+{
+    let mut a: LinkedList<A, i32> = (0..5).collect();
+    let mut b: LinkedList<A, i32> = (5..10).collect();
+    a.append(&mut b);
+    assert!(b.is_empty());
+    assert_eq!(a.len(), 10);
+    assert_eq!(*to_vec(&a), *(0..10).collect::<Vec<A, _>>());
+
+    // appending to an empty list
+    b.append(&mut a);
+    assert!(a.is_empty());
+    assert_eq!(b.len(), 10);
+    b.append(&mut a);
+    assert_eq!(b.len(), 10);
+
+    let c = b.split_off(7);
+    assert_eq!(*to_vec(&b), *(0..7).collect::<Vec<A, _>>());
+    assert_eq!(*to_vec(&c), [7, 8, 9]);
+    assert_eq!(c.back(), Some(&9));
+    assert_eq!(b.back(), Some(&6));
+
+    let d = b.split_off(2);
+    assert_eq!(*to_vec(&b), [0, 1]);
+    assert_eq!(*to_vec(&d), [2, 3, 4, 5, 6]);
+
+    let e = b.split_off(2);
+    assert!(e.is_empty());
+    let f = b.split_off(0);
+    assert!(b.is_empty());
+    assert_eq!(*to_vec(&f), [0, 1]);
+}
+
+fn iter<A>()
+where
+    A: Alloc,
+{
+    let mut l: LinkedList<A, i32> = (0..6).collect();
+
+    let mut it = l.iter();
+    assert_eq!(it.len(), 6);
+    assert_eq!(it.next(), Some(&0));
+    assert_eq!(it.next_back(), Some(&5));
+    assert_eq!(it.len(), 4);
+    assert_eq!(it.rev().cloned().collect::<Vec<A, _>>()[..], [4, 3, 2, 1]);
+
+    for x in l.iter_mut().rev() {
+        *x *= 2;
+    }
+    for x in &mut l {
+        *x += 1;
+    }
+    assert_eq!(*to_vec(&l), [1, 3, 5, 7, 9, 11]);
+
+    let mut it = l.into_iter();
+    assert_eq!(it.next_back(), Some(11));
+    assert_eq!(it.next(), Some(1));
+    assert_eq!(it.len(), 4);
+}
+
+fn cursor<A>()
+where
+    A: Alloc,
+{
+    let mut l: LinkedList<A, i32> = (0..5).collect();
+
+    {
+        let mut c = l.cursor_front_mut();
+        assert_eq!(c.index(), Some(0));
+        assert_eq!(c.current(), Some(&mut 0));
+        assert_eq!(c.peek_prev(), None);
+        assert_eq!(c.peek_next(), Some(&mut 1));
+
+        c.move_next();
+        c.move_next();
+        assert_eq!(c.index(), Some(2));
+
+        // O(1) removal from the middle
+        assert_eq!(c.remove_current(), Some(2));
+        assert_eq!(c.current(), Some(&mut 3));
+        assert_eq!(c.index(), Some(2));
+
+        c.insert_before(20);
+        assert_eq!(c.index(), Some(3));
+        assert_eq!(c.peek_prev(), Some(&mut 20));
+        c.insert_after(30);
+        assert_eq!(c.peek_next(), Some(&mut 30));
+        assert_eq!(c.index(), Some(3));
+    }
+    assert_eq!(*to_vec(&l), [0, 1, 20, 3, 30, 4]);
+
+    {
+        let mut c = l.cursor_back_mut();
+        assert_eq!(c.index(), Some(5));
+        assert_eq!(c.remove_current(), Some(4));
+
+        // the "ghost" non-element sits between the tail and the head
+        assert_eq!(c.index(), None);
+        assert_eq!(c.current(), None);
+        assert_eq!(c.remove_current(), None);
+        assert_eq!(c.peek_next(), Some(&mut 0));
+        assert_eq!(c.peek_prev(), Some(&mut 30));
+
+        c.insert_after(-1);
+        c.insert_before(40);
+        assert_eq!(c.index(), None);
+
+        c.move_next();
+        assert_eq!(c.index(), Some(0));
+        assert_eq!(c.current(), Some(&mut -1));
+        c.move_prev();
+        assert_eq!(c.index(), None);
+        c.move_prev();
+        assert_eq!(c.index(), Some(6));
+        assert_eq!(c.current(), Some(&mut 40));
+    }
+    assert_eq!(*to_vec(&l), [-1, 0, 1, 20, 3, 30, 40]);
+
+    // remove everything through a cursor
+    {
+        let mut c = l.cursor_front_mut();
+        while c.remove_current().is_some() {}
+        assert_eq!(c.index(), None);
+    }
+    assert!(l.is_empty());
+    assert_eq!(l.front(), None);
+    assert_eq!(l.back(), None);
+
+    let mut c = l.cursor_back_mut();
+    assert_eq!(c.current(), None);
+    c.insert_before(1);
+    c.insert_before(2);
+    c.move_next();
+    assert_eq!(c.current(), Some(&mut 1));
+    assert_eq!(c.peek_next(), Some(&mut 2));
+}
+
+fn traits<A>()
+where
+    A: Alloc,
+{
+    let l: LinkedList<A, i32> = [1, 2].iter().cloned().collect();
+    let mut m = l.clone();
+    assert_eq!(l, m);
+    assert_eq!(format!("{:?}", m), "[1, 2]");
+
+    m.push_back(0);
+    assert_ne!(l, m);
+    assert!(l < m);
+    m.pop_front();
+    assert!(l < m);
+
+    let mut d = LinkedList::<A, i32>::default();
+    assert!(d.is_empty());
+    d.extend(&[1, 2]);
+    assert_eq!(d, l);
+}
+
+fn drop_elements<A>()
+where
+    A: Alloc,
+{
+    let dropped = Cell::new(0);
+    let mut l: LinkedList<A, D<'_>> = LinkedList::new();
+    for _ in 0..10 {
+        l.push_back(D(&dropped));
+    }
+
+    drop(l.pop_front());
+    assert_eq!(dropped.get(), 1);
+
+    {
+        let mut c = l.cursor_front_mut();
+        c.move_next();
+        drop(c.remove_current());
+    }
+    assert_eq!(dropped.get(), 2);
+
+    let r = l.split_off(4);
+    drop(l);
+    assert_eq!(dropped.get(), 6);
+
+    let mut it = r.into_iter();
+    drop(it.next_back());
+    drop(it);
+    assert_eq!(dropped.get(), 10);
+}
+
+#[test]
+fn live_nodes() {
+    #[allocator]
+    static A: Counting = Counting::new();
+
+    // one allocation per node
+    let mut l: LinkedList<A, u64> = LinkedList::new();
+    assert_eq!(A.live(), 0);
+    for i in 0..100 {
+        l.push_back(i);
+        assert_eq!(A.live(), l.len());
+    }
+
+    let mut r = l.split_off(30);
+    l.append(&mut r);
+    assert_eq!(A.live(), 100);
+
+    {
+        let mut c = l.cursor_front_mut();
+        while let Some(x) = c.current() {
+            if *x % 2 == 0 {
+                c.remove_current();
+            } else {
+                c.move_next();
+            }
+        }
+    }
+    assert_eq!(l.len(), 50);
+    assert_eq!(A.live(), 50);
+
+    drop(r);
+    drop(l);
+    assert_eq!(A.live(), 0);
+}
+
+#[test]
+fn oom() {
+    #[allocator]
+    static A: BumpAlloc<consts::U64> = BumpAlloc::new();
+
+    let mut l: LinkedList<A, u32> = LinkedList::new();
+    let mut n = 0;
+    while l.try_push_back(n).is_ok() {
+        n += 1;
+    }
+    assert!(n > 0);
+
+    // the list is left untouched
+    assert_eq!(l.len(), n as usize);
+    assert_eq!(l.back(), Some(&(n - 1)));
+    match l.try_push_front(0) {
+        Err(TryReserveError::AllocError { layout }) => {
+            assert_eq!(layout, Layout::new::<[usize; 3]>())
+        }
+        res => panic!("{:?}", res),
+    }
+    assert!(l.cursor_front_mut().try_insert_after(0).is_err());
+    assert_eq!(l.len(), n as usize);
+}
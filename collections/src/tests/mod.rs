@@ -0,0 +1,184 @@
+use core::{
+    alloc::{GlobalAlloc, Layout},
+    sync::atomic::{AtomicU8, AtomicUsize, Ordering},
+};
+use std::alloc::System;
+
+use alloc_many::{allocator, oom, AllocInfo};
+use alloc_many_bump::{consts, BumpAlloc};
+
+use crate::{boxed::Box, vec::Vec};
+
+/// Runs each generic test against a bump allocator and against an allocator that frees memory
+macro_rules! alloc_tests {
+    ($($test:ident),* $(,)*) => {
+        $(
+            mod $test {
+                use alloc_many::allocator;
+                use alloc_many_bump::{consts, BumpAlloc};
+
+                use crate::tests::Counting;
+
+                #[test]
+                fn bump() {
+                    #[allocator]
+                    static A: BumpAlloc<consts::U4096> = BumpAlloc::new();
+
+                    super::$test::<A>()
+                }
+
+                #[test]
+                fn counting() {
+                    #[allocator]
+                    static A: Counting = Counting::new();
+
+                    super::$test::<A>();
+                    assert_eq!(A.live(), 0);
+                }
+            }
+        )*
+    };
+}
+
+mod allocator;
+mod binary_heap;
+mod boxed;
+mod btree_map;
+mod combinators;
+mod hash_map;
+mod linked_list;
+mod rc;
+mod stats;
+mod string;
+mod sync;
+mod vec;
+mod vec_deque;
+
+#[oom]
+fn oom(_: Layout, info: &AllocInfo) -> ! {
+    panic!("{:?}", info)
+}
+
+#[test]
+fn sanity() {
+    #[allocator]
+    static A: BumpAlloc<consts::U128> = BumpAlloc::new();
+
+    let x: Box<A, _> = Box::new(0u8);
+    assert_eq!(*x, 0);
+
+    // test aligned allocation
+    let y: Box<A, _> = Box::new(1);
+    assert_eq!(&*y as *const i32 as usize % 4, 0);
+    assert_eq!(*y, 1);
+
+    let z: Box<A, _> = Box::new([2, 3]);
+    assert_eq!(*z, [2, 3]);
+
+    // test `Drop` implementation
+    static X: AtomicU8 = AtomicU8::new(2);
+    struct Z;
+    impl Drop for Z {
+        fn drop(&mut self) {
+            X.fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+
+    let w: Box<A, _> = Box::new([Z, Z]);
+    drop(w);
+    assert_eq!(X.load(Ordering::Relaxed), 0);
+
+    // OOM
+    use alloc_many::Alloc;
+    unsafe {
+        assert!(A::alloc(Layout::from_size_align(128, 1).unwrap()).is_null());
+    }
+}
+
+#[test]
+fn fallible() {
+    use crate::TryReserveError;
+
+    #[allocator]
+    static A: BumpAlloc<consts::U64> = BumpAlloc::new();
+
+    // NOTE `x` is kept alive so that the vector can't grow in place
+    let x: Box<A, _> = Box::try_new([0u8; 40]).unwrap();
+    assert_eq!(
+        Box::<A, _>::try_new([0u8; 64]),
+        Err(TryReserveError::AllocError {
+            layout: Layout::new::<[u8; 64]>()
+        })
+    );
+
+    let mut v: Vec<A, u8> = Vec::try_with_capacity(16).unwrap();
+    assert_eq!(v.capacity(), 16);
+    for i in 0..16 {
+        v.try_push(i).unwrap();
+    }
+    assert_eq!(v.capacity(), 16);
+
+    // no space left to grow the vector
+    assert_eq!(
+        v.try_push(16),
+        Err(TryReserveError::AllocError {
+            layout: Layout::from_size_align(32, 1).unwrap()
+        })
+    );
+    // the contents are preserved
+    assert_eq!(v.len(), 16);
+    assert_eq!(v.capacity(), 16);
+    assert_eq!(v[15], 15);
+
+    assert_eq!(
+        v.try_reserve(usize::max_value()),
+        Err(TryReserveError::CapacityOverflow)
+    );
+    assert_eq!(
+        Vec::<A, u32>::try_with_capacity(usize::max_value() / 2).err(),
+        Some(TryReserveError::CapacityOverflow)
+    );
+    drop(x);
+}
+
+#[test]
+#[should_panic]
+fn oom_box() {
+    #[allocator]
+    static A: BumpAlloc<consts::U16> = BumpAlloc::new();
+
+    let _x: Box<A, _> = Box::new([0u8; 32]);
+}
+
+/// An allocator that frees memory and keeps track of the number of live allocations
+struct Counting {
+    live: AtomicUsize,
+}
+
+impl Counting {
+    const fn new() -> Self {
+        Counting {
+            live: AtomicUsize::new(0),
+        }
+    }
+
+    /// Number of allocations that have not been freed
+    fn live(&self) -> usize {
+        self.live.load(Ordering::Relaxed)
+    }
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            self.live.fetch_add(1, Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        self.live.fetch_sub(1, Ordering::Relaxed);
+        System.dealloc(ptr, layout)
+    }
+}
@@ -0,0 +1,128 @@
+use core::cell::Cell;
+
+use alloc_many::Alloc;
+
+use crate::rc::Rc;
+
+alloc_tests!(counts, weak, try_unwrap, get_mut, make_mut);
+
+/// Increments a counter when dropped
+#[derive(Clone)]
+struct D<'a>(&'a Cell<usize>);
+
+impl Drop for D<'_> {
+    fn drop(&mut self) {
+        self.0.set(self.0.get() + 1);
+    }
+}
+
+fn counts<A>()
+where
+    A: Alloc,
+{
+    let dropped = Cell::new(0);
+
+    let x: Rc<A, _> = Rc::new(D(&dropped));
+    assert_eq!(Rc::strong_count(&x), 1);
+
+    let y = x.clone();
+    assert_eq!(Rc::strong_count(&x), 2);
+    assert!(Rc::ptr_eq(&x, &y));
+
+    drop(x);
+    assert_eq!(Rc::strong_count(&y), 1);
+    assert_eq!(dropped.get(), 0);
+
+    drop(y);
+    assert_eq!(dropped.get(), 1);
+}
+
+fn weak<A>()
+where
+    A: Alloc,
+{
+    let dropped = Cell::new(0);
+
+    let x: Rc<A, _> = Rc::new(D(&dropped));
+    let w = Rc::downgrade(&x);
+    assert_eq!(Rc::weak_count(&x), 1);
+    assert_eq!(w.strong_count(), 1);
+
+    let y = w.upgrade().unwrap();
+    assert_eq!(Rc::strong_count(&x), 2);
+    drop((x, y));
+    assert_eq!(dropped.get(), 1);
+
+    // the allocation outlives the value
+    assert!(w.upgrade().is_none());
+    assert_eq!(w.strong_count(), 0);
+    assert_eq!(w.weak_count(), 0);
+
+    let w2 = w.clone();
+    assert!(w.ptr_eq(&w2));
+}
+
+fn try_unwrap<A>()
+where
+    A: Alloc,
+{
+    let x: Rc<A, i32> = Rc::new(3);
+    assert_eq!(Rc::try_unwrap(x), Ok(3));
+
+    let x: Rc<A, i32> = Rc::new(4);
+    let y = x.clone();
+    let x = Rc::try_unwrap(x).unwrap_err();
+    drop(y);
+
+    let w = Rc::downgrade(&x);
+    assert_eq!(Rc::try_unwrap(x), Ok(4));
+    assert!(w.upgrade().is_none());
+}
+
+fn get_mut<A>()
+where
+    A: Alloc,
+{
+    let mut x: Rc<A, i32> = Rc::new(3);
+    *Rc::get_mut(&mut x).unwrap() = 4;
+    assert_eq!(*x, 4);
+
+    let y = x.clone();
+    assert!(Rc::get_mut(&mut x).is_none());
+    drop(y);
+
+    let w = Rc::downgrade(&x);
+    assert!(Rc::get_mut(&mut x).is_none());
+    drop(w);
+
+    assert!(Rc::get_mut(&mut x).is_some());
+}
+
+fn make_mut<A>()
+where
+    A: Alloc,
+{
+    // unique: no clone
+    let mut x: Rc<A, i32> = Rc::new(1);
+    let p = &*x as *const i32;
+    *Rc::make_mut(&mut x) += 1;
+    assert_eq!(&*x as *const i32, p);
+    assert_eq!(*x, 2);
+
+    // shared: clone on write
+    let y = x.clone();
+    *Rc::make_mut(&mut x) += 1;
+    assert_eq!(*x, 3);
+    assert_eq!(*y, 2);
+    assert!(!Rc::ptr_eq(&x, &y));
+
+    // weak pointers get disassociated
+    let dropped = Cell::new(0);
+    let mut z: Rc<A, _> = Rc::new(D(&dropped));
+    let w = Rc::downgrade(&z);
+    Rc::make_mut(&mut z);
+    assert!(w.upgrade().is_none());
+    assert_eq!(dropped.get(), 0);
+    drop(z);
+    assert_eq!(dropped.get(), 1);
+}
@@ -0,0 +1,95 @@
+use core::alloc::Layout;
+
+use alloc_many::{
+    allocator,
+    combinators::Fallback,
+    stats::{Snapshot, Stats},
+    Alloc,
+};
+use alloc_many_bump::{consts, BumpAlloc};
+
+use super::Counting;
+use crate::{boxed::Box, vec::Vec};
+
+#[test]
+fn counters() {
+    #[allocator]
+    static A: Stats<Counting> = Stats::new(Counting::new());
+
+    assert_eq!(A.snapshot(), Snapshot::default());
+
+    let x: Box<A, u64> = Box::new(0);
+    let mut v: Vec<A, u32> = Vec::with_capacity(4);
+    assert_eq!(
+        A.snapshot(),
+        Snapshot {
+            live: 24,
+            peak: 24,
+            allocations: 2,
+            ..Snapshot::default()
+        }
+    );
+
+    v.extend_from_slice(&[0, 1, 2, 3, 4]);
+    let peak = 8 + 4 * v.capacity();
+    assert_eq!(A.snapshot().live, peak);
+    assert_eq!(A.snapshot().reallocations, 1);
+
+    drop(x);
+    v.truncate(1);
+    v.shrink_to_fit();
+    assert_eq!(
+        A.snapshot(),
+        Snapshot {
+            live: 4,
+            peak,
+            allocations: 2,
+            deallocations: 1,
+            reallocations: 2,
+            failures: 0,
+        }
+    );
+
+    drop(v);
+    assert_eq!(A.snapshot().live, 0);
+    assert_eq!(A.snapshot().peak, peak);
+    assert_eq!(A.snapshot().deallocations, 2);
+    assert_eq!(A.inner().live(), 0);
+}
+
+#[test]
+fn failures() {
+    #[allocator]
+    static A: Stats<BumpAlloc<consts::U16>> = Stats::new(BumpAlloc::new());
+
+    let _x: Box<A, [u8; 12]> = Box::new([0; 12]);
+    assert!(Box::<A, [u8; 8]>::try_new([0; 8]).is_err());
+
+    unsafe {
+        let layout = Layout::new::<u32>();
+        let p = A::alloc_zeroed(layout);
+        assert!(!p.is_null());
+        assert!(A::realloc(p, layout, 8).is_null());
+    }
+
+    let snapshot = A.snapshot();
+    assert_eq!(snapshot.live, 16);
+    assert_eq!(snapshot.allocations, 2);
+    assert_eq!(snapshot.reallocations, 0);
+    assert_eq!(snapshot.failures, 2);
+}
+
+#[test]
+fn owns() {
+    // the statistics of the primary allocator of a `Fallback`
+    #[allocator]
+    static A: Fallback<Stats<BumpAlloc<consts::U32>>, Counting> =
+        Fallback::new(Stats::new(BumpAlloc::new()), Counting::new());
+
+    let xs: Vec<A, Box<A, u64>> = (0..4).map(Box::new).collect();
+    assert_eq!(A.primary().snapshot().allocations + A.secondary().live(), 5);
+
+    drop(xs);
+    assert_eq!(A.primary().snapshot().live, 0);
+    assert_eq!(A.secondary().live(), 0);
+}
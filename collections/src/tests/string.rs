@@ -0,0 +1,112 @@
+use core::fmt::Write;
+
+use alloc_many::Alloc;
+
+use crate::{string::String, vec::Vec};
+
+alloc_tests!(push, insert_truncate, from_utf8, fmt, ops, format_in);
+
+fn push<A>()
+where
+    A: Alloc,
+{
+    let mut s: String<A> = String::new();
+    assert!(s.is_empty());
+
+    s.push_str("hello");
+    s.push(' ');
+    s.push('w');
+    s.push('ö');
+    s.push_str("rld");
+    assert_eq!(s, "hello wörld");
+    assert_eq!(s.len(), 12);
+
+    assert_eq!(s.pop(), Some('d'));
+    assert_eq!(s.pop(), Some('l'));
+    assert_eq!(s.pop(), Some('r'));
+    assert_eq!(s.pop(), Some('ö'));
+    assert_eq!(s, "hello w");
+
+    s.try_push_str("!").unwrap();
+    assert_eq!(s, "hello w!");
+
+    s.clear();
+    assert_eq!(s.pop(), None);
+}
+
+fn insert_truncate<A>()
+where
+    A: Alloc,
+{
+    let mut s: String<A> = String::from("hllo");
+    s.insert(1, 'e');
+    s.insert(0, '¡');
+    s.insert_str(s.len(), " world");
+    assert_eq!(s, "¡hello world");
+
+    s.truncate(100);
+    assert_eq!(s, "¡hello world");
+    s.truncate(7);
+    assert_eq!(s, "¡hello");
+}
+
+fn from_utf8<A>()
+where
+    A: Alloc,
+{
+    let mut bytes: Vec<A, u8> = Vec::new();
+    bytes.extend_from_slice("añb".as_bytes());
+    let s = String::from_utf8(bytes).unwrap();
+    assert_eq!(s, "añb");
+
+    let mut bytes: Vec<A, u8> = Vec::new();
+    bytes.extend_from_slice(&[b'a', 0xff, b'b']);
+    let e = String::from_utf8(bytes).unwrap_err();
+    assert_eq!(e.utf8_error().valid_up_to(), 1);
+    assert_eq!(e.as_bytes(), [b'a', 0xff, b'b']);
+    assert_eq!(*e.into_bytes(), [b'a', 0xff, b'b']);
+}
+
+fn fmt<A>()
+where
+    A: Alloc,
+{
+    let mut s: String<A> = String::new();
+    write!(s, "{} + {} = {}", 1, 2, 3).unwrap();
+    assert_eq!(s, "1 + 2 = 3");
+
+    let mut t: String<A> = String::new();
+    write!(t, "{}|{:?}", s, s).unwrap();
+    assert_eq!(t, "1 + 2 = 3|\"1 + 2 = 3\"");
+}
+
+fn ops<A>()
+where
+    A: Alloc,
+{
+    let s: String<A> = String::from("foo");
+    let mut s = s + "bar";
+    s += "baz";
+    assert_eq!(s, "foobarbaz");
+    assert_eq!("foobarbaz", s);
+    assert_eq!(*"foobarbaz", s);
+
+    let t = s.clone();
+    assert_eq!(s, t);
+    let a: String<A> = String::from("a");
+    let b: String<A> = String::from("b");
+    assert!(a < b);
+
+    let u: String<A> = "abc".chars().rev().collect();
+    assert_eq!(u, "cba");
+
+    assert_eq!(&s[..3], "foo");
+}
+
+fn format_in<A>()
+where
+    A: Alloc,
+{
+    let s = crate::format_in!(A, "x = {}, y = {:?}", 42, "hi");
+    assert_eq!(s, "x = 42, y = \"hi\"");
+}
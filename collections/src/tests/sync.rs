@@ -0,0 +1,199 @@
+use core::sync::atomic::{AtomicUsize, Ordering};
+use std::{
+    panic::{self, AssertUnwindSafe},
+    sync::Barrier,
+    thread,
+    vec::Vec as StdVec,
+};
+
+use alloc_many::{allocator, Alloc};
+use alloc_many_bump::{consts, BumpAlloc};
+
+use crate::{boxed::Box, sync::Arc};
+
+alloc_tests!(counts, weak, try_unwrap, get_mut, make_mut);
+
+const N: usize = 8;
+
+/// Increments a counter when dropped
+#[derive(Clone)]
+struct D<'a>(&'a AtomicUsize);
+
+impl Drop for D<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+fn counts<A>()
+where
+    A: Alloc,
+{
+    let dropped = AtomicUsize::new(0);
+    let x: Arc<A, _> = Arc::new(D(&dropped));
+    let barrier = Barrier::new(N);
+
+    thread::scope(|s| {
+        for _ in 0..N {
+            // every thread gets its own strong pointer
+            let y = x.clone();
+            let barrier = &barrier;
+            s.spawn(move || {
+                let clones: StdVec<_> = (0..100).map(|_| y.clone()).collect();
+                barrier.wait();
+                assert!(Arc::strong_count(&y) > 100);
+                drop(clones);
+            });
+        }
+    });
+
+    assert_eq!(Arc::strong_count(&x), 1);
+    assert_eq!(dropped.load(Ordering::Relaxed), 0);
+
+    // the last strong pointer is dropped on another thread
+    thread::scope(|s| {
+        s.spawn(move || drop(x));
+    });
+    assert_eq!(dropped.load(Ordering::Relaxed), 1);
+}
+
+fn weak<A>()
+where
+    A: Alloc,
+{
+    let dropped = AtomicUsize::new(0);
+    let x: Arc<A, _> = Arc::new(D(&dropped));
+    let barrier = Barrier::new(N + 1);
+
+    thread::scope(|s| {
+        for _ in 0..N {
+            let w = Arc::downgrade(&x);
+            let (barrier, dropped) = (&barrier, &dropped);
+            s.spawn(move || {
+                barrier.wait();
+                // races with the drop of the last strong pointer
+                while let Some(y) = w.upgrade() {
+                    assert_eq!(dropped.load(Ordering::Relaxed), 0);
+                    drop(y);
+                    thread::yield_now();
+                }
+                assert_eq!(w.strong_count(), 0);
+            });
+        }
+
+        barrier.wait();
+        drop(x);
+    });
+
+    assert_eq!(dropped.load(Ordering::Relaxed), 1);
+}
+
+fn try_unwrap<A>()
+where
+    A: Alloc,
+{
+    let x: Arc<A, i32> = Arc::new(3);
+    let w = Arc::downgrade(&x);
+
+    // the other strong pointers are dropped on other threads
+    thread::scope(|s| {
+        for _ in 0..N {
+            let y = x.clone();
+            s.spawn(move || assert_eq!(*y, 3));
+        }
+    });
+    assert_eq!(Arc::try_unwrap(x), Ok(3));
+
+    thread::scope(|s| {
+        s.spawn(move || assert!(w.upgrade().is_none()));
+    });
+}
+
+fn get_mut<A>()
+where
+    A: Alloc,
+{
+    let mut x: Arc<A, i32> = Arc::new(3);
+
+    thread::scope(|s| {
+        let y = x.clone();
+        let w = Arc::downgrade(&x);
+        s.spawn(move || {
+            assert_eq!(*y, 3);
+            assert_eq!(*w.upgrade().unwrap(), 3);
+        });
+    });
+
+    // the other thread dropped its strong and weak pointers
+    *Arc::get_mut(&mut x).unwrap() = 4;
+    assert_eq!(*x, 4);
+
+    thread::scope(|s| {
+        let w = Arc::downgrade(&x);
+        s.spawn(move || {
+            let mut y = w.upgrade().unwrap();
+            assert!(Arc::get_mut(&mut y).is_none());
+        });
+    });
+
+    assert!(Arc::get_mut(&mut x).is_some());
+}
+
+fn make_mut<A>()
+where
+    A: Alloc,
+{
+    // every thread clones on write; the shared value is left untouched
+    let x: Arc<A, usize> = Arc::new(0);
+    thread::scope(|s| {
+        for i in 0..N {
+            let mut y = x.clone();
+            s.spawn(move || {
+                *Arc::make_mut(&mut y) += i + 1;
+                assert_eq!(*y, i + 1);
+            });
+        }
+    });
+    assert_eq!(*x, 0);
+    assert_eq!(Arc::strong_count(&x), 1);
+
+    // weak pointers on other threads get disassociated
+    let dropped = AtomicUsize::new(0);
+    let mut z: Arc<A, _> = Arc::new(D(&dropped));
+    let barrier = Barrier::new(2);
+    thread::scope(|s| {
+        let w = Arc::downgrade(&z);
+        let barrier = &barrier;
+        s.spawn(move || {
+            barrier.wait();
+            assert!(w.upgrade().is_none());
+        });
+
+        Arc::make_mut(&mut z);
+        barrier.wait();
+    });
+    assert_eq!(dropped.load(Ordering::Relaxed), 0);
+    drop(z);
+    assert_eq!(dropped.load(Ordering::Relaxed), 1);
+}
+
+#[test]
+fn make_mut_oom() {
+    #[allocator]
+    static A: BumpAlloc<consts::U64> = BumpAlloc::new();
+
+    let mut x: Arc<A, [u8; 16]> = Arc::new([0; 16]);
+    let w = Arc::downgrade(&x);
+    let _full: Box<A, [u8; 32]> = Box::new([0; 32]);
+
+    // the OOM handler unwinds out of `make_mut`; the counts must be left untouched
+    assert!(panic::catch_unwind(AssertUnwindSafe(|| {
+        Arc::make_mut(&mut x);
+    }))
+    .is_err());
+    assert_eq!(Arc::strong_count(&x), 1);
+    assert_eq!(Arc::weak_count(&x), 1);
+
+    drop(x);
+    assert!(w.upgrade().is_none());
+}
@@ -0,0 +1,535 @@
+use core::{
+    alloc::Layout,
+    cell::Cell,
+    hash::{Hash, Hasher},
+    sync::atomic::{AtomicU8, Ordering},
+};
+use std::{collections::hash_map::DefaultHasher, format};
+
+use alloc_many::{allocator, Alloc};
+use alloc_many_bump::{consts, BumpAlloc};
+
+use super::Counting;
+use crate::{vec::Vec, TryReserveError};
+
+alloc_tests!(
+    with_capacity,
+    insert_remove,
+    swap_remove,
+    truncate_clear,
+    extend_from_slice,
+    retain,
+    dedup_by_key,
+    split_off_append,
+    resize,
+    drain,
+    shrink_to_fit,
+    raw_parts,
+    leak,
+    into_iter,
+    from_iter_extend,
+    traits,
+);
+
+/// Increments a counter when dropped
+#[derive(Debug)]
+struct D<'a>(&'a Cell<usize>);
+
+impl Drop for D<'_> {
+    fn drop(&mut self) {
+        self.0.set(self.0.get() + 1);
+    }
+}
+
+fn from_slice<A>(xs: &[i32]) -> Vec<A, i32>
+where
+    A: Alloc,
+{
+    let mut v = Vec::new();
+    v.extend_from_slice(xs);
+    v
+}
+
+fn with_capacity<A>()
+where
+    A: Alloc,
+{
+    let v: Vec<A, u32> = Vec::with_capacity(10);
+    assert_eq!(v.capacity(), 10);
+    assert_eq!(v.len(), 0);
+    assert!(v.is_empty());
+
+    let v: Vec<A, u32> = Vec::with_capacity(0);
+    assert_eq!(v.capacity(), 0);
+
+    let v: Vec<A, ()> = Vec::with_capacity(10);
+    assert_eq!(v.capacity(), usize::max_value());
+}
+
+fn insert_remove<A>()
+where
+    A: Alloc,
+{
+    let mut v: Vec<A, i32> = from_slice(&[1, 3]);
+    v.insert(1, 2);
+    v.insert(0, 0);
+    v.insert(4, 4);
+    assert_eq!(*v, [0, 1, 2, 3, 4]);
+    assert_eq!(v.len(), 5);
+    assert!(!v.is_empty());
+
+    assert_eq!(v.remove(0), 0);
+    assert_eq!(v.remove(3), 4);
+    assert_eq!(v.remove(1), 2);
+    assert_eq!(*v, [1, 3]);
+}
+
+fn swap_remove<A>()
+where
+    A: Alloc,
+{
+    let mut v: Vec<A, i32> = from_slice(&[0, 1, 2, 3]);
+    assert_eq!(v.swap_remove(1), 1);
+    assert_eq!(*v, [0, 3, 2]);
+    assert_eq!(v.swap_remove(2), 2);
+    assert_eq!(*v, [0, 3]);
+}
+
+fn truncate_clear<A>()
+where
+    A: Alloc,
+{
+    let dropped = Cell::new(0);
+    let mut v: Vec<A, _> = Vec::new();
+    for _ in 0..5 {
+        v.push(D(&dropped));
+    }
+
+    v.truncate(10);
+    assert_eq!(dropped.get(), 0);
+
+    v.truncate(3);
+    assert_eq!(v.len(), 3);
+    assert_eq!(dropped.get(), 2);
+
+    v.clear();
+    assert!(v.is_empty());
+    assert_eq!(dropped.get(), 5);
+}
+
+fn extend_from_slice<A>()
+where
+    A: Alloc,
+{
+    let mut v: Vec<A, i32> = Vec::new();
+    v.extend_from_slice(&[]);
+    assert!(v.is_empty());
+
+    v.extend_from_slice(&[0, 1]);
+    v.extend_from_slice(&[2, 3, 4]);
+    assert_eq!(*v, [0, 1, 2, 3, 4]);
+}
+
+fn retain<A>()
+where
+    A: Alloc,
+{
+    let mut v: Vec<A, i32> = from_slice(&[0, 1, 2, 3, 4, 5]);
+    v.retain(|x| x % 2 == 0);
+    assert_eq!(*v, [0, 2, 4]);
+
+    let dropped = Cell::new(0);
+    let mut v: Vec<A, _> = Vec::new();
+    for i in 0..4 {
+        v.push((i, D(&dropped)));
+    }
+    v.retain(|x| x.0 >= 2);
+    assert_eq!(dropped.get(), 2);
+    assert_eq!(v[0].0, 2);
+    assert_eq!(v[1].0, 3);
+}
+
+fn dedup_by_key<A>()
+where
+    A: Alloc,
+{
+    let mut v: Vec<A, i32> = from_slice(&[10, 20, 21, 30, 20]);
+    v.dedup_by_key(|x| *x / 10);
+    assert_eq!(*v, [10, 20, 30, 20]);
+
+    let mut v: Vec<A, i32> = from_slice(&[1, 1, 1, 2, 2, 3, 1]);
+    v.dedup();
+    assert_eq!(*v, [1, 2, 3, 1]);
+}
+
+fn split_off_append<A>()
+where
+    A: Alloc,
+{
+    let mut v: Vec<A, i32> = from_slice(&[0, 1, 2, 3]);
+    let mut w = v.split_off(1);
+    assert_eq!(*v, [0]);
+    assert_eq!(*w, [1, 2, 3]);
+
+    let empty = w.split_off(3);
+    assert!(empty.is_empty());
+
+    v.append(&mut w);
+    assert_eq!(*v, [0, 1, 2, 3]);
+    assert!(w.is_empty());
+}
+
+fn resize<A>()
+where
+    A: Alloc,
+{
+    let mut v: Vec<A, i32> = from_slice(&[0]);
+    v.resize(3, 7);
+    assert_eq!(*v, [0, 7, 7]);
+    v.resize(1, 7);
+    assert_eq!(*v, [0]);
+
+    let mut i = 0;
+    v.resize_with(4, || {
+        i += 1;
+        i
+    });
+    assert_eq!(*v, [0, 1, 2, 3]);
+    v.resize_with(2, || unreachable!());
+    assert_eq!(*v, [0, 1]);
+}
+
+fn drain<A>()
+where
+    A: Alloc,
+{
+    let mut v: Vec<A, i32> = from_slice(&[0, 1, 2, 3, 4, 5]);
+    {
+        let mut d = v.drain(1..4);
+        assert_eq!(d.len(), 3);
+        assert_eq!(d.next(), Some(1));
+        assert_eq!(d.next_back(), Some(3));
+    }
+    assert_eq!(*v, [0, 4, 5]);
+
+    assert_eq!(v.drain(..).sum::<i32>(), 9);
+    assert!(v.is_empty());
+
+    let dropped = Cell::new(0);
+    let mut v: Vec<A, _> = Vec::new();
+    for _ in 0..4 {
+        v.push(D(&dropped));
+    }
+    drop(v.drain(1..=2));
+    assert_eq!(dropped.get(), 2);
+    assert_eq!(v.len(), 2);
+}
+
+fn shrink_to_fit<A>()
+where
+    A: Alloc,
+{
+    let mut v: Vec<A, i32> = Vec::with_capacity(10);
+    v.extend_from_slice(&[0, 1, 2]);
+    v.shrink_to_fit();
+    assert_eq!(v.capacity(), 3);
+    assert_eq!(*v, [0, 1, 2]);
+
+    v.clear();
+    v.shrink_to_fit();
+    assert_eq!(v.capacity(), 0);
+
+    v.push(3);
+    assert_eq!(*v, [3]);
+}
+
+fn raw_parts<A>()
+where
+    A: Alloc,
+{
+    let mut v: Vec<A, i32> = Vec::with_capacity(4);
+    v.extend_from_slice(&[0, 1]);
+    let p = v.as_ptr();
+
+    let (ptr, len, cap) = v.into_raw_parts();
+    assert_eq!(ptr as *const i32, p);
+    assert_eq!((len, cap), (2, 4));
+
+    let v: Vec<A, i32> = unsafe { Vec::from_raw_parts(ptr, len, cap) };
+    assert_eq!(*v, [0, 1]);
+    assert_eq!(v.capacity(), 4);
+}
+
+fn leak<A>()
+where
+    A: Alloc,
+{
+    let v: Vec<A, i32> = from_slice(&[0, 1, 2]);
+    let xs: &'static mut [i32] = v.leak();
+    xs[0] = 3;
+    assert_eq!(xs, [3, 1, 2]);
+
+    // give the memory back so the leak checker is happy
+    unsafe { drop(Vec::<A, i32>::from_raw_parts(xs.as_mut_ptr(), 3, 3)) }
+}
+
+fn into_iter<A>()
+where
+    A: Alloc,
+{
+    let v: Vec<A, i32> = from_slice(&[0, 1, 2, 3]);
+    let mut it = v.into_iter();
+    assert_eq!(it.len(), 4);
+    assert_eq!(it.next(), Some(0));
+    assert_eq!(it.next_back(), Some(3));
+    assert_eq!(it.as_slice(), [1, 2]);
+    assert_eq!(it.next(), Some(1));
+    assert_eq!(it.next(), Some(2));
+    assert_eq!(it.next(), None);
+    assert_eq!(it.next_back(), None);
+
+    // partially consumed iterators drop the remaining elements
+    let dropped = Cell::new(0);
+    let mut v: Vec<A, _> = Vec::new();
+    for _ in 0..4 {
+        v.push(D(&dropped));
+    }
+    let mut it = v.into_iter();
+    drop(it.next());
+    assert_eq!(dropped.get(), 1);
+    drop(it);
+    assert_eq!(dropped.get(), 4);
+
+    let mut v: Vec<A, i32> = from_slice(&[0, 1, 2]);
+    for x in &mut v {
+        *x *= 2;
+    }
+    assert_eq!((&v).into_iter().sum::<i32>(), 6);
+}
+
+fn from_iter_extend<A>()
+where
+    A: Alloc,
+{
+    let mut v: Vec<A, i32> = (0..4).collect();
+    assert_eq!(*v, [0, 1, 2, 3]);
+
+    v.extend(4..6);
+    v.extend(&[6, 7]);
+    assert_eq!(*v, [0, 1, 2, 3, 4, 5, 6, 7]);
+
+    let w: Vec<A, i32> = v.into_iter().filter(|x| x % 2 == 1).collect();
+    assert_eq!(*w, [1, 3, 5, 7]);
+}
+
+fn traits<A>()
+where
+    A: Alloc,
+{
+    let v: Vec<A, i32> = from_slice(&[0, 1, 2]);
+
+    let w = v.clone();
+    assert_eq!(v, w);
+    assert_ne!(v.as_ptr(), w.as_ptr());
+
+    assert_eq!(format!("{:?}", v), "[0, 1, 2]");
+    assert!(Vec::<A, i32>::default().is_empty());
+
+    let mut h1 = DefaultHasher::new();
+    v.hash(&mut h1);
+    let mut h2 = DefaultHasher::new();
+    [0, 1, 2][..].hash(&mut h2);
+    assert_eq!(h1.finish(), h2.finish());
+
+    let u: Vec<A, i32> = from_slice(&[0, 2]);
+    assert!(v < u);
+    assert_eq!(v.cmp(&u), core::cmp::Ordering::Less);
+
+    let s: &[i32] = v.as_ref();
+    assert_eq!(s, [0, 1, 2]);
+}
+
+#[test]
+fn cross_alloc_cmp() {
+    #[allocator]
+    static A: BumpAlloc<consts::U128> = BumpAlloc::new();
+
+    #[allocator]
+    static B: Counting = Counting::new();
+
+    let a: Vec<A, i32> = (0..3).collect();
+    let b: Vec<B, i32> = (0..3).collect();
+    assert_eq!(a, b);
+
+    let c: Vec<B, i32> = (1..3).collect();
+    assert_ne!(a, c);
+    assert!(a < c);
+}
+
+#[test]
+fn drop_elements() {
+    #[allocator]
+    static A: BumpAlloc<consts::U128> = BumpAlloc::new();
+
+    static X: AtomicU8 = AtomicU8::new(0);
+    struct Z;
+    impl Drop for Z {
+        fn drop(&mut self) {
+            X.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    let mut v: Vec<A, _> = Vec::new();
+    v.push(Z);
+    v.push(Z);
+    v.push(Z);
+
+    drop(v.pop());
+    assert_eq!(X.load(Ordering::Relaxed), 1);
+
+    drop(v);
+    assert_eq!(X.load(Ordering::Relaxed), 3);
+
+    // zero sized `Vec`s don't own a buffer
+    let v: Vec<A, ()> = Vec::new();
+    drop(v);
+
+    let mut v: Vec<A, ()> = Vec::new();
+    v.push(());
+    drop(v);
+}
+
+#[test]
+fn dealloc() {
+    #[allocator]
+    static A: Counting = Counting::new();
+
+    let v: Vec<A, u8> = Vec::new();
+    drop(v);
+    assert_eq!(A.live(), 0);
+
+    let mut v: Vec<A, _> = Vec::new();
+    for i in 0..10 {
+        v.push(i);
+    }
+    assert_eq!(A.live(), 1);
+
+    drop(v);
+    assert_eq!(A.live(), 0);
+}
+
+#[test]
+fn move_between_allocs() {
+    #[allocator]
+    static A: BumpAlloc<consts::U128> = BumpAlloc::new();
+
+    #[allocator]
+    static B: Counting = Counting::new();
+
+    // build in the arena, promote to the heap
+    let mut a: Vec<A, u32> = Vec::with_capacity(8);
+    a.extend_from_slice(&[1, 2, 3]);
+    let b: Vec<B, u32> = a.clone_in();
+    assert_eq!(a, b);
+    assert_eq!(b.capacity(), 3);
+    assert_eq!(B.live(), 1);
+
+    let c: Vec<B, u32> = a.into_alloc();
+    assert_eq!(*c, [1, 2, 3]);
+    assert_eq!(c.capacity(), 3);
+    assert_eq!(B.live(), 2);
+
+    // moving into the same allocator reuses the buffer
+    let ptr = c.as_ptr();
+    let d: Vec<B, u32> = c.into_alloc();
+    assert_eq!(d.as_ptr(), ptr);
+    assert_eq!(B.live(), 2);
+
+    // elements are moved, not dropped
+    let dropped = Cell::new(0);
+    let mut e: Vec<B, D<'_>> = Vec::new();
+    e.push(D(&dropped));
+    e.push(D(&dropped));
+    let f: Vec<A, D<'_>> = e.try_into_alloc().unwrap();
+    assert_eq!(dropped.get(), 0);
+    assert_eq!(B.live(), 2);
+    drop(f);
+    assert_eq!(dropped.get(), 2);
+
+    let g: Vec<B, ()> = Vec::<A, ()>::new().into_alloc();
+    assert!(g.is_empty());
+
+    drop((b, d, g));
+    assert_eq!(B.live(), 0);
+}
+
+#[test]
+fn move_oom() {
+    #[allocator]
+    static A: BumpAlloc<consts::U64> = BumpAlloc::new();
+
+    #[allocator]
+    static B: Counting = Counting::new();
+
+    let big: Vec<B, u8> = (0..100).collect();
+    assert_eq!(
+        big.try_clone_in::<A>(),
+        Err(TryReserveError::AllocError {
+            layout: Layout::new::<[u8; 100]>()
+        })
+    );
+
+    // on error the vector is handed back untouched
+    let ptr = big.as_ptr();
+    let (big, e) = big.try_into_alloc::<A>().unwrap_err();
+    assert_eq!(
+        e,
+        TryReserveError::AllocError {
+            layout: Layout::new::<[u8; 100]>()
+        }
+    );
+    assert_eq!(big.as_ptr(), ptr);
+    assert!(big.iter().copied().eq(0..100));
+    assert_eq!(B.live(), 1);
+    drop(big);
+    assert_eq!(B.live(), 0);
+
+    let dropped = Cell::new(0);
+    let mut v: Vec<B, D<'_>> = Vec::new();
+    for _ in 0..100 {
+        v.push(D(&dropped));
+    }
+    let (v, _) = v.try_into_alloc::<A>().unwrap_err();
+    assert_eq!(dropped.get(), 0);
+    assert_eq!(v.len(), 100);
+    drop(v);
+    assert_eq!(dropped.get(), 100);
+}
+
+#[test]
+#[should_panic(expected = "attempted to drain up to maximum usize")]
+fn drain_overflow() {
+    #[allocator]
+    static A: BumpAlloc<consts::U64> = BumpAlloc::new();
+
+    let mut v: Vec<A, i32> = from_slice(&[0, 1, 2]);
+    v.drain(..=usize::max_value());
+}
+
+#[test]
+fn drain_send_sync() {
+    fn assert_send_sync<T>(_: &T)
+    where
+        T: Send + Sync,
+    {
+    }
+
+    #[allocator]
+    static A: BumpAlloc<consts::U64> = BumpAlloc::new();
+
+    let mut v: Vec<A, i32> = from_slice(&[0, 1, 2]);
+    let d = v.drain(1..);
+    assert_send_sync(&d);
+    assert!(d.eq([1, 2].iter().copied()));
+    assert_eq!(*v, [0]);
+}
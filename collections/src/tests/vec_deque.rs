@@ -0,0 +1,287 @@
+use core::{cell::Cell, ops::Bound};
+use std::format;
+
+use alloc_many::{allocator, Alloc};
+use alloc_many_bump::{consts, BumpAlloc};
+
+use crate::{vec::Vec, vec_deque::VecDeque};
+
+alloc_tests!(
+    push_pop,
+    grow_wrapped,
+    index,
+    as_slices,
+    iter,
+    drain,
+    rotate,
+    traits,
+    drop_elements
+);
+
+/// Increments a counter when dropped
+struct D<'a>(&'a Cell<usize>);
+
+impl Drop for D<'_> {
+    fn drop(&mut self) {
+        self.0.set(self.0.get() + 1);
+    }
+}
+
+fn collect<A>(deque: &VecDeque<A, i32>) -> Vec<A, i32>
+where
+    A: Alloc,
+{
+    deque.iter().cloned().collect()
+}
+
+fn push_pop<A>()
+where
+    A: Alloc,
+{
+    let mut d: VecDeque<A, i32> = VecDeque::new();
+    assert!(d.is_empty());
+    assert_eq!(d.pop_front(), None);
+    assert_eq!(d.pop_back(), None);
+
+    d.push_back(1);
+    d.push_back(2);
+    d.push_front(0);
+    d.push_front(-1);
+    assert_eq!(d.len(), 4);
+    assert_eq!(d.front(), Some(&-1));
+    assert_eq!(d.back(), Some(&2));
+
+    assert_eq!(d.pop_front(), Some(-1));
+    assert_eq!(d.pop_back(), Some(2));
+    assert_eq!(d.pop_back(), Some(1));
+    assert_eq!(d.pop_front(), Some(0));
+    assert_eq!(d.pop_front(), None);
+
+    let mut z: VecDeque<A, ()> = VecDeque::new();
+    z.push_front(());
+    z.push_back(());
+    assert_eq!(z.len(), 2);
+    assert_eq!(z.pop_front(), Some(()));
+}
+
+fn grow_wrapped<A>()
+where
+    A: Alloc,
+{
+    // short tail
+    let mut d: VecDeque<A, i32> = VecDeque::with_capacity(4);
+    d.push_back(2);
+    d.push_front(1);
+    d.push_front(0);
+    d.push_back(3);
+    assert_eq!(d.capacity(), 4);
+    d.push_back(4);
+    assert_eq!(*collect(&d), [0, 1, 2, 3, 4]);
+
+    // short head segment
+    let mut d: VecDeque<A, i32> = VecDeque::with_capacity(4);
+    d.push_back(1);
+    d.push_back(2);
+    d.push_back(3);
+    d.push_front(0);
+    d.push_front(-1);
+    assert_eq!(*collect(&d), [-1, 0, 1, 2, 3]);
+
+    let mut d: VecDeque<A, i32> = VecDeque::new();
+    for i in 0..32 {
+        if i % 3 == 0 {
+            d.push_front(i);
+        } else {
+            d.push_back(i);
+        }
+    }
+    assert_eq!(d.len(), 32);
+    let mut fronts: Vec<A, i32> = (0..32).filter(|i| i % 3 == 0).rev().collect();
+    fronts.extend((0..32).filter(|i| i % 3 != 0));
+    assert_eq!(collect(&d), fronts);
+}
+
+fn index<A>()
+where
+    A: Alloc,
+{
+    let mut d: VecDeque<A, i32> = VecDeque::new();
+    d.push_back(1);
+    d.push_front(0);
+    assert_eq!(d[0], 0);
+    assert_eq!(d[1], 1);
+    d[1] = 2;
+    *d.back_mut().unwrap() += 1;
+    *d.front_mut().unwrap() -= 1;
+    assert_eq!(d.get(1), Some(&3));
+    assert_eq!(d.get(0), Some(&-1));
+    assert_eq!(d.get(2), None);
+
+    d.swap(0, 1);
+    assert_eq!(*collect(&d), [3, -1]);
+}
+
+fn as_slices<A>()
+where
+    A: Alloc,
+{
+    let mut d: VecDeque<A, i32> = VecDeque::with_capacity(4);
+    d.push_back(2);
+    d.push_back(3);
+    d.push_front(1);
+    d.push_front(0);
+
+    let (a, b) = d.as_slices();
+    assert_eq!((a, b), (&[0, 1][..], &[2, 3][..]));
+
+    assert_eq!(d.make_contiguous(), [0, 1, 2, 3]);
+    let (a, b) = d.as_mut_slices();
+    assert_eq!(a, [0, 1, 2, 3]);
+    assert!(b.is_empty());
+
+    // wrapped with free space in the middle
+    let mut d: VecDeque<A, i32> = VecDeque::with_capacity(8);
+    d.extend(&[3, 4, 5]);
+    for i in (0..3).rev() {
+        d.push_front(i);
+    }
+    assert_eq!(d.make_contiguous(), [0, 1, 2, 3, 4, 5]);
+    assert_eq!(d.as_slices().1.len(), 0);
+}
+
+fn iter<A>()
+where
+    A: Alloc,
+{
+    let mut d: VecDeque<A, i32> = VecDeque::with_capacity(4);
+    d.push_back(2);
+    d.push_back(3);
+    d.push_front(1);
+    d.push_front(0);
+
+    assert_eq!(d.iter().len(), 4);
+    assert_eq!(
+        *d.iter().rev().cloned().collect::<Vec<A, _>>(),
+        [3, 2, 1, 0]
+    );
+
+    for x in &mut d {
+        *x *= 10;
+    }
+    assert_eq!(*collect(&d), [0, 10, 20, 30]);
+
+    let mut it = d.into_iter();
+    assert_eq!(it.next(), Some(0));
+    assert_eq!(it.next_back(), Some(30));
+    assert_eq!(it.len(), 2);
+}
+
+fn drain<A>()
+where
+    A: Alloc,
+{
+    for &(start, end) in &[(0, 0), (0, 8), (0, 3), (5, 8), (1, 4), (4, 7), (2, 6)] {
+        let (start, end): (i32, i32) = (start, end);
+        let mut d: VecDeque<A, i32> = VecDeque::with_capacity(8);
+        d.extend(&[4, 5, 6, 7]);
+        for i in (0..4).rev() {
+            d.push_front(i);
+        }
+
+        let drained: Vec<A, i32> = d.drain(start as usize..end as usize).collect();
+        assert_eq!(*drained, *(start..end).collect::<Vec<A, i32>>());
+
+        let rest: Vec<A, i32> = (0..start).chain(end..8).collect();
+        assert_eq!(collect(&d), rest);
+        assert_eq!(d.len(), (8 - (end - start)) as usize);
+    }
+
+    let dropped = Cell::new(0);
+    let mut d: VecDeque<A, _> = VecDeque::new();
+    for _ in 0..4 {
+        d.push_back(D(&dropped));
+    }
+    drop(d.drain(1..3).next_back());
+    assert_eq!(dropped.get(), 2);
+    assert_eq!(d.len(), 2);
+}
+
+fn rotate<A>()
+where
+    A: Alloc,
+{
+    let mut d: VecDeque<A, i32> = (0..5).collect();
+    d.rotate_left(2);
+    assert_eq!(*collect(&d), [2, 3, 4, 0, 1]);
+    d.rotate_right(2);
+    assert_eq!(*collect(&d), [0, 1, 2, 3, 4]);
+    d.rotate_left(4);
+    assert_eq!(*collect(&d), [4, 0, 1, 2, 3]);
+    d.rotate_left(0);
+    d.rotate_right(5);
+    assert_eq!(*collect(&d), [4, 0, 1, 2, 3]);
+}
+
+fn traits<A>()
+where
+    A: Alloc,
+{
+    let d: VecDeque<A, i32> = (0..3).collect();
+    let mut e = d.clone();
+    assert_eq!(d, e);
+    assert_eq!(format!("{:?}", d), "[0, 1, 2]");
+
+    e.push_front(-1);
+    assert!(e < d);
+    assert_ne!(d, e);
+
+    e.truncate(1);
+    assert_eq!(*collect(&e), [-1]);
+    e.clear();
+    assert!(e.is_empty());
+}
+
+fn drop_elements<A>()
+where
+    A: Alloc,
+{
+    let dropped = Cell::new(0);
+    let mut d: VecDeque<A, _> = VecDeque::with_capacity(4);
+    d.push_back(D(&dropped));
+    d.push_back(D(&dropped));
+    d.push_front(D(&dropped));
+    drop(d.pop_back());
+    assert_eq!(dropped.get(), 1);
+    drop(d);
+    assert_eq!(dropped.get(), 3);
+}
+
+#[test]
+#[should_panic(expected = "attempted to drain from after maximum usize")]
+fn drain_overflow() {
+    #[allocator]
+    static A: BumpAlloc<consts::U64> = BumpAlloc::new();
+
+    let mut d: VecDeque<A, i32> = VecDeque::new();
+    d.push_back(0);
+    d.drain((Bound::Excluded(usize::max_value()), Bound::Unbounded));
+}
+
+#[test]
+fn drain_send_sync() {
+    fn assert_send_sync<T>(_: &T)
+    where
+        T: Send + Sync,
+    {
+    }
+
+    #[allocator]
+    static A: BumpAlloc<consts::U64> = BumpAlloc::new();
+
+    let mut d: VecDeque<A, i32> = VecDeque::new();
+    d.extend(&[0, 1, 2]);
+    let drain = d.drain(1..);
+    assert_send_sync(&drain);
+    assert!(drain.eq([1, 2].iter().copied()));
+    assert_eq!(d.len(), 1);
+}
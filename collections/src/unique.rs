@@ -1,4 +1,6 @@
 use core::{marker::PhantomData, mem, ptr::NonNull};
+#[cfg(feature = "nightly")]
+use core::{marker::Unsize, ops::CoerceUnsized};
 
 #[allow(explicit_outlives_requirements)] // false positive?
 pub struct Unique<T>
@@ -50,3 +52,11 @@ where
 unsafe impl<T> Send for Unique<T> where T: Send + ?Sized {}
 
 unsafe impl<T> Sync for Unique<T> where T: Sync + ?Sized {}
+
+#[cfg(feature = "nightly")]
+impl<T, U> CoerceUnsized<Unique<U>> for Unique<T>
+where
+    T: ?Sized + Unsize<U>,
+    U: ?Sized,
+{
+}
@@ -1,10 +1,21 @@
 //! A contiguous growable array type with heap-allocated contents, written `Vec<T>`.
 
-use core::{alloc::Layout, cmp, marker::PhantomData, mem, ops, slice};
+use core::{
+    alloc::Layout,
+    borrow::{Borrow, BorrowMut},
+    cmp, fmt,
+    hash::{Hash, Hasher},
+    iter::FromIterator,
+    marker::PhantomData,
+    mem,
+    ops::{self, Bound, RangeBounds},
+    ptr::{self, NonNull},
+    slice,
+};
 
 use alloc_many::Alloc;
 
-use crate::unique::Unique;
+use crate::{boxed::Box, unique::Unique, TryReserveError};
 
 /// A contiguous growable array type, written `Vec<T>` but pronounced 'vector'.
 pub struct Vec<A, T>
@@ -37,21 +48,106 @@ where
         }
     }
 
+    /// Constructs a new, empty `Vec<T>` with the specified capacity.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::try_with_capacity(capacity).unwrap_or_else(|e| crate::handle_reserve_error::<A>(e))
+    }
+
+    /// Constructs a new, empty `Vec<T>` with the specified capacity, returning an error if the
+    /// allocation fails
+    pub fn try_with_capacity(capacity: usize) -> Result<Self, TryReserveError> {
+        let mut v = Self::new();
+        if capacity > v.cap {
+            v.try_grow(capacity)?;
+        }
+        Ok(v)
+    }
+
+    /// Creates a `Vec<T>` directly from the raw components of another vector.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must have been allocated on the allocator `A` with the layout of a `[T; capacity]`
+    /// array (e.g. it comes from `Vec::<A, T>::into_raw_parts`), `length` must be less than or
+    /// equal to `capacity` and the first `length` elements must be initialized.
+    pub unsafe fn from_raw_parts(ptr: *mut T, length: usize, capacity: usize) -> Self {
+        let cap = if mem::size_of::<T>() == 0 {
+            usize::max_value()
+        } else {
+            capacity
+        };
+
+        Self {
+            _allocator: PhantomData,
+            cap,
+            len: length,
+            ptr: Unique::new_unchecked(ptr),
+        }
+    }
+
+    /// Decomposes a `Vec<T>` into its raw components: `(pointer, length, capacity)`.
+    ///
+    /// The caller becomes responsible for the memory; use `from_raw_parts` to free it.
+    pub fn into_raw_parts(self) -> (*mut T, usize, usize) {
+        let parts = (self.ptr.as_ptr(), self.len, self.cap);
+        mem::forget(self);
+        parts
+    }
+
     /// Returns the number of elements the vector can hold without reallocating.
     pub fn capacity(&self) -> usize {
         self.cap
     }
 
+    /// Returns the number of elements in the vector.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the vector contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns a raw pointer to the vector's buffer.
+    pub fn as_ptr(&self) -> *const T {
+        self.ptr.as_ptr()
+    }
+
+    /// Returns an unsafe mutable pointer to the vector's buffer.
+    pub fn as_mut_ptr(&mut self) -> *mut T {
+        self.ptr.as_ptr()
+    }
+
+    /// Forces the length of the vector to `new_len`.
+    ///
+    /// # Safety
+    ///
+    /// `new_len` must be less than or equal to `capacity()` and the elements at `old_len..new_len`
+    /// must be initialized.
+    pub unsafe fn set_len(&mut self, new_len: usize) {
+        self.len = new_len;
+    }
+
     /// Appends an element to the back of a collection.
     pub fn push(&mut self, elem: T) {
+        self.try_push(elem)
+            .unwrap_or_else(|e| crate::handle_reserve_error::<A>(e))
+    }
+
+    /// Appends an element to the back of a collection, returning an error if the vector needs to
+    /// grow and the allocation fails
+    pub fn try_push(&mut self, elem: T) -> Result<(), TryReserveError> {
         if self.len == self.cap {
-            self.reserve(1);
+            self.try_reserve(1)?;
         }
 
         unsafe {
             self.as_mut_ptr().add(self.len).write(elem);
             self.len += 1;
         }
+
+        Ok(())
     }
 
     /// Removes the last element from a vector and returns it, or `None` if it is empty.
@@ -66,34 +162,432 @@ where
         }
     }
 
+    /// Inserts an element at position `index` within the vector, shifting all elements after it
+    /// to the right.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len`.
+    pub fn insert(&mut self, index: usize, element: T) {
+        let len = self.len;
+        assert!(
+            index <= len,
+            "insertion index (is {}) should be <= len (is {})",
+            index,
+            len
+        );
+
+        self.reserve(1);
+
+        unsafe {
+            let p = self.as_mut_ptr().add(index);
+            ptr::copy(p, p.add(1), len - index);
+            p.write(element);
+            self.len = len + 1;
+        }
+    }
+
+    /// Removes and returns the element at position `index` within the vector, shifting all
+    /// elements after it to the left.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn remove(&mut self, index: usize) -> T {
+        let len = self.len;
+        assert!(
+            index < len,
+            "removal index (is {}) should be < len (is {})",
+            index,
+            len
+        );
+
+        unsafe {
+            let p = self.as_mut_ptr().add(index);
+            let elem = p.read();
+            ptr::copy(p.add(1), p, len - index - 1);
+            self.len = len - 1;
+            elem
+        }
+    }
+
+    /// Removes an element from the vector and returns it. The removed element is replaced by the
+    /// last element of the vector.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn swap_remove(&mut self, index: usize) -> T {
+        let len = self.len;
+        assert!(
+            index < len,
+            "swap_remove index (is {}) should be < len (is {})",
+            index,
+            len
+        );
+
+        unsafe {
+            let p = self.as_mut_ptr();
+            let elem = p.add(index).read();
+            ptr::copy(p.add(len - 1), p.add(index), 1);
+            self.len = len - 1;
+            elem
+        }
+    }
+
+    /// Shortens the vector, keeping the first `len` elements and dropping the rest.
+    pub fn truncate(&mut self, len: usize) {
+        if len >= self.len {
+            return;
+        }
+
+        unsafe {
+            let tail = slice::from_raw_parts_mut(self.as_mut_ptr().add(len), self.len - len);
+            // update the length first so a panicking destructor leaks rather than double drops
+            self.len = len;
+            ptr::drop_in_place(tail);
+        }
+    }
+
+    /// Clears the vector, removing all values.
+    pub fn clear(&mut self) {
+        self.truncate(0)
+    }
+
+    /// Retains only the elements specified by the predicate.
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let len = self.len;
+        let mut deleted = 0;
+
+        // a panicking `f` leaks the elements rather than double dropping them
+        self.len = 0;
+        unsafe {
+            let p = self.as_mut_ptr();
+            for i in 0..len {
+                let cur = p.add(i);
+                if !f(&*cur) {
+                    ptr::drop_in_place(cur);
+                    deleted += 1;
+                } else if deleted > 0 {
+                    ptr::copy_nonoverlapping(cur, cur.sub(deleted), 1);
+                }
+            }
+        }
+        self.len = len - deleted;
+    }
+
+    /// Removes all but the first of consecutive elements in the vector satisfying a given
+    /// equality relation.
+    pub fn dedup_by<F>(&mut self, mut same_bucket: F)
+    where
+        F: FnMut(&mut T, &mut T) -> bool,
+    {
+        let len = self.len;
+        if len <= 1 {
+            return;
+        }
+
+        // a panicking `same_bucket` leaks the elements rather than double dropping them
+        self.len = 0;
+        let mut write = 1;
+        unsafe {
+            let p = self.as_mut_ptr();
+            for read in 1..len {
+                let cur = p.add(read);
+                if same_bucket(&mut *cur, &mut *p.add(write - 1)) {
+                    ptr::drop_in_place(cur);
+                } else {
+                    if read != write {
+                        ptr::copy_nonoverlapping(cur, p.add(write), 1);
+                    }
+                    write += 1;
+                }
+            }
+        }
+        self.len = write;
+    }
+
+    /// Removes all but the first of consecutive elements in the vector that resolve to the same
+    /// key.
+    pub fn dedup_by_key<F, K>(&mut self, mut key: F)
+    where
+        F: FnMut(&mut T) -> K,
+        K: PartialEq,
+    {
+        self.dedup_by(|a, b| key(a) == key(b))
+    }
+
+    /// Removes consecutive repeated elements in the vector according to the `PartialEq` trait
+    /// implementation.
+    pub fn dedup(&mut self)
+    where
+        T: PartialEq,
+    {
+        self.dedup_by(|a, b| a == b)
+    }
+
+    /// Clones and appends all elements in a slice to the vector.
+    pub fn extend_from_slice(&mut self, other: &[T])
+    where
+        T: Clone,
+    {
+        self.reserve(other.len());
+
+        for elem in other {
+            unsafe {
+                self.as_mut_ptr().add(self.len).write(elem.clone());
+                self.len += 1;
+            }
+        }
+    }
+
+    /// Splits the collection into two at the given index.
+    ///
+    /// Returns a newly allocated vector containing the elements in the range `[at, len)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at > len`.
+    pub fn split_off(&mut self, at: usize) -> Self {
+        assert!(at <= self.len, "`at` out of bounds");
+
+        let other_len = self.len - at;
+        let mut other = Self::with_capacity(other_len);
+
+        unsafe {
+            self.len = at;
+            ptr::copy_nonoverlapping(self.as_ptr().add(at), other.as_mut_ptr(), other_len);
+            other.len = other_len;
+        }
+
+        other
+    }
+
+    /// Moves all the elements of `other` into `self`, leaving `other` empty.
+    pub fn append(&mut self, other: &mut Self) {
+        let count = other.len;
+        self.reserve(count);
+
+        unsafe {
+            ptr::copy_nonoverlapping(other.as_ptr(), self.as_mut_ptr().add(self.len), count);
+            other.len = 0;
+        }
+        self.len += count;
+    }
+
+    /// Resizes the vector in-place so that `len` is equal to `new_len`, filling new slots with
+    /// clones of `value`.
+    pub fn resize(&mut self, new_len: usize, value: T)
+    where
+        T: Clone,
+    {
+        if new_len > self.len {
+            let additional = new_len - self.len;
+            self.reserve(additional);
+
+            unsafe {
+                for _ in 1..additional {
+                    self.as_mut_ptr().add(self.len).write(value.clone());
+                    self.len += 1;
+                }
+                // move `value` into the last slot
+                self.as_mut_ptr().add(self.len).write(value);
+                self.len += 1;
+            }
+        } else {
+            self.truncate(new_len);
+        }
+    }
+
+    /// Resizes the vector in-place so that `len` is equal to `new_len`, filling new slots with
+    /// the values returned by calling the closure `f`.
+    pub fn resize_with<F>(&mut self, new_len: usize, mut f: F)
+    where
+        F: FnMut() -> T,
+    {
+        if new_len > self.len {
+            self.reserve(new_len - self.len);
+
+            while self.len < new_len {
+                unsafe {
+                    self.as_mut_ptr().add(self.len).write(f());
+                    self.len += 1;
+                }
+            }
+        } else {
+            self.truncate(new_len);
+        }
+    }
+
+    /// Creates a draining iterator that removes the specified range in the vector and yields the
+    /// removed items.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the starting point is greater than the end point or if the end point is greater
+    /// than the length of the vector.
+    pub fn drain<R>(&mut self, range: R) -> Drain<'_, A, T>
+    where
+        R: RangeBounds<usize>,
+    {
+        let len = self.len;
+        let start = match range.start_bound() {
+            Bound::Included(&n) => n,
+            Bound::Excluded(&n) => n
+                .checked_add(1)
+                .expect("attempted to drain from after maximum usize"),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&n) => n
+                .checked_add(1)
+                .expect("attempted to drain up to maximum usize"),
+            Bound::Excluded(&n) => n,
+            Bound::Unbounded => len,
+        };
+        assert!(
+            start <= end,
+            "drain start (is {}) should be <= end (is {})",
+            start,
+            end
+        );
+        assert!(
+            end <= len,
+            "drain end (is {}) should be <= len (is {})",
+            end,
+            len
+        );
+
+        unsafe {
+            // the drained elements and the tail are owned by `Drain` until it's dropped
+            self.len = start;
+            let range = slice::from_raw_parts(self.as_ptr().add(start), end - start);
+
+            Drain {
+                tail_start: end,
+                tail_len: len - end,
+                iter: range.iter(),
+                vec: NonNull::from(self),
+                _marker: PhantomData,
+            }
+        }
+    }
+
+    /// Shrinks the capacity of the vector as much as possible.
+    pub fn shrink_to_fit(&mut self) {
+        let layout = if let Some(layout) = self.current_layout() {
+            layout
+        } else {
+            return;
+        };
+
+        if self.len == self.cap {
+            return;
+        }
+
+        unsafe {
+            if self.len == 0 {
+                A::dealloc(self.ptr.as_ptr() as *mut u8, layout);
+                self.ptr = Unique::empty();
+            } else {
+                let new_size = mem::size_of::<T>() * self.len;
+                let res = A::realloc(self.ptr.as_ptr() as *mut u8, layout, new_size);
+                self.ptr = Unique::new(res as *mut T).unwrap_or_else(|| {
+                    crate::handle_reserve_error::<A>(TryReserveError::AllocError {
+                        layout: Layout::from_size_align_unchecked(new_size, layout.align()),
+                    })
+                });
+            }
+        }
+        self.cap = self.len;
+    }
+
+    /// Converts the vector into a boxed slice, dropping any excess capacity.
+    pub fn into_boxed_slice(mut self) -> Box<A, [T]> {
+        self.shrink_to_fit();
+        let (ptr, len, _) = self.into_raw_parts();
+        unsafe {
+            let slice = slice::from_raw_parts_mut(ptr, len);
+            Box::from_raw(slice)
+        }
+    }
+
+    /// Clones the vector into a new allocation on the allocator `B`.
+    ///
+    /// The clone has no excess capacity.
+    pub fn clone_in<B>(&self) -> Vec<B, T>
+    where
+        B: Alloc,
+        T: Clone,
+    {
+        self.try_clone_in()
+            .unwrap_or_else(|e| crate::handle_reserve_error::<B>(e))
+    }
+
+    /// Clones the vector into a new allocation on the allocator `B`, returning an error if the
+    /// allocation fails
+    pub fn try_clone_in<B>(&self) -> Result<Vec<B, T>, TryReserveError>
+    where
+        B: Alloc,
+        T: Clone,
+    {
+        let mut v = Vec::try_with_capacity(self.len)?;
+        v.extend_from_slice(self);
+        Ok(v)
+    }
+
+    /// Consumes and leaks the `Vec`, returning a mutable reference to the contents.
+    pub fn leak<'a>(self) -> &'a mut [T]
+    where
+        T: 'a,
+    {
+        let (ptr, len, _) = self.into_raw_parts();
+        unsafe { slice::from_raw_parts_mut(ptr, len) }
+    }
+
     /// Reserves capacity for at least `additional` more elements to be inserted in the given
     /// Vec<T>.
     pub fn reserve(&mut self, additional: usize) {
+        self.try_reserve(additional)
+            .unwrap_or_else(|e| crate::handle_reserve_error::<A>(e))
+    }
+
+    /// Tries to reserve capacity for at least `additional` more elements to be inserted in the
+    /// given Vec<T>.
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
         if self.cap.wrapping_sub(self.len) >= additional {
-            return;
+            return Ok(());
         }
 
-        unsafe {
-            let (new_cap, new_layout) = amortized_new_capacity(self.len, additional)
-                .and_then(|new_cap| layout_array::<T>(new_cap).map(|layout| (new_cap, layout)))
-                .unwrap_or_else(|| capacity_overflow());
+        let new_cap = amortized_new_capacity(self.len, additional)
+            .ok_or(TryReserveError::CapacityOverflow)?;
+
+        self.try_grow(new_cap)
+    }
+
+    // NOTE `new_cap` must be greater than the current capacity
+    fn try_grow(&mut self, new_cap: usize) -> Result<(), TryReserveError> {
+        let new_layout = layout_array::<T>(new_cap).ok_or(TryReserveError::CapacityOverflow)?;
 
+        unsafe {
             let res = match self.current_layout() {
                 None => A::alloc(new_layout),
                 Some(layout) => A::realloc(self.ptr.as_ptr() as *mut u8, layout, new_layout.size()),
             };
 
-            self.ptr = if let Some(ptr) = Unique::new(res as *mut T) {
-                ptr
-            } else {
-                crate::alloc_many_oom(new_layout)
-            };
-            self.cap = new_cap;
+            self.ptr = Unique::new(res as *mut T)
+                .ok_or(TryReserveError::AllocError { layout: new_layout })?;
         }
+        self.cap = new_cap;
+
+        Ok(())
     }
 
     fn current_layout(&self) -> Option<Layout> {
-        if self.cap == 0 {
+        if self.cap == 0 || mem::size_of::<T>() == 0 {
             None
         } else {
             unsafe {
@@ -105,19 +599,75 @@ where
     }
 }
 
-fn amortized_new_capacity(curr: usize, additional: usize) -> Option<usize> {
+impl<A, T> Vec<A, T>
+where
+    A: Alloc + 'static,
+{
+    /// Moves the elements of the vector into the allocator `B`.
+    ///
+    /// If `B` is `A` the buffer is reused; otherwise the new buffer has no excess capacity.
+    pub fn into_alloc<B>(self) -> Vec<B, T>
+    where
+        B: Alloc + 'static,
+    {
+        self.try_into_alloc()
+            .unwrap_or_else(|(_, e)| crate::handle_reserve_error::<B>(e))
+    }
+
+    /// Moves the elements of the vector into the allocator `B`, returning an error if the
+    /// allocation fails
+    ///
+    /// If `B` is `A` the buffer is reused. On error the original vector is returned along with
+    /// the error.
+    pub fn try_into_alloc<B>(mut self) -> Result<Vec<B, T>, (Self, TryReserveError)>
+    where
+        B: Alloc + 'static,
+    {
+        if crate::same_alloc::<A, B>() {
+            let (ptr, len, cap) = self.into_raw_parts();
+            return unsafe { Ok(Vec::from_raw_parts(ptr, len, cap)) };
+        }
+
+        let mut v = match Vec::try_with_capacity(self.len) {
+            Ok(v) => v,
+            Err(e) => return Err((self, e)),
+        };
+
+        unsafe {
+            ptr::copy_nonoverlapping(self.as_ptr(), v.as_mut_ptr(), self.len);
+            v.set_len(self.len);
+            // `self` only frees its buffer
+            self.set_len(0);
+        }
+
+        Ok(v)
+    }
+}
+
+impl<A, T> Drop for Vec<A, T>
+where
+    A: Alloc,
+{
+    fn drop(&mut self) {
+        unsafe {
+            ptr::drop_in_place(&mut self[..]);
+
+            if let Some(layout) = self.current_layout() {
+                A::dealloc(self.ptr.as_ptr() as *mut u8, layout)
+            }
+        }
+    }
+}
+
+pub(crate) fn amortized_new_capacity(curr: usize, additional: usize) -> Option<usize> {
     let double_cap = curr.checked_mul(2)?;
     let required_cap = curr.checked_add(additional)?;
 
     Some(cmp::max(double_cap, required_cap))
 }
 
-fn capacity_overflow() -> ! {
-    panic!("capacity overflow")
-}
-
 // unstable methods of `core::alloc::Layout`
-fn layout_array<T>(n: usize) -> Option<Layout> {
+pub(crate) fn layout_array<T>(n: usize) -> Option<Layout> {
     layout_repeat(&Layout::new::<T>(), n).map(|(k, _)| k)
 }
 
@@ -128,14 +678,10 @@ fn layout_repeat(layout: &Layout, n: usize) -> Option<(Layout, usize)> {
 
     let alloc_size = padded_size.checked_mul(n)?;
 
-    unsafe {
-        // self.align is already known to be valid and alloc_size has been
-        // padded already.
-        Some((
-            Layout::from_size_align_unchecked(alloc_size, layout.align()),
-            padded_size,
-        ))
-    }
+    // `from_size_align` rejects sizes that overflow `isize` after rounding
+    Layout::from_size_align(alloc_size, layout.align())
+        .ok()
+        .map(|layout| (layout, padded_size))
 }
 
 fn padding_needed_for(layout: &Layout, align: usize) -> usize {
@@ -165,3 +711,375 @@ where
         unsafe { slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
     }
 }
+
+/// A draining iterator for `Vec<T>`.
+///
+/// This `struct` is created by the `drain` method on `Vec`.
+pub struct Drain<'a, A, T>
+where
+    A: Alloc,
+{
+    tail_start: usize,
+    tail_len: usize,
+    iter: slice::Iter<'a, T>,
+    vec: NonNull<Vec<A, T>>,
+    _marker: PhantomData<&'a mut Vec<A, T>>,
+}
+
+unsafe impl<'a, A, T> Send for Drain<'a, A, T>
+where
+    A: Alloc,
+    T: Send,
+{
+}
+
+unsafe impl<'a, A, T> Sync for Drain<'a, A, T>
+where
+    A: Alloc,
+    T: Sync,
+{
+}
+
+impl<'a, A, T> Iterator for Drain<'a, A, T>
+where
+    A: Alloc,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.iter.next().map(|elem| unsafe { ptr::read(elem) })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, A, T> DoubleEndedIterator for Drain<'a, A, T>
+where
+    A: Alloc,
+{
+    fn next_back(&mut self) -> Option<T> {
+        self.iter.next_back().map(|elem| unsafe { ptr::read(elem) })
+    }
+}
+
+impl<'a, A, T> ExactSizeIterator for Drain<'a, A, T> where A: Alloc {}
+
+impl<'a, A, T> Drop for Drain<'a, A, T>
+where
+    A: Alloc,
+{
+    fn drop(&mut self) {
+        // drop the elements that were not yielded
+        self.for_each(drop);
+
+        unsafe {
+            let vec = self.vec.as_mut();
+            let start = vec.len;
+            if self.tail_len != 0 && self.tail_start != start {
+                let p = vec.as_mut_ptr();
+                ptr::copy(p.add(self.tail_start), p.add(start), self.tail_len);
+            }
+            vec.len = start + self.tail_len;
+        }
+    }
+}
+
+/// An iterator that moves out of a vector.
+///
+/// This `struct` is created by the `into_iter` method on `Vec`.
+pub struct IntoIter<A, T>
+where
+    A: Alloc,
+{
+    // NOTE `buf` only owns the allocation; the elements at `start..end` are still to be yielded
+    buf: Vec<A, T>,
+    start: usize,
+    end: usize,
+}
+
+impl<A, T> IntoIter<A, T>
+where
+    A: Alloc,
+{
+    /// Returns the remaining items of this iterator as a slice.
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.buf.as_ptr().add(self.start), self.end - self.start) }
+    }
+
+    /// Returns the remaining items of this iterator as a mutable slice.
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        unsafe {
+            slice::from_raw_parts_mut(self.buf.as_mut_ptr().add(self.start), self.end - self.start)
+        }
+    }
+}
+
+impl<A, T> Iterator for IntoIter<A, T>
+where
+    A: Alloc,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.start == self.end {
+            None
+        } else {
+            unsafe {
+                let elem = self.buf.as_ptr().add(self.start).read();
+                self.start += 1;
+                Some(elem)
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.end - self.start;
+        (len, Some(len))
+    }
+}
+
+impl<A, T> DoubleEndedIterator for IntoIter<A, T>
+where
+    A: Alloc,
+{
+    fn next_back(&mut self) -> Option<T> {
+        if self.start == self.end {
+            None
+        } else {
+            unsafe {
+                self.end -= 1;
+                Some(self.buf.as_ptr().add(self.end).read())
+            }
+        }
+    }
+}
+
+impl<A, T> ExactSizeIterator for IntoIter<A, T> where A: Alloc {}
+
+impl<A, T> fmt::Debug for IntoIter<A, T>
+where
+    A: Alloc,
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("IntoIter").field(&self.as_slice()).finish()
+    }
+}
+
+impl<A, T> Drop for IntoIter<A, T>
+where
+    A: Alloc,
+{
+    fn drop(&mut self) {
+        unsafe { ptr::drop_in_place(self.as_mut_slice()) }
+        // `buf` frees the allocation
+    }
+}
+
+impl<A, T> IntoIterator for Vec<A, T>
+where
+    A: Alloc,
+{
+    type Item = T;
+    type IntoIter = IntoIter<A, T>;
+
+    fn into_iter(mut self) -> IntoIter<A, T> {
+        let end = self.len;
+        unsafe { self.set_len(0) }
+
+        IntoIter {
+            buf: self,
+            start: 0,
+            end,
+        }
+    }
+}
+
+impl<'a, A, T> IntoIterator for &'a Vec<A, T>
+where
+    A: Alloc,
+{
+    type Item = &'a T;
+    type IntoIter = slice::Iter<'a, T>;
+
+    fn into_iter(self) -> slice::Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, A, T> IntoIterator for &'a mut Vec<A, T>
+where
+    A: Alloc,
+{
+    type Item = &'a mut T;
+    type IntoIter = slice::IterMut<'a, T>;
+
+    fn into_iter(self) -> slice::IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+impl<A, T> FromIterator<T> for Vec<A, T>
+where
+    A: Alloc,
+{
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let mut v = Vec::new();
+        v.extend(iter);
+        v
+    }
+}
+
+impl<A, T> Extend<T> for Vec<A, T>
+where
+    A: Alloc,
+{
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+
+        for elem in iter {
+            self.push(elem);
+        }
+    }
+}
+
+impl<'a, A, T> Extend<&'a T> for Vec<A, T>
+where
+    A: Alloc,
+    T: 'a + Copy,
+{
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = &'a T>,
+    {
+        self.extend(iter.into_iter().cloned())
+    }
+}
+
+impl<A, T> Clone for Vec<A, T>
+where
+    A: Alloc,
+    T: Clone,
+{
+    fn clone(&self) -> Self {
+        let mut v = Vec::with_capacity(self.len);
+        v.extend_from_slice(self);
+        v
+    }
+}
+
+impl<A, T> Default for Vec<A, T>
+where
+    A: Alloc,
+{
+    fn default() -> Self {
+        Vec::new()
+    }
+}
+
+impl<A, T> fmt::Debug for Vec<A, T>
+where
+    A: Alloc,
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        <[T] as fmt::Debug>::fmt(self, f)
+    }
+}
+
+impl<A, T> Hash for Vec<A, T>
+where
+    A: Alloc,
+    T: Hash,
+{
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: Hasher,
+    {
+        <[T] as Hash>::hash(self, state)
+    }
+}
+
+impl<A, T> AsRef<[T]> for Vec<A, T>
+where
+    A: Alloc,
+{
+    fn as_ref(&self) -> &[T] {
+        self
+    }
+}
+
+impl<A, T> AsMut<[T]> for Vec<A, T>
+where
+    A: Alloc,
+{
+    fn as_mut(&mut self) -> &mut [T] {
+        self
+    }
+}
+
+impl<A, T> Borrow<[T]> for Vec<A, T>
+where
+    A: Alloc,
+{
+    fn borrow(&self) -> &[T] {
+        self
+    }
+}
+
+impl<A, T> BorrowMut<[T]> for Vec<A, T>
+where
+    A: Alloc,
+{
+    fn borrow_mut(&mut self) -> &mut [T] {
+        self
+    }
+}
+
+impl<A, T> Eq for Vec<A, T>
+where
+    A: Alloc,
+    T: Eq,
+{
+}
+
+impl<A, B, T> PartialEq<Vec<B, T>> for Vec<A, T>
+where
+    A: Alloc,
+    B: Alloc,
+    T: PartialEq,
+{
+    fn eq(&self, other: &Vec<B, T>) -> bool {
+        <[T] as PartialEq>::eq(self, other)
+    }
+}
+
+impl<A, T> Ord for Vec<A, T>
+where
+    A: Alloc,
+    T: Ord,
+{
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        <[T] as Ord>::cmp(self, other)
+    }
+}
+
+impl<A, B, T> PartialOrd<Vec<B, T>> for Vec<A, T>
+where
+    A: Alloc,
+    B: Alloc,
+    T: PartialOrd,
+{
+    fn partial_cmp(&self, other: &Vec<B, T>) -> Option<cmp::Ordering> {
+        <[T] as PartialOrd>::partial_cmp(self, other)
+    }
+}
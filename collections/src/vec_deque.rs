@@ -0,0 +1,889 @@
+//! A double-ended queue implemented with a growable ring buffer.
+
+use core::{
+    alloc::Layout,
+    cmp, fmt,
+    hash::{Hash, Hasher},
+    iter::FromIterator,
+    marker::PhantomData,
+    mem,
+    ops::{self, Bound, RangeBounds},
+    ptr::{self, NonNull},
+    slice,
+};
+
+use alloc_many::Alloc;
+
+use crate::{
+    unique::Unique,
+    vec::{amortized_new_capacity, layout_array},
+    TryReserveError,
+};
+
+/// A double-ended queue implemented with a growable ring buffer.
+pub struct VecDeque<A, T>
+where
+    A: Alloc,
+{
+    cap: usize,
+    // physical index of the first element
+    head: usize,
+    len: usize,
+    ptr: Unique<T>,
+    _allocator: PhantomData<A>,
+}
+
+impl<A, T> VecDeque<A, T>
+where
+    A: Alloc,
+{
+    /// Creates an empty `VecDeque`.
+    pub fn new() -> Self {
+        let cap = if mem::size_of::<T>() == 0 {
+            usize::max_value()
+        } else {
+            0
+        };
+
+        VecDeque {
+            cap,
+            head: 0,
+            len: 0,
+            ptr: Unique::empty(),
+            _allocator: PhantomData,
+        }
+    }
+
+    /// Creates an empty `VecDeque` with space for at least `capacity` elements.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::try_with_capacity(capacity).unwrap_or_else(|e| crate::handle_reserve_error::<A>(e))
+    }
+
+    /// Creates an empty `VecDeque` with space for at least `capacity` elements, returning an
+    /// error if the allocation fails
+    pub fn try_with_capacity(capacity: usize) -> Result<Self, TryReserveError> {
+        let mut deque = Self::new();
+        if capacity > deque.cap {
+            deque.try_grow(capacity)?;
+        }
+        Ok(deque)
+    }
+
+    /// Returns the number of elements the `VecDeque` can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.cap
+    }
+
+    /// Returns the number of elements in the `VecDeque`.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the `VecDeque` is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Reserves capacity for at least `additional` more elements to be inserted in the given
+    /// `VecDeque`.
+    pub fn reserve(&mut self, additional: usize) {
+        self.try_reserve(additional)
+            .unwrap_or_else(|e| crate::handle_reserve_error::<A>(e))
+    }
+
+    /// Tries to reserve capacity for at least `additional` more elements to be inserted in the
+    /// given `VecDeque`.
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        if self.cap.wrapping_sub(self.len) >= additional {
+            return Ok(());
+        }
+
+        let new_cap = amortized_new_capacity(self.len, additional)
+            .ok_or(TryReserveError::CapacityOverflow)?;
+
+        self.try_grow(new_cap)
+    }
+
+    // NOTE `new_cap` must be greater than the current capacity
+    fn try_grow(&mut self, new_cap: usize) -> Result<(), TryReserveError> {
+        let new_layout = layout_array::<T>(new_cap).ok_or(TryReserveError::CapacityOverflow)?;
+
+        unsafe {
+            let res = match self.current_layout() {
+                None => A::alloc(new_layout),
+                Some(layout) => A::realloc(self.ptr.as_ptr() as *mut u8, layout, new_layout.size()),
+            };
+
+            self.ptr = Unique::new(res as *mut T)
+                .ok_or(TryReserveError::AllocError { layout: new_layout })?;
+        }
+
+        let old_cap = self.cap;
+        self.cap = new_cap;
+
+        // the elements wrapped around the end of the old buffer; unwrap them
+        let head_len = old_cap - self.head;
+        if self.len > head_len {
+            let tail_len = self.len - head_len;
+
+            unsafe {
+                let p = self.ptr.as_ptr();
+                if tail_len <= new_cap - old_cap && tail_len < head_len {
+                    // move the tail after the head segment
+                    ptr::copy_nonoverlapping(p, p.add(old_cap), tail_len);
+                } else {
+                    // move the head segment to the end of the new buffer
+                    let new_head = new_cap - head_len;
+                    ptr::copy(p.add(self.head), p.add(new_head), head_len);
+                    self.head = new_head;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn current_layout(&self) -> Option<Layout> {
+        if self.cap == 0 || mem::size_of::<T>() == 0 {
+            None
+        } else {
+            unsafe {
+                let align = mem::align_of::<T>();
+                let size = mem::size_of::<T>() * self.cap;
+                Some(Layout::from_size_align_unchecked(size, align))
+            }
+        }
+    }
+
+    // physical index of the element `offset` positions after the physical index `idx`
+    fn wrap_add(&self, idx: usize, offset: usize) -> usize {
+        // NOTE written this way to avoid overflow when `cap == usize::max_value()`
+        if offset >= self.cap - idx {
+            offset - (self.cap - idx)
+        } else {
+            idx + offset
+        }
+    }
+
+    // physical index of the element `offset` positions before the physical index `idx`
+    fn wrap_sub(&self, idx: usize, offset: usize) -> usize {
+        if offset > idx {
+            self.cap - (offset - idx)
+        } else {
+            idx - offset
+        }
+    }
+
+    fn to_physical(&self, idx: usize) -> usize {
+        self.wrap_add(self.head, idx)
+    }
+
+    unsafe fn slot(&self, idx: usize) -> *mut T {
+        self.ptr.as_ptr().add(self.to_physical(idx))
+    }
+
+    /// Appends an element to the back of the `VecDeque`.
+    pub fn push_back(&mut self, value: T) {
+        self.try_push_back(value)
+            .unwrap_or_else(|e| crate::handle_reserve_error::<A>(e))
+    }
+
+    /// Appends an element to the back of the `VecDeque`, returning an error if the `VecDeque`
+    /// needs to grow and the allocation fails
+    pub fn try_push_back(&mut self, value: T) -> Result<(), TryReserveError> {
+        if self.len == self.cap {
+            self.try_reserve(1)?;
+        }
+
+        unsafe { self.slot(self.len).write(value) }
+        self.len += 1;
+
+        Ok(())
+    }
+
+    /// Prepends an element to the `VecDeque`.
+    pub fn push_front(&mut self, value: T) {
+        self.try_push_front(value)
+            .unwrap_or_else(|e| crate::handle_reserve_error::<A>(e))
+    }
+
+    /// Prepends an element to the `VecDeque`, returning an error if the `VecDeque` needs to grow
+    /// and the allocation fails
+    pub fn try_push_front(&mut self, value: T) -> Result<(), TryReserveError> {
+        if self.len == self.cap {
+            self.try_reserve(1)?;
+        }
+
+        self.head = self.wrap_sub(self.head, 1);
+        unsafe { self.ptr.as_ptr().add(self.head).write(value) }
+        self.len += 1;
+
+        Ok(())
+    }
+
+    /// Removes the last element and returns it, or `None` if the `VecDeque` is empty.
+    pub fn pop_back(&mut self) -> Option<T> {
+        if self.len == 0 {
+            None
+        } else {
+            self.len -= 1;
+            unsafe { Some(self.slot(self.len).read()) }
+        }
+    }
+
+    /// Removes the first element and returns it, or `None` if the `VecDeque` is empty.
+    pub fn pop_front(&mut self) -> Option<T> {
+        if self.len == 0 {
+            None
+        } else {
+            let old_head = self.head;
+            self.head = self.wrap_add(self.head, 1);
+            self.len -= 1;
+            unsafe { Some(self.ptr.as_ptr().add(old_head).read()) }
+        }
+    }
+
+    /// Provides a reference to the element at the given index.
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index < self.len {
+            unsafe { Some(&*self.slot(index)) }
+        } else {
+            None
+        }
+    }
+
+    /// Provides a mutable reference to the element at the given index.
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        if index < self.len {
+            unsafe { Some(&mut *self.slot(index)) }
+        } else {
+            None
+        }
+    }
+
+    /// Provides a reference to the front element, or `None` if the `VecDeque` is empty.
+    pub fn front(&self) -> Option<&T> {
+        self.get(0)
+    }
+
+    /// Provides a mutable reference to the front element, or `None` if the `VecDeque` is empty.
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        self.get_mut(0)
+    }
+
+    /// Provides a reference to the back element, or `None` if the `VecDeque` is empty.
+    pub fn back(&self) -> Option<&T> {
+        self.get(self.len.wrapping_sub(1))
+    }
+
+    /// Provides a mutable reference to the back element, or `None` if the `VecDeque` is empty.
+    pub fn back_mut(&mut self) -> Option<&mut T> {
+        let last = self.len.wrapping_sub(1);
+        self.get_mut(last)
+    }
+
+    /// Swaps elements at indices `i` and `j`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either index is out of bounds.
+    pub fn swap(&mut self, i: usize, j: usize) {
+        assert!(i < self.len);
+        assert!(j < self.len);
+
+        unsafe { ptr::swap(self.slot(i), self.slot(j)) }
+    }
+
+    /// Shortens the `VecDeque`, keeping the first `len` elements and dropping the rest.
+    pub fn truncate(&mut self, len: usize) {
+        while self.len > len {
+            drop(self.pop_back());
+        }
+    }
+
+    /// Clears the `VecDeque`, removing all values.
+    pub fn clear(&mut self) {
+        self.truncate(0);
+        self.head = 0;
+    }
+
+    /// Returns a pair of slices which contain, in order, the contents of the `VecDeque`.
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        unsafe {
+            let p = self.ptr.as_ptr();
+            let head_len = self.cap - self.head;
+
+            if self.len <= head_len {
+                (slice::from_raw_parts(p.add(self.head), self.len), &[])
+            } else {
+                (
+                    slice::from_raw_parts(p.add(self.head), head_len),
+                    slice::from_raw_parts(p, self.len - head_len),
+                )
+            }
+        }
+    }
+
+    /// Returns a pair of slices which contain, in order, the contents of the `VecDeque`.
+    pub fn as_mut_slices(&mut self) -> (&mut [T], &mut [T]) {
+        unsafe {
+            let p = self.ptr.as_ptr();
+            let head_len = self.cap - self.head;
+
+            if self.len <= head_len {
+                (
+                    slice::from_raw_parts_mut(p.add(self.head), self.len),
+                    &mut [],
+                )
+            } else {
+                (
+                    slice::from_raw_parts_mut(p.add(self.head), head_len),
+                    slice::from_raw_parts_mut(p, self.len - head_len),
+                )
+            }
+        }
+    }
+
+    /// Rearranges the internal storage of this deque so it is one contiguous slice, which is then
+    /// returned.
+    pub fn make_contiguous(&mut self) -> &mut [T] {
+        let head_len = self.cap - self.head;
+
+        if self.len > head_len {
+            let tail_len = self.len - head_len;
+
+            unsafe {
+                let p = self.ptr.as_ptr();
+                // place the head segment right after the tail segment ..
+                ptr::copy(p.add(self.head), p.add(tail_len), head_len);
+                // .. and then rotate the (now contiguous) elements into order
+                slice::from_raw_parts_mut(p, self.len).rotate_left(tail_len);
+            }
+
+            self.head = 0;
+        }
+
+        self.as_mut_slices().0
+    }
+
+    /// Rotates the double-ended queue `n` places to the left.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is greater than `len()`.
+    pub fn rotate_left(&mut self, n: usize) {
+        assert!(n <= self.len);
+
+        if n <= self.len / 2 {
+            for _ in 0..n {
+                // NOTE doesn't reallocate because `pop_front` freed a slot
+                if let Some(x) = self.pop_front() {
+                    self.push_back(x);
+                }
+            }
+        } else {
+            for _ in 0..self.len - n {
+                if let Some(x) = self.pop_back() {
+                    self.push_front(x);
+                }
+            }
+        }
+    }
+
+    /// Rotates the double-ended queue `n` places to the right.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is greater than `len()`.
+    pub fn rotate_right(&mut self, n: usize) {
+        assert!(n <= self.len);
+
+        self.rotate_left(self.len - n)
+    }
+
+    /// Returns a front-to-back iterator.
+    pub fn iter(&self) -> Iter<'_, T> {
+        let (front, back) = self.as_slices();
+
+        Iter {
+            front: front.iter(),
+            back: back.iter(),
+        }
+    }
+
+    /// Returns a front-to-back iterator that returns mutable references.
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        let (front, back) = self.as_mut_slices();
+
+        IterMut {
+            front: front.iter_mut(),
+            back: back.iter_mut(),
+        }
+    }
+
+    /// Creates a draining iterator that removes the specified range in the `VecDeque` and yields
+    /// the removed items.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the starting point is greater than the end point or if the end point is greater
+    /// than the length of the `VecDeque`.
+    pub fn drain<R>(&mut self, range: R) -> Drain<'_, A, T>
+    where
+        R: RangeBounds<usize>,
+    {
+        let len = self.len;
+        let start = match range.start_bound() {
+            Bound::Included(&n) => n,
+            Bound::Excluded(&n) => n
+                .checked_add(1)
+                .expect("attempted to drain from after maximum usize"),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&n) => n
+                .checked_add(1)
+                .expect("attempted to drain up to maximum usize"),
+            Bound::Excluded(&n) => n,
+            Bound::Unbounded => len,
+        };
+        assert!(
+            start <= end,
+            "drain start (is {}) should be <= end (is {})",
+            start,
+            end
+        );
+        assert!(
+            end <= len,
+            "drain end (is {}) should be <= len (is {})",
+            end,
+            len
+        );
+
+        // the drained elements and the tail are owned by `Drain` until it's dropped
+        self.len = start;
+
+        Drain {
+            start,
+            end,
+            front: start,
+            back: end,
+            orig_len: len,
+            deque: NonNull::from(self),
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// An iterator over the elements of a `VecDeque`.
+///
+/// This `struct` is created by the `iter` method on `VecDeque`.
+#[derive(Clone)]
+pub struct Iter<'a, T> {
+    front: slice::Iter<'a, T>,
+    back: slice::Iter<'a, T>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        match self.front.next() {
+            None => self.back.next(),
+            x => x,
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.front.len() + self.back.len();
+        (len, Some(len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<&'a T> {
+        match self.back.next_back() {
+            None => self.front.next_back(),
+            x => x,
+        }
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {}
+
+/// A mutable iterator over the elements of a `VecDeque`.
+///
+/// This `struct` is created by the `iter_mut` method on `VecDeque`.
+pub struct IterMut<'a, T> {
+    front: slice::IterMut<'a, T>,
+    back: slice::IterMut<'a, T>,
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<&'a mut T> {
+        match self.front.next() {
+            None => self.back.next(),
+            x => x,
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.front.len() + self.back.len();
+        (len, Some(len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
+    fn next_back(&mut self) -> Option<&'a mut T> {
+        match self.back.next_back() {
+            None => self.front.next_back(),
+            x => x,
+        }
+    }
+}
+
+impl<'a, T> ExactSizeIterator for IterMut<'a, T> {}
+
+/// An owning iterator over the elements of a `VecDeque`.
+///
+/// This `struct` is created by the `into_iter` method on `VecDeque`.
+pub struct IntoIter<A, T>
+where
+    A: Alloc,
+{
+    inner: VecDeque<A, T>,
+}
+
+impl<A, T> Iterator for IntoIter<A, T>
+where
+    A: Alloc,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.inner.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.inner.len, Some(self.inner.len))
+    }
+}
+
+impl<A, T> DoubleEndedIterator for IntoIter<A, T>
+where
+    A: Alloc,
+{
+    fn next_back(&mut self) -> Option<T> {
+        self.inner.pop_back()
+    }
+}
+
+impl<A, T> ExactSizeIterator for IntoIter<A, T> where A: Alloc {}
+
+/// A draining iterator over the elements of a `VecDeque`.
+///
+/// This `struct` is created by the `drain` method on `VecDeque`.
+pub struct Drain<'a, A, T>
+where
+    A: Alloc,
+{
+    // the drained range
+    start: usize,
+    end: usize,
+    // the elements at `front..back` have not been yielded yet
+    front: usize,
+    back: usize,
+    orig_len: usize,
+    deque: NonNull<VecDeque<A, T>>,
+    _marker: PhantomData<&'a mut VecDeque<A, T>>,
+}
+
+unsafe impl<'a, A, T> Send for Drain<'a, A, T>
+where
+    A: Alloc,
+    T: Send,
+{
+}
+
+unsafe impl<'a, A, T> Sync for Drain<'a, A, T>
+where
+    A: Alloc,
+    T: Sync,
+{
+}
+
+impl<'a, A, T> Iterator for Drain<'a, A, T>
+where
+    A: Alloc,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.front == self.back {
+            None
+        } else {
+            let idx = self.front;
+            self.front += 1;
+            unsafe { Some(self.deque.as_ref().slot(idx).read()) }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back - self.front;
+        (len, Some(len))
+    }
+}
+
+impl<'a, A, T> DoubleEndedIterator for Drain<'a, A, T>
+where
+    A: Alloc,
+{
+    fn next_back(&mut self) -> Option<T> {
+        if self.front == self.back {
+            None
+        } else {
+            self.back -= 1;
+            unsafe { Some(self.deque.as_ref().slot(self.back).read()) }
+        }
+    }
+}
+
+impl<'a, A, T> ExactSizeIterator for Drain<'a, A, T> where A: Alloc {}
+
+impl<'a, A, T> Drop for Drain<'a, A, T>
+where
+    A: Alloc,
+{
+    fn drop(&mut self) {
+        // drop the elements that were not yielded
+        self.for_each(drop);
+
+        unsafe {
+            let deque = self.deque.as_mut();
+            let gap = self.end - self.start;
+            let head_len = self.start;
+            let tail_len = self.orig_len - self.end;
+
+            if gap != 0 {
+                // close the gap by moving the shorter side
+                if head_len <= tail_len {
+                    for i in (0..head_len).rev() {
+                        ptr::copy_nonoverlapping(deque.slot(i), deque.slot(i + gap), 1);
+                    }
+                    deque.head = deque.wrap_add(deque.head, gap);
+                } else {
+                    for i in self.end..self.orig_len {
+                        ptr::copy_nonoverlapping(deque.slot(i), deque.slot(i - gap), 1);
+                    }
+                }
+            }
+
+            deque.len = self.orig_len - gap;
+        }
+    }
+}
+
+impl<A, T> Drop for VecDeque<A, T>
+where
+    A: Alloc,
+{
+    fn drop(&mut self) {
+        unsafe {
+            let (front, back) = self.as_mut_slices();
+            ptr::drop_in_place(front);
+            ptr::drop_in_place(back);
+
+            if let Some(layout) = self.current_layout() {
+                A::dealloc(self.ptr.as_ptr() as *mut u8, layout)
+            }
+        }
+    }
+}
+
+impl<A, T> ops::Index<usize> for VecDeque<A, T>
+where
+    A: Alloc,
+{
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        self.get(index).expect("Out of bounds access")
+    }
+}
+
+impl<A, T> ops::IndexMut<usize> for VecDeque<A, T>
+where
+    A: Alloc,
+{
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        self.get_mut(index).expect("Out of bounds access")
+    }
+}
+
+impl<A, T> IntoIterator for VecDeque<A, T>
+where
+    A: Alloc,
+{
+    type Item = T;
+    type IntoIter = IntoIter<A, T>;
+
+    fn into_iter(self) -> IntoIter<A, T> {
+        IntoIter { inner: self }
+    }
+}
+
+impl<'a, A, T> IntoIterator for &'a VecDeque<A, T>
+where
+    A: Alloc,
+{
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, A, T> IntoIterator for &'a mut VecDeque<A, T>
+where
+    A: Alloc,
+{
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+impl<A, T> Extend<T> for VecDeque<A, T>
+where
+    A: Alloc,
+{
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+
+        for elem in iter {
+            self.push_back(elem);
+        }
+    }
+}
+
+impl<'a, A, T> Extend<&'a T> for VecDeque<A, T>
+where
+    A: Alloc,
+    T: 'a + Copy,
+{
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = &'a T>,
+    {
+        self.extend(iter.into_iter().cloned())
+    }
+}
+
+impl<A, T> FromIterator<T> for VecDeque<A, T>
+where
+    A: Alloc,
+{
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let mut deque = VecDeque::new();
+        deque.extend(iter);
+        deque
+    }
+}
+
+impl<A, T> Clone for VecDeque<A, T>
+where
+    A: Alloc,
+    T: Clone,
+{
+    fn clone(&self) -> Self {
+        let mut deque = VecDeque::with_capacity(self.len);
+        deque.extend(self.iter().cloned());
+        deque
+    }
+}
+
+impl<A, T> Default for VecDeque<A, T>
+where
+    A: Alloc,
+{
+    fn default() -> Self {
+        VecDeque::new()
+    }
+}
+
+impl<A, T> fmt::Debug for VecDeque<A, T>
+where
+    A: Alloc,
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<A, T> Hash for VecDeque<A, T>
+where
+    A: Alloc,
+    T: Hash,
+{
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: Hasher,
+    {
+        self.len.hash(state);
+        for elem in self {
+            elem.hash(state);
+        }
+    }
+}
+
+impl<A, T> Eq for VecDeque<A, T>
+where
+    A: Alloc,
+    T: Eq,
+{
+}
+
+impl<A, B, T> PartialEq<VecDeque<B, T>> for VecDeque<A, T>
+where
+    A: Alloc,
+    B: Alloc,
+    T: PartialEq,
+{
+    fn eq(&self, other: &VecDeque<B, T>) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl<A, T> Ord for VecDeque<A, T>
+where
+    A: Alloc,
+    T: Ord,
+{
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<A, B, T> PartialOrd<VecDeque<B, T>> for VecDeque<A, T>
+where
+    A: Alloc,
+    B: Alloc,
+    T: PartialOrd,
+{
+    fn partial_cmp(&self, other: &VecDeque<B, T>) -> Option<cmp::Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
@@ -9,25 +9,57 @@ use proc_macro::TokenStream;
 
 use proc_macro2::Span;
 use quote::quote;
-use syn::{parse, parse_macro_input, FnArg, ItemFn, ItemStatic, ReturnType, Type};
+use syn::{
+    parse::{self, Parse, ParseStream},
+    parse_macro_input, FnArg, Ident, ItemFn, ItemStatic, LitStr, Path, ReturnType, Token, Type,
+};
 
 /// Creates a singleton allocator from a static that implements `GlobalAlloc`
+///
+/// The attribute accepts these optional, comma separated arguments:
+///
+/// - `oom = handler`: `handler` is called when this allocator runs out of memory instead of the
+///   `#[oom]` handler. Like the `#[oom]` handler it must have signature
+///   `fn(core::alloc::Layout) -> !` or `fn(core::alloc::Layout, &alloc_many::AllocInfo) -> !`.
+///
+/// - `stats`: wraps the allocator in `alloc_many::stats::Stats`. Not available on ARMv6-M.
+///
+/// - `link_section = ".section"`: places the static in the given linker section.
 #[proc_macro_attribute]
 pub fn allocator(args: TokenStream, input: TokenStream) -> TokenStream {
-    if !args.is_empty() {
-        return parse::Error::new(Span::call_site(), "`#[allocator]` takes no arguments")
-            .to_compile_error()
-            .into();
-    }
-
+    let args = parse_macro_input!(args as Args);
     let item = parse_macro_input!(input as ItemStatic);
 
     let attrs = &item.attrs;
-    let expr = &item.expr;
     let ident = &item.ident;
-    let ty = &item.ty;
     let vis = &item.vis;
+    let (ty, expr) = {
+        let ty = &item.ty;
+        let expr = &item.expr;
+
+        if args.stats {
+            (
+                quote!(alloc_many::stats::Stats<#ty>),
+                quote!(alloc_many::stats::Stats::new(#expr)),
+            )
+        } else {
+            (quote!(#ty), quote!(#expr))
+        }
+    };
+    let link_section = args
+        .link_section
+        .map(|section| quote!(#[link_section = #section]));
+    let oom = args.oom.map(|handler| {
+        quote!(
+            #[inline(always)]
+            fn oom(layout: core::alloc::Layout) -> ! {
+                alloc_many::__private::oom(#handler, layout, &<Self as alloc_many::Alloc>::info())
+            }
+        )
+    });
+    let name = ident.to_string();
     quote!(
+        #[derive(Default)]
         #vis struct #ident;
 
         impl core::ops::Deref for #ident {
@@ -35,6 +67,7 @@ pub fn allocator(args: TokenStream, input: TokenStream) -> TokenStream {
 
             fn deref(&self) -> &#ty {
                 #(#attrs)*
+                #link_section
                 static #ident: #ty = #expr;
 
                 &#ident
@@ -43,30 +76,109 @@ pub fn allocator(args: TokenStream, input: TokenStream) -> TokenStream {
 
         unsafe impl alloc_many::Alloc for #ident {
             #[inline(always)]
-            unsafe fn alloc(layout: Layout) -> *mut u8 {
+            unsafe fn alloc(layout: core::alloc::Layout) -> *mut u8 {
                 <#ty as core::alloc::GlobalAlloc>::alloc(&#ident, layout)
             }
 
             #[inline(always)]
-            unsafe fn dealloc(ptr: *mut u8, layout: Layout) {
+            unsafe fn dealloc(ptr: *mut u8, layout: core::alloc::Layout) {
                 <#ty as core::alloc::GlobalAlloc>::dealloc(&#ident, ptr, layout)
             }
 
             #[inline(always)]
-            unsafe fn alloc_zeroed(layout: Layout) -> *mut u8 {
+            unsafe fn alloc_zeroed(layout: core::alloc::Layout) -> *mut u8 {
                 <#ty as core::alloc::GlobalAlloc>::alloc_zeroed(&#ident, layout)
             }
 
             #[inline(always)]
-            unsafe fn realloc(ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            unsafe fn realloc(
+                ptr: *mut u8,
+                layout: core::alloc::Layout,
+                new_size: usize,
+            ) -> *mut u8 {
                 <#ty as core::alloc::GlobalAlloc>::realloc(&#ident, ptr, layout, new_size)
             }
+
+            #oom
+
+            #[inline(always)]
+            fn info() -> alloc_many::AllocInfo {
+                #[allow(unused_imports)]
+                use alloc_many::__private::{NoUsageProbe, Probe, UsageProbe};
+
+                let (capacity, used) = (&Probe::<#ty>(&#ident)).usage();
+
+                alloc_many::AllocInfo {
+                    name: #name,
+                    capacity,
+                    used,
+                }
+            }
         }
     )
     .into()
 }
 
+// arguments of `#[allocator]`
+struct Args {
+    oom: Option<Path>,
+    stats: bool,
+    link_section: Option<LitStr>,
+}
+
+impl Parse for Args {
+    fn parse(input: ParseStream<'_>) -> parse::Result<Self> {
+        let mut args = Args {
+            oom: None,
+            stats: false,
+            link_section: None,
+        };
+
+        while !input.is_empty() {
+            let ident: Ident = input.parse()?;
+            let duplicate = match &*ident.to_string() {
+                "oom" => {
+                    input.parse::<Token![=]>()?;
+                    args.oom.replace(input.parse()?).is_some()
+                }
+
+                "stats" => {
+                    let duplicate = args.stats;
+                    args.stats = true;
+                    duplicate
+                }
+
+                "link_section" => {
+                    input.parse::<Token![=]>()?;
+                    args.link_section.replace(input.parse()?).is_some()
+                }
+
+                _ => {
+                    return Err(parse::Error::new(
+                        ident.span(),
+                        "expected `oom = <path>`, `stats` or `link_section = \"<section>\"`",
+                    ));
+                }
+            };
+
+            if duplicate {
+                return Err(parse::Error::new(ident.span(), "duplicate argument"));
+            }
+
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+
+        Ok(args)
+    }
+}
+
 /// Defines the OOM (Out Of Memory) handler
+///
+/// The handler must have signature `fn(core::alloc::Layout) -> !` or
+/// `fn(core::alloc::Layout, &alloc_many::AllocInfo) -> !`. The second argument identifies the
+/// allocator that ran out of memory.
 #[proc_macro_attribute]
 pub fn oom(args: TokenStream, input: TokenStream) -> TokenStream {
     if !args.is_empty() {
@@ -77,38 +189,48 @@ pub fn oom(args: TokenStream, input: TokenStream) -> TokenStream {
 
     let item = parse_macro_input!(input as ItemFn);
 
-    let arg = item.decl.inputs.iter().next().and_then(|arg| {
-        if let FnArg::Captured(arg) = arg {
-            Some(arg)
-        } else {
-            None
-        }
-    });
+    let args = item
+        .decl
+        .inputs
+        .iter()
+        .filter_map(|arg| {
+            if let FnArg::Captured(arg) = arg {
+                Some(arg)
+            } else {
+                None
+            }
+        })
+        .collect::<Vec<_>>();
     if item.constness.is_some()
         || item.asyncness.is_some()
         || item.abi.is_some()
         || !item.decl.generics.params.is_empty()
         || item.decl.generics.where_clause.is_some()
-        || item.decl.inputs.len() != 1
-        || arg.is_none()
+        || (args.len() != 1 && args.len() != 2)
+        || args.len() != item.decl.inputs.len()
         || item.decl.variadic.is_some()
         || !is_bottom(&item.decl.output)
     {
         return parse::Error::new(
             Span::call_site(),
-            "`#[oom]` must have signature `fn(core::alloc::Layout) -> !`",
+            "`#[oom]` must have signature `fn(core::alloc::Layout) -> !` or \
+             `fn(core::alloc::Layout, &alloc_many::AllocInfo) -> !`",
         )
         .to_compile_error()
         .into();
     }
 
-    let arg = arg.expect("UNREACHABLE");
+    let layout = args[0];
+    let info = args
+        .get(1)
+        .map(|info| quote!(#info))
+        .unwrap_or_else(|| quote!(_: &alloc_many::AllocInfo));
     let ident = &item.ident;
     let block = &item.block;
     quote!(
         #[export_name = "alloc_many_oom"]
-        fn #ident(#arg) -> ! {
-            let _: fn(core::alloc::Layout) -> ! = #ident;
+        fn #ident(#layout, #info) -> ! {
+            let _: fn(core::alloc::Layout, &alloc_many::AllocInfo) -> ! = #ident;
 
             #block
         }
@@ -0,0 +1,252 @@
+//! Allocators built out of other allocators
+//!
+//! The combinators implement `GlobalAlloc` so they can be bound to a singleton with
+//! `#[allocator]`, just like the allocators they are made of.
+//!
+//! ``` ignore
+//! use alloc_many::{allocator, combinators::{Fallback, Segregator}};
+//! use alloc_many_bump::{consts, BumpAlloc};
+//!
+//! type Small = BumpAlloc<consts::U256>;
+//! type Large = Fallback<BumpAlloc<consts::U1024>, BumpAlloc<consts::U4096>>;
+//!
+//! // requests of up to 16 bytes are served by `Small`; larger requests are served by the first
+//! // bump allocator of `Large` until it runs out of memory and then by the second one
+//! #[allocator]
+//! static A: Segregator<Small, Large> =
+//!     Segregator::new(16, BumpAlloc::new(), Fallback::new(BumpAlloc::new(), BumpAlloc::new()));
+//! ```
+
+use core::{
+    alloc::{GlobalAlloc, Layout},
+    cmp, ptr,
+};
+
+use crate::{Owns, Usage};
+
+/// An allocator that allocates on `P` and falls back to `S` when `P` is out of memory
+///
+/// `dealloc` and `realloc` are routed to the allocator that owns the block, which is why `P` must
+/// implement `Owns`.
+pub struct Fallback<P, S> {
+    primary: P,
+    secondary: S,
+}
+
+impl<P, S> Fallback<P, S> {
+    /// Creates a new `Fallback` allocator
+    pub const fn new(primary: P, secondary: S) -> Self {
+        Fallback { primary, secondary }
+    }
+
+    /// Returns a reference to the primary allocator
+    pub fn primary(&self) -> &P {
+        &self.primary
+    }
+
+    /// Returns a reference to the secondary allocator
+    pub fn secondary(&self) -> &S {
+        &self.secondary
+    }
+}
+
+unsafe impl<P, S> GlobalAlloc for Fallback<P, S>
+where
+    P: Owns,
+    S: GlobalAlloc,
+{
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = self.primary.alloc(layout);
+
+        if ptr.is_null() {
+            self.secondary.alloc(layout)
+        } else {
+            ptr
+        }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        if self.primary.owns(ptr, layout) {
+            self.primary.dealloc(ptr, layout)
+        } else {
+            self.secondary.dealloc(ptr, layout)
+        }
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = self.primary.alloc_zeroed(layout);
+
+        if ptr.is_null() {
+            self.secondary.alloc_zeroed(layout)
+        } else {
+            ptr
+        }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        if self.primary.owns(ptr, layout) {
+            let new_ptr = self.primary.realloc(ptr, layout, new_size);
+
+            if new_ptr.is_null() {
+                // the primary allocator is out of memory; move the block to the secondary one
+                move_block(&self.primary, &self.secondary, ptr, layout, new_size)
+            } else {
+                new_ptr
+            }
+        } else {
+            self.secondary.realloc(ptr, layout, new_size)
+        }
+    }
+}
+
+unsafe impl<P, S> Owns for Fallback<P, S>
+where
+    P: Owns,
+    S: Owns,
+{
+    fn owns(&self, ptr: *mut u8, layout: Layout) -> bool {
+        self.primary.owns(ptr, layout) || self.secondary.owns(ptr, layout)
+    }
+}
+
+impl<P, S> Usage for Fallback<P, S>
+where
+    P: Owns + Usage,
+    S: Usage,
+{
+    fn capacity(&self) -> usize {
+        self.primary.capacity() + self.secondary.capacity()
+    }
+
+    fn used(&self) -> usize {
+        self.primary.used() + self.secondary.used()
+    }
+}
+
+/// An allocator that serves requests of up to `threshold` bytes from `S` and larger requests from
+/// `L`
+pub struct Segregator<S, L> {
+    threshold: usize,
+    small: S,
+    large: L,
+}
+
+impl<S, L> Segregator<S, L> {
+    /// Creates a new `Segregator` allocator
+    pub const fn new(threshold: usize, small: S, large: L) -> Self {
+        Segregator {
+            threshold,
+            small,
+            large,
+        }
+    }
+
+    /// Returns the size, in bytes, of the largest request served by the small allocator
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    /// Returns a reference to the allocator used for small requests
+    pub fn small(&self) -> &S {
+        &self.small
+    }
+
+    /// Returns a reference to the allocator used for large requests
+    pub fn large(&self) -> &L {
+        &self.large
+    }
+
+    fn is_small(&self, size: usize) -> bool {
+        size <= self.threshold
+    }
+}
+
+unsafe impl<S, L> GlobalAlloc for Segregator<S, L>
+where
+    S: GlobalAlloc,
+    L: GlobalAlloc,
+{
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if self.is_small(layout.size()) {
+            self.small.alloc(layout)
+        } else {
+            self.large.alloc(layout)
+        }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        if self.is_small(layout.size()) {
+            self.small.dealloc(ptr, layout)
+        } else {
+            self.large.dealloc(ptr, layout)
+        }
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        if self.is_small(layout.size()) {
+            self.small.alloc_zeroed(layout)
+        } else {
+            self.large.alloc_zeroed(layout)
+        }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        match (self.is_small(layout.size()), self.is_small(new_size)) {
+            (true, true) => self.small.realloc(ptr, layout, new_size),
+            (false, false) => self.large.realloc(ptr, layout, new_size),
+            (true, false) => move_block(&self.small, &self.large, ptr, layout, new_size),
+            (false, true) => move_block(&self.large, &self.small, ptr, layout, new_size),
+        }
+    }
+}
+
+unsafe impl<S, L> Owns for Segregator<S, L>
+where
+    S: Owns,
+    L: Owns,
+{
+    fn owns(&self, ptr: *mut u8, layout: Layout) -> bool {
+        if self.is_small(layout.size()) {
+            self.small.owns(ptr, layout)
+        } else {
+            self.large.owns(ptr, layout)
+        }
+    }
+}
+
+// moves a block allocated on `from` into a new block of `new_size` bytes allocated on `to`
+unsafe fn move_block<F, T>(
+    from: &F,
+    to: &T,
+    ptr: *mut u8,
+    layout: Layout,
+    new_size: usize,
+) -> *mut u8
+where
+    F: GlobalAlloc,
+    T: GlobalAlloc,
+{
+    let new_layout = Layout::from_size_align_unchecked(new_size, layout.align());
+    let new_ptr = to.alloc(new_layout);
+
+    if !new_ptr.is_null() {
+        ptr::copy_nonoverlapping(ptr, new_ptr, cmp::min(layout.size(), new_size));
+        from.dealloc(ptr, layout);
+    }
+
+    new_ptr
+}
+
+impl<S, L> Usage for Segregator<S, L>
+where
+    S: Usage,
+    L: Usage,
+{
+    fn capacity(&self) -> usize {
+        self.small.capacity() + self.large.capacity()
+    }
+
+    fn used(&self) -> usize {
+        self.small.used() + self.large.used()
+    }
+}
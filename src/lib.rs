@@ -21,8 +21,11 @@
 //! - Doesn't integrate with the `alloc` crate. Meaning that we need to re-create that crate from
 //! scratch.
 //!
-//! - Dynamically Sized Types (e.g. `Box<[u8]>` and `Box<dyn Fn()>`) are not supported because
-//! [`CoerceUnsized`] and [`Unsize`] are unstable APIs.
+//! - Implicit unsized coercions (e.g. `Box<A, T>` to `Box<A, dyn Fn()>`) require the `nightly`
+//!   feature of the collections crate because [`CoerceUnsized`] and [`Unsize`] are unstable APIs.
+//!   On stable, use the `unsize_box!` macro to create trait objects. Boxed slices and string slices
+//!   (`Box<A, [T]>` and `Box<A, str>`) are supported; they are created with
+//!   `Vec::into_boxed_slice`, `Box::from_slice`, `Box::from_str`, etc.
 //!
 //! [`CoerceUnsized`]: https://doc.rust-lang.org/core/ops/trait.CoerceUnsized.html
 //! [`Unsize`]: https://doc.rust-lang.org/core/marker/trait.Unsize.html
@@ -36,7 +39,7 @@
 //! use core::alloc::Layout;
 //!
 //! use alloc_many::{alloc, oom};
-//! use alloc_many_bump::{consts, BumpAlloc}; // NOTE: MSRV = 1.36
+//! use alloc_many_bump::{consts, BumpAlloc}; // NOTE: MSRV = 1.61
 //! use alloc_many_collections::Box; // instead of the (still) unstable `alloc` crate
 //! use cortex_m_rt::entry;
 //! use panic_halt as _; // panic handler
@@ -53,7 +56,7 @@
 //!     loop {}
 //! }
 //!
-//! // called when any allocator signals OOM
+//! // called when an allocator without its own `oom` handler signals OOM
 //! #[oom] // instead of the reserved, unstable `#[alloc_error_handler]`
 //! fn oom(_: Layout) -> ! {
 //!     loop {}
@@ -65,10 +68,50 @@
 //!
 //! [tlsf]: https://github.com/japaric/tlsf
 //!
+//! # Out Of Memory (OOM)
+//!
+//! When an allocator can't satisfy a request made through an infallible API, like `Box::new` or
+//! `Vec::push`, the collection calls the allocator's `Alloc::oom` function. By default that
+//! function forwards to the `#[oom]` handler, which is shared by all allocators, but each
+//! allocator can have its own handler:
+//!
+//! ``` ignore
+//! // pool of DMA buffers
+//! #[allocator(oom = dma_oom)]
+//! static DMA: BumpAlloc<consts::U1024> = BumpAlloc::new();
+//!
+//! fn dma_oom(layout: Layout) -> ! {
+//!     // ..
+//! }
+//! ```
+//!
+//! (`Alloc` implementations written by hand can override `Alloc::oom` instead.)
+//!
+//! OOM handlers, the `#[oom]` one and the per-allocator ones, can take a second argument to learn
+//! which allocator ran out of memory:
+//!
+//! ``` ignore
+//! #[oom]
+//! fn oom(layout: Layout, info: &AllocInfo) -> ! {
+//!     // e.g. `info.name == "DMA"`; `info.capacity` and `info.used` are only available if the
+//!     // allocator implements `Usage`
+//!     log!("{} ran out of memory ({:?} / {:?} bytes used)", info.name, info.used, info.capacity);
+//!
+//!     loop {}
+//! }
+//! ```
+//!
+//! OOM handlers never return so they can't make the failed request succeed. To recover from OOM,
+//! e.g. by recycling buffers and trying again, use the fallible APIs like `Box::try_new` and
+//! `Vec::try_reserve`. Note that capacity overflows are not OOM errors; they result in a panic.
+//!
 //! # Minimum Supported Rust Version (MSRV)
 //!
 //! This crate is guaranteed to compile on stable Rust 1.32 and up. It might compile on older
 //! versions but that may change in any new patch release.
+//!
+//! The other crates in this repository have their own MSRV: 1.36 for `alloc-many-collections`
+//! and 1.61 for `alloc-many-bump`.
 
 #![cfg_attr(not(test), no_std)]
 #![deny(missing_docs)]
@@ -80,10 +123,19 @@
 #[cfg(test)]
 extern crate self as alloc_many;
 
-use core::alloc::Layout;
+use core::alloc::{GlobalAlloc, Layout};
 
 pub use alloc_many_macros::{allocator, oom};
 
+pub mod combinators;
+#[cfg(not(armv6m))]
+pub mod stats;
+
+#[allow(improper_ctypes)]
+extern "Rust" {
+    fn alloc_many_oom(layout: Layout, info: &AllocInfo) -> !;
+}
+
 /// Singleton version of [`core::alloc::GlobalAlloc`][0]
 ///
 /// [0]: https://doc.rust-lang.org/core/alloc/trait.GlobalAlloc.html
@@ -117,4 +169,145 @@ pub unsafe trait Alloc {
     ///
     /// [0]: https://doc.rust-lang.org/core/alloc/trait.GlobalAlloc.html#tymethod.realloc
     unsafe fn realloc(ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8;
+
+    /// Signals that an allocation request made on this allocator could not be satisfied
+    ///
+    /// The infallible methods of the collections (e.g. `Box::new`) call this function when the
+    /// allocator returns a null pointer. The default implementation calls the `#[oom]` handler
+    /// with `Self::info()`; use `#[allocator(oom = handler)]` to give an allocator its own
+    /// handler.
+    fn oom(layout: Layout) -> ! {
+        unsafe { alloc_many_oom(layout, &Self::info()) }
+    }
+
+    /// Returns information about this allocator
+    ///
+    /// `#[allocator]` implements this function; the default implementation returns an unnamed
+    /// `AllocInfo` with no usage information.
+    fn info() -> AllocInfo {
+        AllocInfo {
+            name: "",
+            capacity: None,
+            used: None,
+        }
+    }
+}
+
+/// Information about an allocator, passed to the `#[oom]` handler
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct AllocInfo {
+    /// Name of the allocator; the identifier of the static annotated with `#[allocator]`
+    pub name: &'static str,
+
+    /// Capacity of the allocator in bytes, if the allocator implements `Usage`
+    pub capacity: Option<usize>,
+
+    /// Bytes in use, if the allocator implements `Usage`
+    pub used: Option<usize>,
+}
+
+/// Allocators that can tell whether they allocated a block of memory
+///
+/// This is what makes composing allocators possible; see the [`combinators`](combinators/index.html)
+/// module. Once an allocator is bound to a singleton with `#[allocator]` the query is available
+/// on the singleton through `Deref`, e.g. `A.owns(ptr, layout)`.
+///
+/// # Safety
+///
+/// `owns` must return `true` for every block currently allocated by this allocator and `false`
+/// for blocks allocated by any other allocator.
+pub unsafe trait Owns: GlobalAlloc {
+    /// Returns `true` if the block of memory described by `ptr` and `layout` was allocated by this
+    /// allocator
+    fn owns(&self, ptr: *mut u8, layout: Layout) -> bool;
+}
+
+/// Allocators that know how much memory they manage
+///
+/// `#[allocator]` includes this information in the `AllocInfo` passed to the `#[oom]` handler.
+pub trait Usage: GlobalAlloc {
+    /// Returns the number of bytes managed by this allocator
+    fn capacity(&self) -> usize;
+
+    /// Returns the number of bytes currently in use
+    fn used(&self) -> usize;
+}
+
+// Implementation details of the procedural macros. NOT part of the public API
+#[doc(hidden)]
+pub mod __private {
+    use core::alloc::Layout;
+
+    use crate::{AllocInfo, Usage};
+
+    // NOTE "autoref specialization": method resolution prefers `UsageProbe::usage`, which takes
+    // `&Probe`, over `NoUsageProbe::usage`, which takes `&&Probe`, when `T: Usage` holds
+    pub struct Probe<'a, T>(pub &'a T);
+
+    pub trait UsageProbe {
+        fn usage(&self) -> (Option<usize>, Option<usize>);
+    }
+
+    impl<'a, T> UsageProbe for Probe<'a, T>
+    where
+        T: Usage,
+    {
+        fn usage(&self) -> (Option<usize>, Option<usize>) {
+            (Some(self.0.capacity()), Some(self.0.used()))
+        }
+    }
+
+    pub trait NoUsageProbe {
+        fn usage(&self) -> (Option<usize>, Option<usize>);
+    }
+
+    impl<'a, 'b, T> NoUsageProbe for &'b Probe<'a, T> {
+        fn usage(&self) -> (Option<usize>, Option<usize>) {
+            (None, None)
+        }
+    }
+
+    // NOTE `!` can't be written in trait bounds on stable but it can be named through the return
+    // type of a function pointer
+    pub trait Output {
+        type Output;
+    }
+
+    impl<T> Output for fn() -> T {
+        type Output = T;
+    }
+
+    pub type Never = <fn() -> ! as Output>::Output;
+
+    // NOTE the type parameter `M` tells the two signatures apart so that the handler passed to
+    // `#[allocator(oom = ..)]` can take the `AllocInfo` argument or not
+    pub trait OomHandler<M> {
+        fn call(self, layout: Layout, info: &AllocInfo) -> !;
+    }
+
+    impl<F> OomHandler<(Layout,)> for F
+    where
+        F: FnOnce(Layout) -> Never,
+    {
+        fn call(self, layout: Layout, _: &AllocInfo) -> ! {
+            self(layout)
+        }
+    }
+
+    impl<F> OomHandler<(Layout, &'static AllocInfo)> for F
+    where
+        F: for<'a> FnOnce(Layout, &'a AllocInfo) -> Never,
+    {
+        fn call(self, layout: Layout, info: &AllocInfo) -> ! {
+            self(layout, info)
+        }
+    }
+
+    #[inline(always)]
+    pub fn oom<H, M>(handler: H, layout: Layout, info: &AllocInfo) -> !
+    where
+        H: OomHandler<M>,
+    {
+        handler.call(layout, info)
+    }
 }
@@ -0,0 +1,182 @@
+//! Allocator statistics
+//!
+//! ``` ignore
+//! use alloc_many::{allocator, stats::Stats};
+//! use alloc_many_bump::{consts, BumpAlloc};
+//!
+//! #[allocator]
+//! static A: Stats<BumpAlloc<consts::U1024>> = Stats::new(BumpAlloc::new());
+//!
+//! let snapshot = A.snapshot();
+//! report(snapshot.live, snapshot.peak);
+//! ```
+
+use core::{
+    alloc::{GlobalAlloc, Layout},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use crate::{Owns, Usage};
+
+/// An allocator that keeps track of the memory allocated on `A`
+///
+/// All the counters are updated with `Relaxed` atomic operations so the wrapper is as lock-free as
+/// `A` is.
+pub struct Stats<A> {
+    inner: A,
+    live: AtomicUsize,
+    peak: AtomicUsize,
+    allocations: AtomicUsize,
+    deallocations: AtomicUsize,
+    reallocations: AtomicUsize,
+    failures: AtomicUsize,
+}
+
+/// A copy of the counters of a `Stats` allocator
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Snapshot {
+    /// Bytes currently allocated
+    pub live: usize,
+
+    /// Largest value `live` has reached
+    pub peak: usize,
+
+    /// Number of successful `alloc` and `alloc_zeroed` calls
+    pub allocations: usize,
+
+    /// Number of `dealloc` calls
+    pub deallocations: usize,
+
+    /// Number of successful `realloc` calls
+    pub reallocations: usize,
+
+    /// Number of `alloc`, `alloc_zeroed` and `realloc` calls that returned a null pointer
+    pub failures: usize,
+}
+
+impl<A> Stats<A> {
+    /// Wraps the allocator `inner`
+    pub const fn new(inner: A) -> Self {
+        Stats {
+            inner,
+            live: AtomicUsize::new(0),
+            peak: AtomicUsize::new(0),
+            allocations: AtomicUsize::new(0),
+            deallocations: AtomicUsize::new(0),
+            reallocations: AtomicUsize::new(0),
+            failures: AtomicUsize::new(0),
+        }
+    }
+
+    /// Returns a reference to the wrapped allocator
+    pub fn inner(&self) -> &A {
+        &self.inner
+    }
+
+    /// Returns a copy of the counters
+    ///
+    /// NOTE the counters are read one at a time so the snapshot may be inconsistent (e.g.
+    /// `allocations - deallocations` may not match `live`) if other threads are using the
+    /// allocator
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            live: self.live.load(Ordering::Relaxed),
+            peak: self.peak.load(Ordering::Relaxed),
+            allocations: self.allocations.load(Ordering::Relaxed),
+            deallocations: self.deallocations.load(Ordering::Relaxed),
+            reallocations: self.reallocations.load(Ordering::Relaxed),
+            failures: self.failures.load(Ordering::Relaxed),
+        }
+    }
+
+    fn grow(&self, bytes: usize) {
+        let live = self.live.fetch_add(bytes, Ordering::Relaxed) + bytes;
+
+        // NOTE `AtomicUsize::fetch_max` is not available on our MSRV
+        let mut peak = self.peak.load(Ordering::Relaxed);
+        while live > peak {
+            match self
+                .peak
+                .compare_exchange_weak(peak, live, Ordering::Relaxed, Ordering::Relaxed)
+            {
+                Ok(_) => break,
+                Err(current) => peak = current,
+            }
+        }
+    }
+
+    fn shrink(&self, bytes: usize) {
+        self.live.fetch_sub(bytes, Ordering::Relaxed);
+    }
+
+    fn on_alloc(&self, ptr: *mut u8, layout: Layout) -> *mut u8 {
+        if ptr.is_null() {
+            self.failures.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.allocations.fetch_add(1, Ordering::Relaxed);
+            self.grow(layout.size());
+        }
+
+        ptr
+    }
+}
+
+unsafe impl<A> GlobalAlloc for Stats<A>
+where
+    A: GlobalAlloc,
+{
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        self.on_alloc(self.inner.alloc(layout), layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        self.inner.dealloc(ptr, layout);
+
+        self.deallocations.fetch_add(1, Ordering::Relaxed);
+        self.shrink(layout.size());
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        self.on_alloc(self.inner.alloc_zeroed(layout), layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = self.inner.realloc(ptr, layout, new_size);
+
+        if new_ptr.is_null() {
+            self.failures.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.reallocations.fetch_add(1, Ordering::Relaxed);
+
+            if new_size > layout.size() {
+                self.grow(new_size - layout.size());
+            } else {
+                self.shrink(layout.size() - new_size);
+            }
+        }
+
+        new_ptr
+    }
+}
+
+unsafe impl<A> Owns for Stats<A>
+where
+    A: Owns,
+{
+    fn owns(&self, ptr: *mut u8, layout: Layout) -> bool {
+        self.inner.owns(ptr, layout)
+    }
+}
+
+impl<A> Usage for Stats<A>
+where
+    A: Usage,
+{
+    fn capacity(&self) -> usize {
+        self.inner.capacity()
+    }
+
+    fn used(&self) -> usize {
+        self.inner.used()
+    }
+}